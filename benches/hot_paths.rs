@@ -0,0 +1,117 @@
+use chess_engine::bench::BENCH_POSITIONS;
+use chess_engine::chess_game::ChessGame;
+use chess_engine::color::Color;
+use chess_engine::runtime_calculated_constants::Constants;
+use chess_engine::square::Square;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+// An opening, a middlegame full of castling, en passant and promotions, and an endgame. All from the `bench` positions.
+const POSITIONS: [(&str, &str); 3] = [
+    ("start", BENCH_POSITIONS[0]),
+    ("kiwipete", BENCH_POSITIONS[1]),
+    ("endgame", BENCH_POSITIONS[2]),
+];
+
+// Depth for the `minimax` benchmarks. Deep enough to go through move ordering and the transposition table.
+const MINIMAX_DEPTH: u32 = 3;
+
+fn game_at(fen: &str) -> ChessGame {
+    let mut game = ChessGame::new();
+    game.import_fen(fen)
+        .expect("Benchmark positions are valid.");
+    game.set_legal_moves(None);
+    return game;
+}
+
+fn bench_get_legal_moves(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_legal_moves");
+    for (name, fen) in POSITIONS {
+        let mut game = game_at(fen);
+        group.bench_function(name, |b| b.iter(|| black_box(game.get_legal_moves())));
+    }
+    group.finish();
+}
+
+// Every legal move in the position, made and unmade once.
+fn bench_make_unmake_move(c: &mut Criterion) {
+    let mut group = c.benchmark_group("make_unmake_move");
+    for (name, fen) in POSITIONS {
+        let mut game = game_at(fen);
+        let moves = game.legal_moves.clone();
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for this_move in moves.iter() {
+                    game.make_move(black_box(this_move), false);
+                    game.unmake_move(this_move);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_evaluate_board(c: &mut Criterion) {
+    let mut group = c.benchmark_group("evaluate_board");
+    for (name, fen) in POSITIONS {
+        let game = game_at(fen);
+        group.bench_function(name, |b| b.iter(|| black_box(game.evaluate_board())));
+    }
+    group.finish();
+}
+
+// Every square, attacked by either side.
+fn bench_is_square_attacked(c: &mut Criterion) {
+    let mut group = c.benchmark_group("is_square_attacked");
+    for (name, fen) in POSITIONS {
+        let game = game_at(fen);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut attacked = 0;
+                for square in 0..64 {
+                    for side in [Color::White, Color::Black] {
+                        if game.is_square_attacked(Square::new(square), &side) {
+                            attacked += 1;
+                        }
+                    }
+                }
+                return black_box(attacked);
+            })
+        });
+    }
+    group.finish();
+}
+
+// Building the magic bitboard and zobrist tables. Games share one copy, so this is paid once per process.
+fn bench_constants_new(c: &mut Criterion) {
+    let mut group = c.benchmark_group("constants");
+    group.sample_size(10);
+    group.bench_function("new", |b| b.iter(|| black_box(Constants::new())));
+    group.finish();
+}
+
+// A fixed depth search from an empty transposition table, so every iteration does the same work.
+fn bench_minimax(c: &mut Criterion) {
+    let mut group = c.benchmark_group("minimax");
+    group.sample_size(10);
+    for (name, fen) in POSITIONS {
+        let mut game = game_at(fen);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                game.transposition_table.clear();
+                return black_box(game.minimax(MINIMAX_DEPTH, i64::MIN, i64::MAX));
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_get_legal_moves,
+    bench_make_unmake_move,
+    bench_evaluate_board,
+    bench_is_square_attacked,
+    bench_constants_new,
+    bench_minimax
+);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+use crate::chess_game::ChessGame;
+use crate::search_limits::SearchLimits;
+
+// Depth the `bench` command searches to when not given one.
+pub const DEFAULT_BENCH_DEPTH: u32 = 4;
+
+// Openings, middlegames and endgames. Changing this list changes the bench signature.
+pub const BENCH_POSITIONS: [&str; 12] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    "r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4",
+    "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1",
+    "r1b2rk1/2q1b1pp/p2ppn2/1p6/3QP3/1BN1B3/PPP3PP/R4RK1 w - - 0 1",
+    "6k1/5ppp/8/8/8/8/5PPP/3R2K1 w - - 0 1",
+    "8/8/4k3/8/2p5/8/B2P4/4K3 w - - 0 1",
+    "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+];
+
+// One bench position. `time_ms` is the only part that changes from run to run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchResult {
+    pub fen: String,
+    pub nodes: u64,
+    pub time_ms: u128,
+}
+
+/**
+ * Searches every `BENCH_POSITIONS` entry to `depth`, each from an empty transposition table.
+ * The search has no other state and the zobrist keys are seeded, so the node total only changes when the search or
+ * evaluation does. That makes it a quick signature for spotting changes in behaviour.
+ */
+pub fn run_bench(depth: u32) -> Vec<BenchResult> {
+    let mut game = ChessGame::new();
+    let limits = SearchLimits::depth(depth);
+    let mut results = vec![];
+
+    for fen in BENCH_POSITIONS {
+        game.import_fen(fen).expect("Bench positions are valid.");
+        game.set_legal_moves(None);
+        game.transposition_table.clear();
+
+        let start_time = std::time::SystemTime::now();
+        let start_nodes = game.debug_minimax_calls;
+        game.search_with_limits(&limits);
+        let time_ms = std::time::SystemTime::now()
+            .duration_since(start_time)
+            .expect("Time went back?")
+            .as_millis();
+
+        results.push(BenchResult {
+            fen: fen.to_string(),
+            nodes: game.debug_minimax_calls - start_nodes,
+            time_ms,
+        });
+    }
+
+    return results;
+}
+
+// Over a whole bench. Zero if it was too quick to time.
+pub fn nodes_per_second(nodes: u64, time_ms: u128) -> u64 {
+    if time_ms == 0 {
+        return 0;
+    }
+    return (nodes as u128 * 1000 / time_ms) as u64;
+}
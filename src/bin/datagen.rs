@@ -0,0 +1,98 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::datagen::{
+    append_packed_positions, append_text_positions, DatagenOptions, SelfPlay,
+};
+use chess_engine::search_limits::SearchLimits;
+use std::env;
+use std::process;
+
+const USAGE: &str = "Usage: datagen <output file> [--games <n>] [--depth <n> | --nodes <n>] [--random-plies <n>] [--max-plies <n>] [--seed <n>] [--binary]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut output_path: Option<String> = None;
+    let mut games: u64 = 100;
+    let mut binary = false;
+    let mut options = DatagenOptions::default();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--games" => {
+                i += 1;
+                games = parse_number(args.get(i), "--games");
+            }
+            "--depth" => {
+                i += 1;
+                options.limits = SearchLimits::depth(parse_number(args.get(i), "--depth"));
+            }
+            "--nodes" => {
+                i += 1;
+                options.limits = SearchLimits::nodes(parse_number(args.get(i), "--nodes"));
+            }
+            "--random-plies" => {
+                i += 1;
+                options.random_plies = parse_number(args.get(i), "--random-plies");
+            }
+            "--max-plies" => {
+                i += 1;
+                options.max_plies = parse_number(args.get(i), "--max-plies");
+            }
+            "--seed" => {
+                i += 1;
+                options.seed = parse_number(args.get(i), "--seed");
+            }
+            "--binary" => binary = true,
+            s => {
+                if output_path.is_none() {
+                    output_path = Some(s.to_string());
+                } else {
+                    exit_with_error(&format!("Unexpected argument `{s}`."));
+                }
+            }
+        }
+        i += 1;
+    }
+
+    let output_path = match output_path {
+        Some(o) => o,
+        None => exit_with_error("Missing output file."),
+    };
+
+    let mut self_play = SelfPlay::new(ChessGame::new(), options);
+    let mut total_positions: usize = 0;
+
+    for game_number in 0..games {
+        let positions = self_play.play_game();
+
+        let written = match binary {
+            true => append_packed_positions(&output_path, &positions),
+            false => append_text_positions(&output_path, &positions),
+        };
+        if let Err(e) = written {
+            exit_with_error(&e);
+        }
+
+        total_positions += positions.len();
+        println!(
+            "Game {}/{games}: {} positions, {total_positions} total.",
+            game_number + 1,
+            positions.len()
+        );
+    }
+
+    println!("Wrote {total_positions} positions to `{output_path}`.");
+}
+
+fn parse_number<T: std::str::FromStr>(arg: Option<&String>, flag: &str) -> T {
+    return match arg.map(|s| s.parse::<T>()) {
+        Some(Ok(n)) => n,
+        _ => exit_with_error(&format!("`{flag}` expects a positive number.")),
+    };
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    process::exit(1);
+}
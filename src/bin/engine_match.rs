@@ -0,0 +1,243 @@
+use chess_engine::engine_match::{play_game, Adjudication, MatchEngine, MatchOptions, TimeControl};
+use chess_engine::epd::EpdPosition;
+use chess_engine::evaluation_parameters::EvaluationParameters;
+use chess_engine::game_result::GameResult;
+use chess_engine::nnue::NnueNetwork;
+use chess_engine::sprt::{MatchScore, Sprt, SprtVerdict};
+use chess_engine::variant::Variant;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process;
+use std::sync::Arc;
+
+const USAGE: &str = "Usage: engine_match --openings <file> [--games <n>] [--tc <seconds+increment>] [--params-a <file>] [--params-b <file>] [--nnue-a <file>] [--nnue-b <file>] [--name-a <name>] [--name-b <name>] [--elo0 <n>] [--elo1 <n>] [--alpha <n>] [--beta <n>] [--variant <name>] [--pgn <file>] [--no-adjudication]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // Engine A is the one being tested, B the baseline.
+    let mut engines = [MatchEngine::new("A"), MatchEngine::new("B")];
+    let mut openings_path: Option<String> = None;
+    let mut pgn_path: Option<String> = None;
+    let mut games: u64 = 1_000;
+    let mut sprt = Sprt::default();
+    let mut options = MatchOptions {
+        variant: Variant::Standard,
+        time_control: TimeControl {
+            base_ms: 10_000,
+            increment_ms: 100,
+        },
+        adjudication: Adjudication::default(),
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        let flag = args[i].as_str();
+        let engine_index = match flag.ends_with("-b") {
+            true => 1,
+            false => 0,
+        };
+        match flag {
+            "--openings" => {
+                i += 1;
+                openings_path = Some(parse_string(args.get(i), flag));
+            }
+            "--pgn" => {
+                i += 1;
+                pgn_path = Some(parse_string(args.get(i), flag));
+            }
+            "--games" => {
+                i += 1;
+                games = parse_number(args.get(i), flag);
+            }
+            "--tc" => {
+                i += 1;
+                options.time_control = match TimeControl::parse(&parse_string(args.get(i), flag)) {
+                    Ok(tc) => tc,
+                    Err(e) => exit_with_error(&e),
+                };
+            }
+            "--name-a" | "--name-b" => {
+                i += 1;
+                engines[engine_index].name = parse_string(args.get(i), flag);
+            }
+            "--params-a" | "--params-b" => {
+                i += 1;
+                let path = parse_string(args.get(i), flag);
+                engines[engine_index].evaluation_parameters =
+                    match EvaluationParameters::load_from_file(&path) {
+                        Ok(p) => p,
+                        Err(e) => exit_with_error(&e),
+                    };
+            }
+            "--nnue-a" | "--nnue-b" => {
+                i += 1;
+                let path = parse_string(args.get(i), flag);
+                engines[engine_index].nnue_network = match NnueNetwork::load_from_file(&path) {
+                    Ok(n) => Some(Arc::new(n)),
+                    Err(e) => exit_with_error(&e),
+                };
+            }
+            "--elo0" => {
+                i += 1;
+                sprt.elo0 = parse_number(args.get(i), flag);
+            }
+            "--elo1" => {
+                i += 1;
+                sprt.elo1 = parse_number(args.get(i), flag);
+            }
+            "--alpha" => {
+                i += 1;
+                sprt.alpha = parse_number(args.get(i), flag);
+            }
+            "--beta" => {
+                i += 1;
+                sprt.beta = parse_number(args.get(i), flag);
+            }
+            "--variant" => {
+                i += 1;
+                options.variant = match Variant::from_pgn_name(&parse_string(args.get(i), flag)) {
+                    Ok(v) => v,
+                    Err(e) => exit_with_error(&e.to_string()),
+                };
+            }
+            "--no-adjudication" => {
+                options.adjudication.resign_plies = 0;
+                options.adjudication.draw_plies = 0;
+            }
+            s => exit_with_error(&format!("Unexpected argument `{s}`.")),
+        }
+        i += 1;
+    }
+
+    let openings = match openings_path {
+        Some(path) => load_openings(&path),
+        None => exit_with_error("Missing opening suite."),
+    };
+
+    let mut match_score = MatchScore::default();
+    println!(
+        "{} vs {}, SPRT elo0 {} elo1 {} alpha {} beta {}. LLR bounds [{:.2}, {:.2}].",
+        engines[0].name,
+        engines[1].name,
+        sprt.elo0,
+        sprt.elo1,
+        sprt.alpha,
+        sprt.beta,
+        sprt.lower_bound(),
+        sprt.upper_bound()
+    );
+
+    for game_number in 0..games {
+        // Each opening is played twice, with the engines swapping colors.
+        let opening = &openings[(game_number / 2) as usize % openings.len()];
+        let a_is_white = game_number % 2 == 0;
+        let (white, black) = match a_is_white {
+            true => (&engines[0], &engines[1]),
+            false => (&engines[1], &engines[0]),
+        };
+
+        let game = match play_game(white, black, opening, &options) {
+            Ok(g) => g,
+            Err(e) => exit_with_error(&format!("Unable to play opening `{opening}`. {e}")),
+        };
+
+        match (game.result, a_is_white) {
+            (GameResult::WhiteWins, true) | (GameResult::BlackWins, false) => match_score.wins += 1,
+            (GameResult::WhiteWins, false) | (GameResult::BlackWins, true) => {
+                match_score.losses += 1
+            }
+            _ => match_score.draws += 1,
+        }
+
+        if let Some(path) = &pgn_path {
+            if let Err(e) = append_to_file(path, &game.pgn.to_string()) {
+                exit_with_error(&e);
+            }
+        }
+
+        println!(
+            "Game {}: {} vs {} {} ({}). {} - {} - {}, Elo {:.1} +/- {:.1}, LLR {:.2}",
+            game_number + 1,
+            white.name,
+            black.name,
+            game.pgn.tag("Result").unwrap_or("*"),
+            game.termination,
+            match_score.wins,
+            match_score.losses,
+            match_score.draws,
+            match_score.elo(),
+            match_score.elo_error(),
+            sprt.llr(&match_score)
+        );
+
+        match sprt.verdict(&match_score) {
+            SprtVerdict::Continue => {}
+            SprtVerdict::AcceptH0 => {
+                println!(
+                    "H0 accepted: {} isn't {} Elo stronger.",
+                    engines[0].name, sprt.elo1
+                );
+                return;
+            }
+            SprtVerdict::AcceptH1 => {
+                println!("H1 accepted: {} is stronger.", engines[0].name);
+                return;
+            }
+        }
+    }
+
+    println!("Out of games before SPRT finished.");
+}
+
+// One opening per line, as a FEN or an EPD. Blank lines and `#` comments are skipped.
+fn load_openings(path: &str) -> Vec<String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => exit_with_error(&format!("Unable to read openings `{path}`. Error: {e}")),
+    };
+
+    let mut openings = vec![];
+    for (line_index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        match EpdPosition::parse(line) {
+            Ok(position) => openings.push(position.fen),
+            Err(e) => exit_with_error(&format!("Line {} of `{path}`: {e}", line_index + 1)),
+        }
+    }
+
+    if openings.is_empty() {
+        exit_with_error(&format!("No openings in `{path}`."));
+    }
+    return openings;
+}
+
+fn append_to_file(path: &str, contents: &str) -> Result<(), String> {
+    let file = fs::OpenOptions::new().create(true).append(true).open(path);
+    return match file.and_then(|mut f| writeln!(f, "{contents}")) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Unable to write PGN file `{path}`. Error: {e}")),
+    };
+}
+
+fn parse_string(arg: Option<&String>, flag: &str) -> String {
+    return match arg {
+        Some(s) => s.clone(),
+        None => exit_with_error(&format!("`{flag}` expects a value.")),
+    };
+}
+
+fn parse_number<T: std::str::FromStr>(arg: Option<&String>, flag: &str) -> T {
+    return match arg.map(|s| s.parse::<T>()) {
+        Some(Ok(n)) => n,
+        _ => exit_with_error(&format!("`{flag}` expects a number.")),
+    };
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    process::exit(1);
+}
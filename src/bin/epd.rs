@@ -0,0 +1,135 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::epd::EpdPosition;
+use chess_engine::search_limits::SearchLimits;
+use chess_engine::variant::Variant;
+use std::env;
+use std::fs;
+use std::process;
+
+const USAGE: &str =
+    "Usage: epd <suite file> [--time <ms> | --depth <n> | --nodes <n>] [--variant <name>]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut suite_path: Option<String> = None;
+    let mut limits = SearchLimits::time_ms(1_000);
+    let mut variant = Variant::Standard;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--time" => {
+                i += 1;
+                limits = SearchLimits::time_ms(parse_number(args.get(i), "--time"));
+            }
+            "--depth" => {
+                i += 1;
+                limits = SearchLimits::depth(parse_number(args.get(i), "--depth"));
+            }
+            "--nodes" => {
+                i += 1;
+                limits = SearchLimits::nodes(parse_number(args.get(i), "--nodes"));
+            }
+            "--variant" => {
+                i += 1;
+                variant = match args.get(i).map(|name| Variant::from_pgn_name(name)) {
+                    Some(Ok(v)) => v,
+                    Some(Err(e)) => exit_with_error(&e.to_string()),
+                    None => exit_with_error("`--variant` expects a variant name."),
+                };
+            }
+            s => {
+                if suite_path.is_none() {
+                    suite_path = Some(s.to_string());
+                } else {
+                    exit_with_error(&format!("Unexpected argument `{s}`."));
+                }
+            }
+        }
+        i += 1;
+    }
+
+    let suite_path = match suite_path {
+        Some(s) => s,
+        None => exit_with_error("Missing suite file."),
+    };
+    let suite = match fs::read_to_string(&suite_path) {
+        Ok(s) => s,
+        Err(e) => exit_with_error(&format!("Unable to read suite `{suite_path}`. Error: {e}")),
+    };
+
+    let mut game = ChessGame::new();
+    game.variant = variant;
+
+    let mut solved: usize = 0;
+    let mut checked: usize = 0;
+    let mut skipped: usize = 0;
+    let mut total_time_ms: u128 = 0;
+    let mut total_nodes: u64 = 0;
+
+    for (line_index, line) in suite.lines().enumerate() {
+        let line_number = line_index + 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let outcome = EpdPosition::parse(line)
+            .and_then(|position| Ok((position.solve(&mut game, &limits)?, position)));
+        let (outcome, position) = match outcome {
+            Ok(o) => o,
+            Err(e) => {
+                println!("Line {line_number}: skipped. {e}");
+                skipped += 1;
+                continue;
+            }
+        };
+
+        checked += 1;
+        total_time_ms += outcome.time_ms;
+        total_nodes += outcome.nodes;
+        if outcome.solved {
+            solved += 1;
+        }
+
+        let mut expected = vec![];
+        if !position.best_moves.is_empty() {
+            expected.push(format!("bm {}", position.best_moves.join(" ")));
+        }
+        if !position.avoid_moves.is_empty() {
+            expected.push(format!("am {}", position.avoid_moves.join(" ")));
+        }
+        println!(
+            "{:<16} {:<8} found {:<8} expected {:<16} {:>7} ms {:>10} nodes",
+            position.id.unwrap_or_else(|| format!("line {line_number}")),
+            match outcome.solved {
+                true => "solved",
+                false => "UNSOLVED",
+            },
+            outcome.found.unwrap_or_else(|| "-".to_string()),
+            expected.join(", "),
+            outcome.time_ms,
+            outcome.nodes
+        );
+    }
+
+    let percentage = match checked {
+        0 => 0.0,
+        _ => solved as f64 * 100.0 / checked as f64,
+    };
+    println!(
+        "\nSolved {solved}/{checked} ({percentage:.1}%), {skipped} skipped. {total_time_ms} ms, {total_nodes} nodes."
+    );
+}
+
+fn parse_number<T: std::str::FromStr>(arg: Option<&String>, flag: &str) -> T {
+    return match arg.map(|s| s.parse::<T>()) {
+        Some(Ok(n)) => n,
+        _ => exit_with_error(&format!("`{flag}` expects a positive number.")),
+    };
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    process::exit(1);
+}
@@ -0,0 +1,94 @@
+use chess_engine::evaluation_parameters::EvaluationParameters;
+use chess_engine::texel::{load_labelled_positions, TexelTuner};
+use std::env;
+use std::process;
+
+const USAGE: &str = "Usage: texel_tune <positions file> <output file> [--params <starting parameters file>] [--iterations <n>]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut positions_path: Option<String> = None;
+    let mut output_path: Option<String> = None;
+    let mut starting_parameters_path: Option<String> = None;
+    let mut max_iterations: u32 = 1000;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--params" => {
+                i += 1;
+                starting_parameters_path = args.get(i).cloned();
+            }
+            "--iterations" => {
+                i += 1;
+                max_iterations = match args.get(i).map(|s| s.parse::<u32>()) {
+                    Some(Ok(n)) => n,
+                    _ => exit_with_error("`--iterations` expects a positive number."),
+                };
+            }
+            s => {
+                if positions_path.is_none() {
+                    positions_path = Some(s.to_string());
+                } else if output_path.is_none() {
+                    output_path = Some(s.to_string());
+                } else {
+                    exit_with_error(&format!("Unexpected argument `{s}`."));
+                }
+            }
+        }
+        i += 1;
+    }
+
+    let (positions_path, output_path) = match (positions_path, output_path) {
+        (Some(p), Some(o)) => (p, o),
+        _ => exit_with_error("Missing positions file or output file."),
+    };
+
+    let starting_parameters = match starting_parameters_path {
+        Some(path) => match EvaluationParameters::load_from_file(&path) {
+            Ok(p) => p,
+            Err(e) => exit_with_error(&e),
+        },
+        None => EvaluationParameters::default(),
+    };
+
+    println!("Loading positions from `{positions_path}`...");
+    let positions = match load_labelled_positions(&positions_path) {
+        Ok(p) => p,
+        Err(e) => exit_with_error(&e),
+    };
+
+    let mut tuner = match TexelTuner::new(&positions, &starting_parameters) {
+        Ok(t) => t,
+        Err(e) => exit_with_error(&e),
+    };
+    println!(
+        "Loaded {} positions, skipped {} endgame positions.",
+        tuner.len(),
+        tuner.skipped
+    );
+
+    let starting_values = starting_parameters.to_vec();
+    let k = tuner.find_best_k(&starting_values);
+    println!(
+        "Best K: {k:.4}. Starting error: {:.8}",
+        tuner.mean_squared_error(&starting_values, k)
+    );
+
+    let tuned_parameters = tuner.tune(&starting_parameters, max_iterations, true);
+    println!(
+        "Final error: {:.8}",
+        tuner.mean_squared_error(&tuned_parameters.to_vec(), k)
+    );
+
+    match tuned_parameters.save_to_file(&output_path) {
+        Ok(_) => println!("Wrote tuned parameters to `{output_path}`."),
+        Err(e) => exit_with_error(&e),
+    }
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    process::exit(1);
+}
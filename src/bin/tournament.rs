@@ -0,0 +1,223 @@
+use chess_engine::engine_match::{Adjudication, MatchOptions, TimeControl};
+use chess_engine::epd::EpdPosition;
+use chess_engine::tournament::{play_game, round_robin, Crosstable};
+use chess_engine::uci_engine::UciEngine;
+use chess_engine::variant::Variant;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process;
+
+const USAGE: &str = "Usage: tournament --engine <path> [--name <name>] [--option <name>=<value>] --engine <path> ... --openings <file> [--games <n>] [--tc <seconds+increment>] [--variant <name>] [--pgn <file>] [--no-adjudication]";
+
+// An engine from the command line, not started yet.
+struct EngineConfig {
+    path: String,
+    name: Option<String>,
+    options: Vec<(String, String)>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut configs: Vec<EngineConfig> = vec![];
+    let mut openings_path: Option<String> = None;
+    let mut pgn_path: Option<String> = None;
+    let mut games_per_pairing: usize = 2;
+    let mut options = MatchOptions {
+        variant: Variant::Standard,
+        time_control: TimeControl {
+            base_ms: 10_000,
+            increment_ms: 100,
+        },
+        adjudication: Adjudication::default(),
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        let flag = args[i].as_str();
+        match flag {
+            "--engine" => {
+                i += 1;
+                configs.push(EngineConfig {
+                    path: parse_string(args.get(i), flag),
+                    name: None,
+                    options: vec![],
+                });
+            }
+            // `--name` and `--option` go with the `--engine` before them.
+            "--name" => {
+                i += 1;
+                let name = parse_string(args.get(i), flag);
+                last_engine(&mut configs, flag).name = Some(name);
+            }
+            "--option" => {
+                i += 1;
+                let option = parse_string(args.get(i), flag);
+                let (name, value) = match option.split_once('=') {
+                    Some((name, value)) => (name.to_string(), value.to_string()),
+                    None => exit_with_error(&format!("Expected `<name>=<value>`, got `{option}`.")),
+                };
+                last_engine(&mut configs, flag).options.push((name, value));
+            }
+            "--openings" => {
+                i += 1;
+                openings_path = Some(parse_string(args.get(i), flag));
+            }
+            "--pgn" => {
+                i += 1;
+                pgn_path = Some(parse_string(args.get(i), flag));
+            }
+            "--games" => {
+                i += 1;
+                games_per_pairing = parse_number(args.get(i), flag);
+            }
+            "--tc" => {
+                i += 1;
+                options.time_control = match TimeControl::parse(&parse_string(args.get(i), flag)) {
+                    Ok(tc) => tc,
+                    Err(e) => exit_with_error(&e),
+                };
+            }
+            "--variant" => {
+                i += 1;
+                options.variant = match Variant::from_pgn_name(&parse_string(args.get(i), flag)) {
+                    Ok(Variant::Standard) => Variant::Standard,
+                    Ok(Variant::Chess960) => Variant::Chess960,
+                    Ok(_) => exit_with_error("UCI engines only play standard chess and Chess960."),
+                    Err(e) => exit_with_error(&e.to_string()),
+                };
+            }
+            "--no-adjudication" => {
+                options.adjudication.resign_plies = 0;
+                options.adjudication.draw_plies = 0;
+            }
+            s => exit_with_error(&format!("Unexpected argument `{s}`.")),
+        }
+        i += 1;
+    }
+
+    if configs.len() < 2 {
+        exit_with_error("A tournament needs at least two engines.");
+    }
+    let openings = match openings_path {
+        Some(path) => load_openings(&path),
+        None => exit_with_error("Missing opening suite."),
+    };
+
+    let mut engines = vec![];
+    for config in configs.iter() {
+        let mut engine = match UciEngine::start(&config.path, &config.options) {
+            Ok(e) => e,
+            Err(e) => exit_with_error(&e.to_string()),
+        };
+        if let Some(name) = &config.name {
+            engine.name = name.clone();
+        }
+        engines.push(engine);
+    }
+
+    let names: Vec<String> = engines.iter().map(|e| e.name.clone()).collect();
+    let mut crosstable = Crosstable::new(&names);
+    let schedule = round_robin(engines.len(), openings.len(), games_per_pairing);
+    println!("{} engines, {} games.", engines.len(), schedule.len());
+
+    for (game_number, pairing) in schedule.iter().enumerate() {
+        let opening = &openings[pairing.opening];
+        let (white, black) = two_engines(&mut engines, pairing.white, pairing.black);
+        let game = match play_game(white, black, opening, &options) {
+            Ok(g) => g,
+            Err(e) => exit_with_error(&format!("Unable to play opening `{opening}`. {e}")),
+        };
+        crosstable.add_game(pairing.white, pairing.black, game.result);
+
+        if let Some(path) = &pgn_path {
+            if let Err(e) = append_to_file(path, &game.pgn.to_string()) {
+                exit_with_error(&e);
+            }
+        }
+
+        println!(
+            "Game {} of {}: {} vs {} {} ({}).",
+            game_number + 1,
+            schedule.len(),
+            names[pairing.white],
+            names[pairing.black],
+            game.pgn.tag("Result").unwrap_or("*"),
+            game.termination
+        );
+    }
+
+    println!("\n{crosstable}");
+}
+
+fn last_engine<'a>(configs: &'a mut [EngineConfig], flag: &str) -> &'a mut EngineConfig {
+    return match configs.last_mut() {
+        Some(config) => config,
+        None => exit_with_error(&format!("`{flag}` has to come after an `--engine`.")),
+    };
+}
+
+// Both engines of a pairing, which are never the same one.
+fn two_engines(
+    engines: &mut [UciEngine],
+    white: usize,
+    black: usize,
+) -> (&mut UciEngine, &mut UciEngine) {
+    let (low, high) = engines.split_at_mut(white.max(black));
+    return match white < black {
+        true => (&mut low[white], &mut high[0]),
+        false => (&mut high[0], &mut low[black]),
+    };
+}
+
+// One opening per line, as a FEN or an EPD. Blank lines and `#` comments are skipped.
+fn load_openings(path: &str) -> Vec<String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => exit_with_error(&format!("Unable to read openings `{path}`. Error: {e}")),
+    };
+
+    let mut openings = vec![];
+    for (line_index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        match EpdPosition::parse(line) {
+            Ok(position) => openings.push(position.fen),
+            Err(e) => exit_with_error(&format!("Line {} of `{path}`: {e}", line_index + 1)),
+        }
+    }
+
+    if openings.is_empty() {
+        exit_with_error(&format!("No openings in `{path}`."));
+    }
+    return openings;
+}
+
+fn append_to_file(path: &str, contents: &str) -> Result<(), String> {
+    let file = fs::OpenOptions::new().create(true).append(true).open(path);
+    return match file.and_then(|mut f| writeln!(f, "{contents}")) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Unable to write PGN file `{path}`. Error: {e}")),
+    };
+}
+
+fn parse_string(arg: Option<&String>, flag: &str) -> String {
+    return match arg {
+        Some(s) => s.clone(),
+        None => exit_with_error(&format!("`{flag}` expects a value.")),
+    };
+}
+
+fn parse_number<T: std::str::FromStr>(arg: Option<&String>, flag: &str) -> T {
+    return match arg.map(|s| s.parse::<T>()) {
+        Some(Ok(n)) => n,
+        _ => exit_with_error(&format!("`{flag}` expects a number.")),
+    };
+}
+
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{message}\n{USAGE}");
+    process::exit(1);
+}
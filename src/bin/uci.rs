@@ -0,0 +1,121 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::engine_match::TimeControl;
+use chess_engine::search_limits::SearchLimits;
+use chess_engine::variant::Variant;
+use std::io::{self, BufRead};
+
+// Speaks UCI on stdin and stdout, so GUIs and tournament managers (including our own `tournament`) can run the engine.
+fn main() {
+    let mut game = ChessGame::new();
+    let mut variant = Variant::Standard;
+    set_position(&mut game, variant, &["startpos"]);
+
+    for line in io::stdin().lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => return,
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.first().copied() {
+            Some("uci") => {
+                println!("id name chess_engine");
+                println!("option name UCI_Chess960 type check default false");
+                println!("uciok");
+            }
+            Some("isready") => println!("readyok"),
+            Some("setoption") => {
+                // `setoption name UCI_Chess960 value true` is the only option there is.
+                if words.get(2) == Some(&"UCI_Chess960") {
+                    variant = match words.get(4) {
+                        Some(&"true") => Variant::Chess960,
+                        _ => Variant::Standard,
+                    };
+                }
+            }
+            Some("ucinewgame") => game.transposition_table.clear(),
+            Some("position") => set_position(&mut game, variant, &words[1..]),
+            Some("go") => go(&mut game, &words[1..]),
+            Some("quit") => return,
+            _ => {}
+        }
+    }
+}
+
+// `startpos` or `fen <fen>`, then optionally `moves <move> <move> ...`.
+fn set_position(game: &mut ChessGame, variant: Variant, words: &[&str]) {
+    let moves_index = words
+        .iter()
+        .position(|w| *w == "moves")
+        .unwrap_or(words.len());
+    let fen = match words.first() {
+        Some(&"fen") => words[1..moves_index].join(" "),
+        _ => INITIAL_GAME_STATE_FEN.to_string(),
+    };
+
+    game.variant = variant;
+    if let Err(e) = game.import_fen(&fen) {
+        println!("info string Invalid position `{fen}`. {e}");
+        return;
+    }
+    game.set_legal_moves(None);
+
+    for uci in words.iter().skip(moves_index + 1) {
+        match game.uci_to_legal_move(uci) {
+            Ok(this_move) => game.make_move(&this_move, true),
+            Err(e) => {
+                println!("info string {e}");
+                return;
+            }
+        }
+    }
+}
+
+/**
+ * Searches with whatever limits `go` was given and answers with `bestmove`. The search can't be interrupted, so
+ * `stop` isn't supported, and `go infinite` or a bare `go` searches for the usual 5 seconds.
+ */
+fn go(game: &mut ChessGame, words: &[&str]) {
+    let value = |name: &str| -> Option<u128> {
+        let index = words.iter().position(|w| *w == name)?;
+        return words.get(index + 1)?.parse::<u128>().ok();
+    };
+    let (clock, increment) = match game.white_to_move {
+        true => (value("wtime"), value("winc")),
+        false => (value("btime"), value("binc")),
+    };
+
+    let limits = if let Some(depth) = value("depth") {
+        SearchLimits::depth(depth as u32)
+    } else if let Some(nodes) = value("nodes") {
+        SearchLimits::nodes(nodes as u64)
+    } else if let Some(movetime) = value("movetime") {
+        SearchLimits::time_ms(movetime)
+    } else if let Some(clock) = clock {
+        let time_control = TimeControl {
+            base_ms: clock,
+            increment_ms: increment.unwrap_or(0),
+        };
+        time_control.move_limits(clock)
+    } else {
+        SearchLimits::time_ms(5_000)
+    };
+
+    let (score, best_move) = game.search_with_limits(&limits);
+    let best_move = match best_move {
+        Some(m) => m,
+        None => {
+            println!("bestmove 0000");
+            return;
+        }
+    };
+
+    // Our scores are from white's side and UCI's are from the mover's. The search doesn't track how far away a mate
+    // is, so mates are reported as large centipawn scores.
+    let score = match game.white_to_move {
+        true => score,
+        false => score.saturating_neg(),
+    };
+    println!("info score cp {}", score.clamp(-30_000, 30_000));
+    println!("bestmove {}", game.move_to_uci(&best_move));
+}
@@ -0,0 +1,131 @@
+use crate::square::Square;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+// One bit per square, see `Square` for the numbering.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    pub fn contains(&self, square: Square) -> bool {
+        return self.0 & (1 << square.index()) != 0;
+    }
+
+    pub fn with(self, square: Square) -> Self {
+        return Bitboard(self.0 | (1 << square.index()));
+    }
+
+    pub fn without(self, square: Square) -> Self {
+        return Bitboard(self.0 & !(1 << square.index()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.0 == 0;
+    }
+
+    pub fn count(&self) -> usize {
+        return self.0.count_ones() as usize;
+    }
+
+    pub fn lsb(&self) -> Option<Square> {
+        if self.0 == 0 {
+            return None;
+        }
+        return Some(Square::new(self.0.trailing_zeros() as usize));
+    }
+
+    pub fn squares(&self) -> BitboardSquares {
+        return BitboardSquares(self.0);
+    }
+}
+
+// Every square in a bitboard, lowest index first.
+pub struct BitboardSquares(u64);
+
+impl Iterator for BitboardSquares {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Square> {
+        if self.0 == 0 {
+            return None;
+        }
+        let square = Square::new(self.0.trailing_zeros() as usize);
+
+        // Clear the lowest bit.
+        self.0 &= self.0 - 1;
+        return Some(square);
+    }
+}
+
+impl IntoIterator for Bitboard {
+    type Item = Square;
+    type IntoIter = BitboardSquares;
+
+    fn into_iter(self) -> BitboardSquares {
+        return self.squares();
+    }
+}
+
+impl From<u64> for Bitboard {
+    fn from(bits: u64) -> Self {
+        return Bitboard(bits);
+    }
+}
+
+impl From<Square> for Bitboard {
+    fn from(square: Square) -> Self {
+        return square.bitboard();
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(self, other: Bitboard) -> Bitboard {
+        return Bitboard(self.0 & other.0);
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(self, other: Bitboard) -> Bitboard {
+        return Bitboard(self.0 | other.0);
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Bitboard;
+
+    fn bitxor(self, other: Bitboard) -> Bitboard {
+        return Bitboard(self.0 ^ other.0);
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard {
+        return Bitboard(!self.0);
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, other: Bitboard) {
+        self.0 &= other.0;
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, other: Bitboard) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitXorAssign for Bitboard {
+    fn bitxor_assign(&mut self, other: Bitboard) {
+        self.0 ^= other.0;
+    }
+}
@@ -0,0 +1,285 @@
+use crate::bot_config_error::BotConfigError;
+use crate::evaluation_parameters::EvaluationParameters;
+use crate::lichess_structs::InnerChallenge;
+use crate::log_level::LogLevel;
+use crate::variant::Variant;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+// Where the bot looks for its config when neither `--config` nor `LICHESS_BOT_CONFIG` says otherwise.
+pub const DEFAULT_BOT_CONFIG_PATH: &str = "lichess_bot.json";
+
+/**
+ * How the Lichess bot runs, read from a JSON file at startup. Only `username` is required; anything left out takes
+ * its value from `Default`. Unknown fields are errors, so a typo can't quietly do nothing.
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BotConfig {
+    // The bot's Lichess account, which tells us which color we're playing.
+    pub username: String,
+
+    pub challengers: ChallengerPolicy,
+
+    // Lichess variant keys to accept, like `standard` or `chess960`. Empty accepts every variant we can play.
+    pub variants: Vec<String>,
+
+    pub time_controls: TimeControlPolicy,
+    pub rated: RatedPolicy,
+
+    // Games played at once. Challenges past this are ignored until one finishes.
+    pub max_concurrent_games: usize,
+
+    pub engine: EngineOptions,
+    pub log_level: LogLevel,
+}
+
+// Who can challenge the bot, by Lichess username.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChallengerPolicy {
+    // When not empty, only these players.
+    pub allow: Vec<String>,
+
+    // Never these players.
+    pub deny: Vec<String>,
+}
+
+// Clocks to accept, in seconds. Both ends of each range are included.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TimeControlPolicy {
+    pub min_initial_seconds: u32,
+    pub max_initial_seconds: u32,
+    pub min_increment_seconds: u32,
+    pub max_increment_seconds: u32,
+
+    // Games with days per move instead of a clock, and games with no clock at all.
+    pub allow_correspondence: bool,
+    pub allow_unlimited: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RatedPolicy {
+    Any,
+    Rated,
+    Casual,
+}
+
+// How each game's engine is set up.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineOptions {
+    // Transposition table size, per game.
+    pub hash_mb: usize,
+
+    // The search is single threaded for now. More than 1 is allowed, but only gets a warning.
+    pub threads: usize,
+
+    // Opening book. There's no book support yet, so it's only checked to exist.
+    pub book_path: Option<String>,
+
+    // Evaluation weights saved by the Texel tuner. The hand-picked defaults when not set.
+    pub evaluation_parameters_path: Option<String>,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        return BotConfig {
+            username: String::new(),
+            challengers: ChallengerPolicy::default(),
+            variants: vec![],
+            time_controls: TimeControlPolicy::default(),
+            rated: RatedPolicy::Any,
+            max_concurrent_games: 1,
+            engine: EngineOptions::default(),
+            log_level: LogLevel::Info,
+        };
+    }
+}
+
+impl Default for TimeControlPolicy {
+    // Anything with a clock, up to three hours plus three minutes a move.
+    fn default() -> Self {
+        return TimeControlPolicy {
+            min_initial_seconds: 0,
+            max_initial_seconds: 10_800,
+            min_increment_seconds: 0,
+            max_increment_seconds: 180,
+            allow_correspondence: false,
+            allow_unlimited: false,
+        };
+    }
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        return EngineOptions {
+            hash_mb: 16,
+            threads: 1,
+            book_path: None,
+            evaluation_parameters_path: None,
+        };
+    }
+}
+
+impl BotConfig {
+    pub fn load_from_file(path: &str) -> Result<Self, BotConfigError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(error) => {
+                return Err(BotConfigError::Io {
+                    path: path.to_string(),
+                    error,
+                })
+            }
+        };
+
+        return Self::from_json(&contents);
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, BotConfigError> {
+        let config: BotConfig = serde_json::from_str(s)?;
+        config.validate()?;
+        return Ok(config);
+    }
+
+    // Catches settings that can't work, so the bot stops at startup rather than partway through a game.
+    pub fn validate(&self) -> Result<(), BotConfigError> {
+        if self.username.trim().is_empty() {
+            return Err(BotConfigError::MissingUsername);
+        }
+
+        for name in self.challengers.allow.iter() {
+            if contains_name(&self.challengers.deny, name) {
+                return Err(BotConfigError::ChallengerAllowedAndDenied(name.clone()));
+            }
+        }
+
+        for key in self.variants.iter() {
+            if Variant::from_lichess_key(key).is_err() {
+                return Err(BotConfigError::UnsupportedVariant(key.clone()));
+            }
+        }
+
+        let time_controls = &self.time_controls;
+        if time_controls.min_initial_seconds > time_controls.max_initial_seconds {
+            return Err(BotConfigError::InvalidTimeControlRange(
+                "initial_seconds".to_string(),
+            ));
+        }
+        if time_controls.min_increment_seconds > time_controls.max_increment_seconds {
+            return Err(BotConfigError::InvalidTimeControlRange(
+                "increment_seconds".to_string(),
+            ));
+        }
+
+        let out_of_range = |setting: &str, expected: &str| -> BotConfigError {
+            return BotConfigError::OutOfRange {
+                setting: setting.to_string(),
+                expected: expected.to_string(),
+            };
+        };
+        if self.max_concurrent_games == 0 {
+            return Err(out_of_range("max_concurrent_games", "at least 1"));
+        }
+        if !(1..=65_536).contains(&self.engine.hash_mb) {
+            return Err(out_of_range("engine.hash_mb", "between 1 and 65536"));
+        }
+        if self.engine.threads == 0 {
+            return Err(out_of_range("engine.threads", "at least 1"));
+        }
+
+        if let Some(path) = &self.engine.book_path {
+            if !Path::new(path).is_file() {
+                return Err(BotConfigError::MissingBook(path.clone()));
+            }
+        }
+
+        if let Some(path) = &self.engine.evaluation_parameters_path {
+            if let Err(e) = EvaluationParameters::load_from_file(path) {
+                return Err(BotConfigError::InvalidEvaluationParameters(e));
+            }
+        }
+
+        return Ok(());
+    }
+
+    // Settings that are valid, but don't do anything yet.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = vec![];
+        if self.engine.threads > 1 {
+            warnings.push(format!(
+                "`engine.threads` is {}, but the search only uses one thread.",
+                self.engine.threads
+            ));
+        }
+        if let Some(path) = &self.engine.book_path {
+            warnings.push(format!(
+                "Opening books aren't supported yet, `{path}` won't be used."
+            ));
+        }
+        return warnings;
+    }
+
+    // Whether a Lichess user id or name is the bot's own account. Lichess ids are the lower case username.
+    pub fn is_bot(&self, user: &str) -> bool {
+        return user.eq_ignore_ascii_case(self.username.trim());
+    }
+
+    // Whether to accept `challenge`, and if not, why. Doesn't know how many games are running.
+    pub fn check_challenge(&self, challenge: &InnerChallenge) -> Result<(), String> {
+        let challenger = &challenge.challenger.name;
+        if contains_name(&self.challengers.deny, challenger) {
+            return Err(format!("{challenger} is on the deny list."));
+        }
+        if !self.challengers.allow.is_empty() && !contains_name(&self.challengers.allow, challenger)
+        {
+            return Err(format!("{challenger} isn't on the allow list."));
+        }
+
+        let key = &challenge.variant.key;
+        if let Err(e) = Variant::from_lichess_key(key) {
+            return Err(e.to_string());
+        }
+        if !self.variants.is_empty() && !self.variants.contains(key) {
+            return Err(format!("Not accepting `{key}` games."));
+        }
+
+        match (self.rated, challenge.rated) {
+            (RatedPolicy::Rated, false) => return Err("Only playing rated games.".to_string()),
+            (RatedPolicy::Casual, true) => return Err("Only playing casual games.".to_string()),
+            _ => {}
+        }
+
+        let time_controls = &self.time_controls;
+        let time_control = &challenge.time_control;
+        return match time_control.r#type.as_str() {
+            "clock" => {
+                let initial = time_control.limit.unwrap_or(0);
+                let increment = time_control.increment.unwrap_or(0);
+                if initial < time_controls.min_initial_seconds
+                    || initial > time_controls.max_initial_seconds
+                {
+                    return Err(format!("Not accepting {initial} seconds on the clock."));
+                }
+                if increment < time_controls.min_increment_seconds
+                    || increment > time_controls.max_increment_seconds
+                {
+                    return Err(format!("Not accepting a {increment} second increment."));
+                }
+                Ok(())
+            }
+            "correspondence" if time_controls.allow_correspondence => Ok(()),
+            "unlimited" if time_controls.allow_unlimited => Ok(()),
+            other => Err(format!("Not accepting `{other}` time controls.")),
+        };
+    }
+}
+
+// Lichess usernames aren't case sensitive.
+fn contains_name(names: &[String], name: &str) -> bool {
+    return names.iter().any(|n| n.eq_ignore_ascii_case(name));
+}
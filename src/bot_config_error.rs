@@ -0,0 +1,86 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+// Why the bot's config file couldn't be used. The bot doesn't start without one.
+#[derive(Debug)]
+pub enum BotConfigError {
+    // The file at `path` couldn't be read.
+    Io { path: String, error: io::Error },
+
+    // Not JSON, or JSON with a field that's misspelt or has the wrong type. Serde says where.
+    Parse(serde_json::Error),
+
+    MissingUsername,
+
+    // A name is on both the allow and deny lists.
+    ChallengerAllowedAndDenied(String),
+
+    // A Lichess variant key we can't play.
+    UnsupportedVariant(String),
+
+    // A `min_` time control setting above its `max_`. Holds the setting.
+    InvalidTimeControlRange(String),
+
+    // Out of range engine or concurrency setting. The setting and what it has to be.
+    OutOfRange { setting: String, expected: String },
+
+    // `engine.book_path` doesn't point at a file.
+    MissingBook(String),
+
+    // `engine.evaluation_parameters_path` couldn't be loaded. Holds the detail from `EvaluationParameters`.
+    InvalidEvaluationParameters(String),
+}
+
+impl fmt::Display for BotConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            BotConfigError::Io { path, error } => {
+                write!(f, "Unable to read bot config `{path}`. Error: {error}")
+            }
+            BotConfigError::Parse(e) => write!(f, "Unable to parse bot config. Error: {e}"),
+            BotConfigError::MissingUsername => {
+                write!(f, "The bot config needs the bot's Lichess `username`.")
+            }
+            BotConfigError::ChallengerAllowedAndDenied(name) => write!(
+                f,
+                "`{name}` is on both `challengers.allow` and `challengers.deny`."
+            ),
+            BotConfigError::UnsupportedVariant(key) => {
+                write!(f, "Unsupported variant in `variants`: `{key}`.")
+            }
+            BotConfigError::InvalidTimeControlRange(setting) => write!(
+                f,
+                "`time_controls.min_{setting}` is more than `time_controls.max_{setting}`."
+            ),
+            BotConfigError::OutOfRange { setting, expected } => {
+                write!(f, "`{setting}` has to be {expected}.")
+            }
+            BotConfigError::MissingBook(path) => {
+                write!(
+                    f,
+                    "`engine.book_path` is set, but there's no file at `{path}`."
+                )
+            }
+            BotConfigError::InvalidEvaluationParameters(detail) => {
+                write!(f, "Bad `engine.evaluation_parameters_path`. {detail}")
+            }
+        };
+    }
+}
+
+impl Error for BotConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            BotConfigError::Io { error, .. } => Some(error),
+            BotConfigError::Parse(e) => Some(e),
+            _ => None,
+        };
+    }
+}
+
+impl From<serde_json::Error> for BotConfigError {
+    fn from(e: serde_json::Error) -> Self {
+        return BotConfigError::Parse(e);
+    }
+}
@@ -1,25 +1,80 @@
+use crate::bitboard::Bitboard;
 use crate::castle_sides::CastleSides;
 use crate::color::Color;
 use crate::constants;
+use crate::endgame;
+use crate::evaluation_mode::EvaluationMode;
+use crate::evaluation_parameters::EvaluationParameters;
+use crate::evaluation_trace::{EvaluationTrace, PhaseScore};
+use crate::fen_error::FenError;
+use crate::file::File;
+use crate::game_result::GameResult;
 use crate::helpers::*;
+use crate::illegal_move_error::IllegalMoveError;
+use crate::move_generation_mode::MoveGenerationMode;
+use crate::nnue::{NnueAccumulator, NnueNetwork};
+use crate::piece::Piece;
 use crate::piece_type::PieceType;
 use crate::r#move::Move;
-use crate::runtime_calculated_constants::Constants;
+use crate::rank::Rank;
+use crate::runtime_calculated_constants::{Constants, MAX_CHECK_COUNT, MAX_POCKET_COUNT};
+use crate::search_limits::{SearchLimits, UNLIMITED_SEARCH_DEPTH};
+use crate::square::Square;
 use crate::transposition_table_entry::{TranspositionTableEntry, TranspositionTableNodeType};
+use crate::variant::Variant;
 use std::collections::HashMap;
 use std::io;
+use std::sync::Arc;
+
+// Pieces that can be dropped in crazyhouse, cheapest first.
+pub const POCKET_PIECE_TYPES: [PieceType; 5] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+];
+
+// Second to seventh rank.
+const PAWN_DROP_SQUARES: u64 = 0x00FF_FFFF_FFFF_FF00;
+
+// King of the Hill. d5, e5, d4, e4.
+const CENTRE_SQUARES: u64 = 0x0000_0018_1800_0000;
+
+// Racing Kings goal.
+const EIGHTH_RANK: u64 = 0xFF;
+
+// Where pawns can't be.
+const BACK_RANKS: u64 = EIGHTH_RANK | 0xFF00_0000_0000_0000;
+
+// Transposition table slots a new game gets. Changing it changes the bench signature.
+const DEFAULT_TRANSPOSITION_TABLE_SIZE: u64 = 10_000;
+
+// h1, a1, h8, a8.
+const DEFAULT_CASTLING_ROOK_SQUARES: [usize; 4] = [63, 56, 7, 0];
 
-// TODO: Research more on lifetime stuff.
 #[derive(Clone)]
-pub struct ChessGame<'a> {
-    pub bitboard_constants: &'a Constants,
+pub struct ChessGame {
+    // Shared by every game in the process, see `Constants::get`.
+    pub bitboard_constants: &'static Constants,
 
     pub zobrist_hash: u64,
 
     pub transposition_table: HashMap<u64, TranspositionTableEntry>,
 
+    // How many slots positions are hashed into, see `set_transposition_table_mb`.
+    pub transposition_table_size: u64,
+
+    // Weights used by `evaluate_board`. Can be swapped out for tuned values.
+    pub evaluation_parameters: EvaluationParameters,
+
+    // Optional neural network evaluation. Accumulators are a stack, one entry per move made.
+    pub evaluation_mode: EvaluationMode,
+    pub nnue_network: Option<Arc<NnueNetwork>>,
+    pub nnue_accumulators: Vec<NnueAccumulator>,
+
     // En-Passant
-    pub en_passant_target: Option<usize>,
+    pub en_passant_target: Option<Square>,
 
     // Flags.
     pub white_to_move: bool,
@@ -28,6 +83,26 @@ pub struct ChessGame<'a> {
     pub can_black_castle_long: bool,
     pub can_black_castle_short: bool,
 
+    // Rules we are playing by. Chess960 changes how castling is read, written, and played.
+    pub variant: Variant,
+
+    // Where the rook for each castling right starts. Same order as the zobrist castling keys:
+    // white short, white long, black short, black long.
+    pub(crate) castling_rook_squares: [usize; 4],
+
+    // Crazyhouse. Pieces in hand, indexed by color then piece bitboard index (pawn to queen).
+    pub(crate) pockets: [[usize; 5]; 2],
+
+    // Crazyhouse. Squares holding a piece that used to be a pawn, either color.
+    pub(crate) promoted_pieces: u64,
+
+    // Atomic. (piece bitboard index, square) of everything each capture blew up, so unmake can put it back.
+    // A stack like the NNUE accumulators, one entry per capture made.
+    pub(crate) explosions: Vec<Vec<(usize, usize)>>,
+
+    // Three-check. How many checks each side has given, indexed by color.
+    pub(crate) checks_given: [usize; 2],
+
     /*
         0 -> white_pawns
         1 -> white_bishops
@@ -38,28 +113,46 @@ pub struct ChessGame<'a> {
         6 -> black_pawns
         ...
     */
-    pub piece_bitboards: [u64; 12],
+    pub(crate) piece_bitboards: [u64; 12],
 
     /*
         0 -> white_occupancies
         1 -> black_occupancies
         2 -> all_occupancies
     */
-    pub occupancy_bitboards: [u64; 3],
+    pub(crate) occupancy_bitboards: [u64; 3],
 
     pub legal_moves: Vec<Move>,
 
     pub debug_minimax_calls: u64,
     pub debug_mimimax_moves_made: Vec<Move>,
+
+    // Budget for the depth being searched by `search_with_limits`. `minimax` gives up once either is spent.
+    search_node_limit: Option<u64>,
+    search_deadline: Option<std::time::Instant>,
+    search_aborted: bool,
+}
+
+impl Default for ChessGame {
+    fn default() -> Self {
+        return ChessGame::new();
+    }
 }
 
-impl<'a> ChessGame<'a> {
-    pub fn new(c: &'a Constants) -> Self {
+impl ChessGame {
+    pub fn new() -> Self {
         return ChessGame {
-            bitboard_constants: c,
+            bitboard_constants: Constants::get(),
 
             zobrist_hash: 0,
             transposition_table: HashMap::new(),
+            transposition_table_size: DEFAULT_TRANSPOSITION_TABLE_SIZE,
+
+            evaluation_parameters: EvaluationParameters::default(),
+
+            evaluation_mode: EvaluationMode::Classical,
+            nnue_network: None,
+            nnue_accumulators: vec![],
 
             en_passant_target: None,
 
@@ -69,6 +162,14 @@ impl<'a> ChessGame<'a> {
             can_black_castle_long: true,
             can_black_castle_short: true,
 
+            variant: Variant::Standard,
+            castling_rook_squares: DEFAULT_CASTLING_ROOK_SQUARES,
+
+            pockets: [[0; 5]; 2],
+            promoted_pieces: 0,
+            explosions: vec![],
+            checks_given: [0; 2],
+
             piece_bitboards: [0; 12],
             occupancy_bitboards: [0; 3],
 
@@ -76,6 +177,10 @@ impl<'a> ChessGame<'a> {
 
             debug_minimax_calls: 0,
             debug_mimimax_moves_made: vec![],
+
+            search_node_limit: None,
+            search_deadline: None,
+            search_aborted: false,
         };
     }
 
@@ -154,12 +259,29 @@ impl<'a> ChessGame<'a> {
 
             panic!("Black pieces desynced by {called_by}.");
         }
+
+        // Make sure the incremental hash agrees with one worked out from scratch.
+        if self.zobrist_hash != self.compute_zobrist() {
+            println!("Previous game state");
+            prev_game_state.print_board();
+            println!(
+                "Tried to make/unmake move: {:#?}\n\n Ended up with:",
+                this_move
+            );
+            self.print_board();
+
+            panic!("Zobrist hash desynced by {called_by}.");
+        }
     }
 
     // Takes all pieces off the board.
     pub fn clear_board(&mut self) {
         self.piece_bitboards = [0; 12];
         self.occupancy_bitboards = [0; 3];
+        self.pockets = [[0; 5]; 2];
+        self.promoted_pieces = 0;
+        self.explosions.clear();
+        self.checks_given = [0; 2];
     }
 
     pub fn print_board(&self) {
@@ -171,9 +293,8 @@ impl<'a> ChessGame<'a> {
             for file in 0..8 {
                 let square: usize = rank * 8 + file;
 
-                let (piece_type, color) = self.get_piece_at_square(square);
-                let c = match piece_type {
-                    Some(t) => t.to_char(color.unwrap()),
+                let c = match self.get_piece_at_square(Square::new(square)) {
+                    Some(piece) => piece.to_char(),
                     None => ' ',
                 };
 
@@ -186,7 +307,7 @@ impl<'a> ChessGame<'a> {
         println!("    A   B   C   D   E   F   G   H");
     }
 
-    pub fn import_fen(&mut self, fen: &str) -> Result<(), String> {
+    pub fn import_fen(&mut self, fen: &str) -> Result<(), FenError> {
         // Clear the board.
         self.clear_board();
 
@@ -200,7 +321,16 @@ impl<'a> ChessGame<'a> {
         let mut parts = trimmed_full_fen.split(' ');
         let board_str = match parts.next() {
             Some(s) => s,
-            None => return Err("No board position found in FEN.".to_string()),
+            None => return Err(FenError::MissingBoard),
+        };
+
+        // Crazyhouse pockets come after the board, in brackets (`...RNBQKBNR[Qn]`) or as a ninth row (`...RNBQKBNR/Qn`).
+        let (board_str, pocket_str) = match board_str.split_once('[') {
+            Some((board, pocket)) => (board, Some(pocket.trim_end_matches(']'))),
+            None => match board_str.match_indices('/').nth(7) {
+                Some((index, _)) => (&board_str[..index], Some(&board_str[index + 1..])),
+                None => (board_str, None),
+            },
         };
 
         // Prepare to populate our board.
@@ -216,17 +346,29 @@ impl<'a> ChessGame<'a> {
                 if c.is_digit(10) {
                     let num_empties: usize = match c.to_digit(10) {
                         Some(n) => n as usize,
-                        None => return Err(format!("Failed to parse digit: {}.", c)),
+                        None => return Err(FenError::InvalidEmptyCount(c)),
                     };
 
-                    if num_empties < 1 || num_empties > 8 {
-                        return Err(format!("Invalid number of empty spaces: {}", num_empties));
+                    if num_empties < 1 || x_pos + num_empties > 8 {
+                        return Err(FenError::InvalidEmptyCount(c));
                     }
 
                     x_pos += num_empties;
                     continue;
                 }
 
+                // Crazyhouse marks promoted pieces with a `~` after them.
+                if c == '~' {
+                    if x_pos == 0 {
+                        return Err(FenError::PromotedMarkerWithoutPiece);
+                    }
+                    if y_pos >= 8 {
+                        return Err(FenError::WrongRowCount(y_pos + 1));
+                    }
+                    self.promoted_pieces = set_bit(self.promoted_pieces, y_pos * 8 + x_pos - 1);
+                    continue;
+                }
+
                 // Place the piece on the board.
                 let piece_color: Color;
                 if c.is_ascii_uppercase() {
@@ -242,25 +384,31 @@ impl<'a> ChessGame<'a> {
                     'b' => PieceType::Bishop,
                     'n' => PieceType::Knight,
                     'p' => PieceType::Pawn,
-                    _ => return Err(format!("Unexpected piece letter {}", c)),
+                    _ => return Err(FenError::InvalidPiece(c)),
                 };
 
+                // Too many pieces for the row, or too many rows.
+                if x_pos >= 8 {
+                    return Err(FenError::WrongColumnCount(x_pos + 1));
+                }
+                if y_pos >= 8 {
+                    return Err(FenError::WrongRowCount(y_pos + 1));
+                }
+
                 let square: usize = y_pos * 8 + x_pos;
-                self.place_piece_on_board(piece_color, piece_type, square);
+                let piece = Piece::new(piece_color, piece_type);
+                self.place_piece_on_board(piece, Square::new(square));
 
                 // Update the zobrist hash.
-                self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                    [piece_type.bitboard_index() + piece_color.piece_bitboard_offset()][square];
+                self.zobrist_hash ^=
+                    self.bitboard_constants.zobrist_table[piece.bitboard_index()][square];
 
                 x_pos += 1;
             }
 
             // Ensure that the board has exactly 8 cols.
             if x_pos != 8 {
-                return Err(format!(
-                    "Board must have exactly 8 columns. We parsed: {}",
-                    x_pos
-                ));
+                return Err(FenError::WrongColumnCount(x_pos));
             }
 
             y_pos += 1;
@@ -268,16 +416,54 @@ impl<'a> ChessGame<'a> {
 
         // Ensure that the board has exactly 8 rows.
         if y_pos != 8 {
-            return Err(format!(
-                "Board must have exactly 8 rows. We parsed: {}",
-                y_pos
-            ));
+            return Err(FenError::WrongRowCount(y_pos));
         }
 
+        // Move generation expects every pawn to have a square in front of it, and a king for each side.
+        for side in [Color::White, Color::Black] {
+            let pawns = self.pieces(Piece::new(side, PieceType::Pawn)) & Bitboard(BACK_RANKS);
+            if let Some(square) = pawns.lsb() {
+                return Err(FenError::PawnOnBackRank(square));
+            }
+
+            let kings = self.pieces(Piece::new(side, PieceType::King)).count();
+            if !self.variant.allows_king_count(kings) {
+                return Err(FenError::WrongKingCount { side, count: kings });
+            }
+        }
+
+        // Pieces in hand.
+        if let Some(pocket) = pocket_str {
+            for c in pocket.chars() {
+                if c == '-' {
+                    continue;
+                }
+
+                let piece_type = match PieceType::char_to_piece_type(c) {
+                    Ok(t) => t,
+                    Err(_) => return Err(FenError::InvalidPiece(c)),
+                };
+                let side = match c.is_ascii_uppercase() {
+                    true => Color::White,
+                    false => Color::Black,
+                };
+                if piece_type == PieceType::King {
+                    return Err(FenError::KingInPocket);
+                }
+                if self.pockets[side.idx()][piece_type.bitboard_index()] >= MAX_POCKET_COUNT {
+                    return Err(FenError::PocketOverflow(c));
+                }
+                self.add_to_pocket(side, piece_type);
+            }
+        }
+
+        // The board changed completely, rebuild the network accumulators.
+        self.refresh_nnue_accumulators();
+
         // Store whose turn it is to move.
         let whose_turn = match parts.next() {
             Some(s) => s,
-            None => return Err("Unsure whose turn it is. Cannot proceed.".to_string()),
+            None => return Err(FenError::MissingSideToMove),
         };
 
         if whose_turn.to_ascii_lowercase() == "w" {
@@ -289,13 +475,10 @@ impl<'a> ChessGame<'a> {
             // XOR if it's black to move.
             self.zobrist_hash ^= self.bitboard_constants.zobrist_to_move;
         } else {
-            return Err(format!(
-                "Unexpected character for whose turn it is: {}. Should be 'w' or 'b'.",
-                whose_turn
-            ));
+            return Err(FenError::InvalidSideToMove(whose_turn.to_string()));
         }
 
-        // Castling.
+        // Castling. Besides KQkq we take the X-FEN and Shredder-FEN file letters used in Chess960.
         let castling_rights_str = parts.next();
         match castling_rights_str {
             Some(s) => {
@@ -304,28 +487,43 @@ impl<'a> ChessGame<'a> {
                 self.can_white_castle_short = false;
                 self.can_black_castle_long = false;
                 self.can_black_castle_short = false;
+                self.castling_rook_squares = DEFAULT_CASTLING_ROOK_SQUARES;
 
                 // Update rights based on what we find in the string.
                 for c in s.chars() {
-                    match c {
-                        'K' => {
-                            self.can_white_castle_short = true;
-                            self.zobrist_hash ^= self.bitboard_constants.zobrist_castling_rights[0];
-                        }
-                        'Q' => {
-                            self.can_white_castle_long = true;
-                            self.zobrist_hash ^= self.bitboard_constants.zobrist_castling_rights[1];
-                        }
-                        'k' => {
-                            self.can_black_castle_short = true;
-                            self.zobrist_hash ^= self.bitboard_constants.zobrist_castling_rights[2];
-                        }
-                        'q' => {
-                            self.can_black_castle_long = true;
-                            self.zobrist_hash ^= self.bitboard_constants.zobrist_castling_rights[3];
+                    let side = match c.is_ascii_uppercase() {
+                        true => Color::White,
+                        false => Color::Black,
+                    };
+
+                    let (castle_side, rook_square) = match c.to_ascii_lowercase() {
+                        'k' => (
+                            CastleSides::Short,
+                            self.find_castling_rook(side, CastleSides::Short),
+                        ),
+                        'q' => (
+                            CastleSides::Long,
+                            self.find_castling_rook(side, CastleSides::Long),
+                        ),
+                        'a'..='h' => {
+                            let file = c.to_ascii_lowercase() as usize - 'a' as usize;
+                            let castle_side = match file > self.castling_king_square(side) % 8 {
+                                true => CastleSides::Short,
+                                false => CastleSides::Long,
+                            };
+                            (castle_side, back_rank_start(side) + file)
                         }
-                        _ => (),
+                        _ => continue,
+                    };
+
+                    let rights_index = castling_rights_index(side, castle_side);
+                    if self.has_castling_right(rights_index) {
+                        continue;
                     }
+                    self.set_castling_right(rights_index, true);
+                    self.castling_rook_squares[rights_index] = rook_square;
+                    self.zobrist_hash ^=
+                        self.bitboard_constants.zobrist_castling_rights[rights_index];
                 }
             }
             None => return Ok(()),
@@ -336,11 +534,12 @@ impl<'a> ChessGame<'a> {
         match en_passant_target_str {
             Some(s) => {
                 // Try to parse the string as a coordinate.
-                let parsed_coord = str_coord_to_square(s);
+                let parsed_coord = s.parse::<Square>();
                 if parsed_coord.is_ok() {
                     let square = parsed_coord.unwrap();
                     self.en_passant_target = Some(square);
-                    self.zobrist_hash ^= self.bitboard_constants.zobrist_en_passant[square % 8];
+                    self.zobrist_hash ^=
+                        self.bitboard_constants.zobrist_en_passant[square.file().index()];
                 } else {
                     self.en_passant_target = None;
                 }
@@ -348,6 +547,33 @@ impl<'a> ChessGame<'a> {
             None => return Ok(()),
         };
 
+        // Three-check counters. Either the checks still needed after the en-passant square (`3+3`),
+        // or the checks given at the very end (`+0+0`).
+        if let Some(check_counts) = parts.find(|p| p.contains('+')) {
+            let counts: Vec<usize> = match check_counts
+                .trim_start_matches('+')
+                .split('+')
+                .map(|n| n.parse::<usize>())
+                .collect()
+            {
+                Ok(c) => c,
+                Err(_) => return Err(FenError::InvalidCheckCounts(check_counts.to_string())),
+            };
+            if counts.len() != 2 || counts.iter().any(|n| *n > MAX_CHECK_COUNT) {
+                return Err(FenError::InvalidCheckCounts(check_counts.to_string()));
+            }
+
+            for (side, count) in [Color::White, Color::Black].into_iter().zip(counts) {
+                let checks_given = match check_counts.starts_with('+') {
+                    true => count,
+                    false => MAX_CHECK_COUNT - count,
+                };
+                for _ in 0..checks_given {
+                    self.add_check(side);
+                }
+            }
+        }
+
         return Ok(());
     }
 
@@ -360,9 +586,7 @@ impl<'a> ChessGame<'a> {
             file = square % 8;
 
             // Get the piece at this square.
-            let (piece_option, color_option) = self.get_piece_at_square(square);
-
-            match piece_option {
+            match self.get_piece_at_square(Square::new(square)) {
                 Some(piece) => {
                     // If we had spaces before, print that and reset.
                     if prior_empty_count > 0 {
@@ -370,8 +594,10 @@ impl<'a> ChessGame<'a> {
                         prior_empty_count = 0;
                     }
 
-                    let color = color_option.expect("Piece returned without a color?");
-                    fen += &piece.to_char(color).to_string();
+                    fen += &piece.to_char().to_string();
+                    if get_bit(self.promoted_pieces, square) != 0 {
+                        fen += "~";
+                    }
                 }
                 None => {
                     prior_empty_count += 1;
@@ -395,6 +621,18 @@ impl<'a> ChessGame<'a> {
             }
         }
 
+        // Crazyhouse pockets, white then black.
+        if matches!(self.variant, Variant::Crazyhouse) {
+            fen += "[";
+            for side in [Color::White, Color::Black] {
+                for piece_type in POCKET_PIECE_TYPES.iter().rev() {
+                    let count = self.pockets[side.idx()][piece_type.bitboard_index()];
+                    fen += &piece_type.to_char(side).to_string().repeat(count);
+                }
+            }
+            fen += "]";
+        }
+
         // Add the game status (turn to move)
         if self.white_to_move {
             fen += " w ";
@@ -402,49 +640,71 @@ impl<'a> ChessGame<'a> {
             fen += " b ";
         }
 
-        // Castling rights.
-        if self.can_white_castle_short {
-            fen += "K";
-        }
-        if self.can_white_castle_long {
-            fen += "Q";
-        }
-        if self.can_black_castle_short {
-            fen += "k";
-        }
-        if self.can_black_castle_long {
-            fen += "q";
+        // Castling rights. Chess960 rooks that KQkq can't pin down get their file letter (X-FEN).
+        let castling_rights = [
+            (Color::White, CastleSides::Short, 'K'),
+            (Color::White, CastleSides::Long, 'Q'),
+            (Color::Black, CastleSides::Short, 'k'),
+            (Color::Black, CastleSides::Long, 'q'),
+        ];
+        let mut any_castling_rights = false;
+        for (side, castle_side, letter) in castling_rights {
+            let rights_index = castling_rights_index(side, castle_side);
+            if !self.has_castling_right(rights_index) {
+                continue;
+            }
+            any_castling_rights = true;
+
+            let rook_square = self.castling_rook_squares[rights_index];
+            if matches!(self.variant, Variant::Chess960)
+                && rook_square != self.find_castling_rook(side, castle_side)
+            {
+                let file_letter = (b'a' + (rook_square % 8) as u8) as char;
+                fen.push(match side {
+                    Color::White => file_letter.to_ascii_uppercase(),
+                    Color::Black => file_letter,
+                });
+            } else {
+                fen.push(letter);
+            }
         }
 
         // Special case if no casting rights available.
-        if !self.can_white_castle_short
-            && !self.can_white_castle_long
-            && !self.can_black_castle_short
-            && !self.can_black_castle_long
-        {
+        if !any_castling_rights {
             fen += "-";
         }
 
         // En-Passant Square.
         fen += " ";
         match self.en_passant_target {
-            Some(square) => fen += &square_to_coord(square),
+            Some(square) => fen += &square.to_string(),
             None => fen += "-",
         }
 
+        // Three-check. Checks each side still needs.
+        if matches!(self.variant, Variant::ThreeCheck) {
+            fen += &format!(
+                " {}+{}",
+                MAX_CHECK_COUNT - self.checks_given[Color::White.idx()],
+                MAX_CHECK_COUNT - self.checks_given[Color::Black.idx()]
+            );
+        }
+
         // Ehhh, at some point add the half moves since last capture or pawn advance. And the full move count.
 
         return fen;
     }
 
-    pub fn place_piece_on_board(&mut self, side: Color, piece_type: PieceType, square: usize) {
+    pub fn place_piece_on_board(&mut self, piece: Piece, square: Square) {
+        let square = square.index();
+
         // Piece bitboard.
-        let piece_bitboard_index = piece_type.bitboard_index() + side.piece_bitboard_offset();
+        let piece_bitboard_index = piece.bitboard_index();
         self.piece_bitboards[piece_bitboard_index] =
             set_bit(self.piece_bitboards[piece_bitboard_index], square);
 
         // Color occupancies.
-        let occupancy_bitboard_index = side.occupancy_bitboard_index();
+        let occupancy_bitboard_index = piece.color.occupancy_bitboard_index();
         self.occupancy_bitboards[occupancy_bitboard_index] =
             set_bit(self.occupancy_bitboards[occupancy_bitboard_index], square);
 
@@ -453,10 +713,11 @@ impl<'a> ChessGame<'a> {
     }
 
     // WARNING: Not efficient function??
-    pub fn get_piece_at_square(&self, square: usize) -> (Option<PieceType>, Option<Color>) {
+    pub fn get_piece_at_square(&self, square: Square) -> Option<Piece> {
+        let square = square.index();
         let is_occupied = get_bit(self.occupancy_bitboards[2], square) != 0;
         if !is_occupied {
-            return (None, None);
+            return None;
         }
 
         let is_occupied_white = get_bit(self.occupancy_bitboards[0], square) != 0;
@@ -476,19 +737,54 @@ impl<'a> ChessGame<'a> {
         // Loop over all the piece bitboards until we find the piece we want.
         for i in start_bitboard_index..start_bitboard_index + 6 {
             if get_bit(self.piece_bitboards[i], square) != 0 {
-                return (
-                    Some(PieceType::bitboard_index_to_piece_type(i)),
-                    Some(piece_color),
-                );
+                return Some(Piece::from_bitboard_index(i));
             }
         }
 
         panic!("Someting has gone very wrong. Looked at all bitboards and could not find a piece.");
     }
 
+    // Where `piece` stands.
+    pub fn pieces(&self, piece: Piece) -> Bitboard {
+        return Bitboard(self.piece_bitboards[piece.bitboard_index()]);
+    }
+
+    // Every piece, indexed like `Piece::bitboard_index`.
+    pub fn piece_bitboards(&self) -> [Bitboard; 12] {
+        return self.piece_bitboards.map(Bitboard);
+    }
+
+    // Crazyhouse. Pieces that were promoted, and go back to the pocket as pawns when captured.
+    pub fn promoted_pieces(&self) -> Bitboard {
+        return Bitboard(self.promoted_pieces);
+    }
+
+    // Where the rook for each castling right starts. White short, white long, black short, black long.
+    pub fn castling_rook_squares(&self) -> [Square; 4] {
+        return self.castling_rook_squares.map(Square::new);
+    }
+
+    // Crazyhouse. How many of `piece_type` `side` has in hand.
+    pub fn pocket(&self, side: Color, piece_type: PieceType) -> usize {
+        return self.pockets[side.idx()][piece_type.bitboard_index()];
+    }
+
+    // Three-check. How many checks `side` has given.
+    pub fn checks_given(&self, side: Color) -> usize {
+        return self.checks_given[side.idx()];
+    }
+
+    pub fn occupied(&self) -> Bitboard {
+        return Bitboard(self.occupancy_bitboards[2]);
+    }
+
+    pub fn occupied_by(&self, side: Color) -> Bitboard {
+        return Bitboard(self.occupancy_bitboards[side.occupancy_bitboard_index()]);
+    }
+
     // Bitwise operations make this pretty quick.
-    pub fn is_square_attacked(&self, square: usize, who_is_attacking: &Color) -> bool {
-        let piece_bitboard_offset = who_is_attacking.piece_bitboard_offset();
+    pub fn is_square_attacked(&self, square: Square, who_is_attacking: &Color) -> bool {
+        let square = square.index();
         let all_occupancies = self.occupancy_bitboards[2];
         let opponent_pawn_attacks_index = match who_is_attacking {
             Color::White => Color::Black.idx(),
@@ -497,15 +793,19 @@ impl<'a> ChessGame<'a> {
 
         // Pawns.
         if self.bitboard_constants.pawn_attacks[opponent_pawn_attacks_index][square]
-            & self.piece_bitboards[0 + piece_bitboard_offset]
+            & self
+                .pieces(Piece::new(*who_is_attacking, PieceType::Pawn))
+                .0
             != 0
         {
             return true;
         }
 
         // Bishops.
-        if self.get_bishop_attacks(square, all_occupancies)
-            & self.piece_bitboards[1 + piece_bitboard_offset]
+        if self.bishop_attacks(square, all_occupancies)
+            & self
+                .pieces(Piece::new(*who_is_attacking, PieceType::Bishop))
+                .0
             != 0
         {
             return true;
@@ -513,23 +813,29 @@ impl<'a> ChessGame<'a> {
 
         // Knights.
         if self.bitboard_constants.knight_attacks[square]
-            & self.piece_bitboards[2 + piece_bitboard_offset]
+            & self
+                .pieces(Piece::new(*who_is_attacking, PieceType::Knight))
+                .0
             != 0
         {
             return true;
         }
 
         // Rooks.
-        if self.get_rook_attacks(square, all_occupancies)
-            & self.piece_bitboards[3 + piece_bitboard_offset]
+        if self.rook_attacks(square, all_occupancies)
+            & self
+                .pieces(Piece::new(*who_is_attacking, PieceType::Rook))
+                .0
             != 0
         {
             return true;
         }
 
         // Queens. (we could speed this up slightly... look here for optimization if needed.)
-        if self.get_queen_attacks(square, all_occupancies)
-            & self.piece_bitboards[4 + piece_bitboard_offset]
+        if self.queen_attacks(square, all_occupancies)
+            & self
+                .pieces(Piece::new(*who_is_attacking, PieceType::Queen))
+                .0
             != 0
         {
             return true;
@@ -537,7 +843,9 @@ impl<'a> ChessGame<'a> {
 
         // King.
         if self.bitboard_constants.king_attacks[square]
-            & self.piece_bitboards[5 + piece_bitboard_offset]
+            & self
+                .pieces(Piece::new(*who_is_attacking, PieceType::King))
+                .0
             != 0
         {
             return true;
@@ -547,7 +855,7 @@ impl<'a> ChessGame<'a> {
         return false;
     }
 
-    pub fn get_bishop_attacks(&self, square: usize, mut occupancy: u64) -> u64 {
+    fn bishop_attacks(&self, square: usize, mut occupancy: u64) -> u64 {
         occupancy &= constants::BISHOP_MASKED_ATTACKS[square];
         (occupancy, _) = occupancy.overflowing_mul(constants::BISHOP_MAGIC_NUMBERS[square]);
         occupancy >>= 64 - constants::BISHOP_RELEVANT_BITS[square];
@@ -555,7 +863,7 @@ impl<'a> ChessGame<'a> {
         return self.bitboard_constants.bishop_attacks[square][occupancy as usize];
     }
 
-    pub fn get_rook_attacks(&self, square: usize, mut occupancy: u64) -> u64 {
+    fn rook_attacks(&self, square: usize, mut occupancy: u64) -> u64 {
         occupancy &= constants::ROOK_MASKED_ATTACKS[square];
         (occupancy, _) = occupancy.overflowing_mul(constants::ROOK_MAGIC_NUMBERS[square]);
         occupancy >>= 64 - constants::ROOK_RELEVANT_BITS[square];
@@ -563,13 +871,27 @@ impl<'a> ChessGame<'a> {
         return self.bitboard_constants.rook_attacks[square][occupancy as usize];
     }
 
-    pub fn get_queen_attacks(&self, square: usize, occupancy: u64) -> u64 {
-        return self.get_bishop_attacks(square, occupancy)
-            | self.get_rook_attacks(square, occupancy);
+    fn queen_attacks(&self, square: usize, occupancy: u64) -> u64 {
+        return self.bishop_attacks(square, occupancy) | self.rook_attacks(square, occupancy);
+    }
+
+    // Squares a bishop on `square` sees, with `occupancy` for blockers. Blockers themselves are included.
+    // Some table entries include the square they're for, so it's left out here.
+    pub fn get_bishop_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        return Bitboard(self.bishop_attacks(square.index(), occupancy.0)).without(square);
+    }
+
+    pub fn get_rook_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        return Bitboard(self.rook_attacks(square.index(), occupancy.0)).without(square);
+    }
+
+    pub fn get_queen_attacks(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        return Bitboard(self.queen_attacks(square.index(), occupancy.0)).without(square);
     }
 
     pub fn make_move(&mut self, this_move: &Move, update_legal_moves: bool) {
         //let debug_initial_game_state = self.clone();
+        let previous_piece_bitboards = self.piece_bitboards;
 
         let source_piece = this_move
             .from_piece_type
@@ -587,120 +909,157 @@ impl<'a> ChessGame<'a> {
             their_color = Color::White;
         }
 
-        let our_piece_bitboard_offset: usize = our_color.piece_bitboard_offset();
-        let our_piece_bitboard_index: usize =
-            our_piece_bitboard_offset + source_piece.bitboard_index();
+        let our_piece_bitboard_index: usize = Piece::new(our_color, source_piece).bitboard_index();
         let our_occupancies_index: usize = our_color.occupancy_bitboard_index();
-        let their_piece_bitboard_offset: usize = their_color.piece_bitboard_offset();
         let their_occupancies_index: usize = their_color.occupancy_bitboard_index();
 
+        // Crazyhouse. Note what is promoted before pieces move around.
+        let is_moving_promoted_piece =
+            !this_move.is_drop && get_bit(self.promoted_pieces, this_move.from_square.index()) != 0;
+
         // Remove our piece from it's starting square, and place it in the new spot.
-        // This does not handle castling, and en-passant logic.
+        // This does not handle castling, and en-passant logic. Drops come out of our pocket instead.
+        if this_move.is_drop {
+            self.remove_from_pocket(our_color, source_piece);
+        }
         match source_piece {
             PieceType::Pawn => {
-                self.piece_bitboards[our_piece_bitboard_index] = pop_bit(
-                    self.piece_bitboards[our_piece_bitboard_index],
-                    this_move.from_square,
-                );
-                self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                    [our_piece_bitboard_index][this_move.from_square];
+                if !this_move.is_drop {
+                    self.piece_bitboards[our_piece_bitboard_index] = pop_bit(
+                        self.piece_bitboards[our_piece_bitboard_index],
+                        this_move.from_square.index(),
+                    );
+                    self.zobrist_hash ^= self.bitboard_constants.zobrist_table
+                        [our_piece_bitboard_index][this_move.from_square.index()];
+                }
 
                 // Special logic for pawn promotion.
                 match this_move.pawn_promoting_to {
                     Some(piece_promoted_to) => {
                         match piece_promoted_to {
                             PieceType::Queen => {
-                                self.piece_bitboards[our_piece_bitboard_offset
-                                    + piece_promoted_to.bitboard_index()] = set_bit(
-                                    self.piece_bitboards[our_piece_bitboard_offset
-                                        + piece_promoted_to.bitboard_index()],
-                                    this_move.to_square,
-                                )
+                                self.piece_bitboards
+                                    [Piece::new(our_color, piece_promoted_to).bitboard_index()] =
+                                    set_bit(
+                                        self.piece_bitboards[Piece::new(
+                                            our_color,
+                                            piece_promoted_to,
+                                        )
+                                        .bitboard_index()],
+                                        this_move.to_square.index(),
+                                    )
                             }
                             PieceType::Rook => {
-                                self.piece_bitboards[our_piece_bitboard_offset
-                                    + piece_promoted_to.bitboard_index()] = set_bit(
-                                    self.piece_bitboards[our_piece_bitboard_offset
-                                        + piece_promoted_to.bitboard_index()],
-                                    this_move.to_square,
-                                )
+                                self.piece_bitboards
+                                    [Piece::new(our_color, piece_promoted_to).bitboard_index()] =
+                                    set_bit(
+                                        self.piece_bitboards[Piece::new(
+                                            our_color,
+                                            piece_promoted_to,
+                                        )
+                                        .bitboard_index()],
+                                        this_move.to_square.index(),
+                                    )
                             }
                             PieceType::Bishop => {
-                                self.piece_bitboards[our_piece_bitboard_offset
-                                    + piece_promoted_to.bitboard_index()] = set_bit(
-                                    self.piece_bitboards[our_piece_bitboard_offset
-                                        + piece_promoted_to.bitboard_index()],
-                                    this_move.to_square,
-                                )
+                                self.piece_bitboards
+                                    [Piece::new(our_color, piece_promoted_to).bitboard_index()] =
+                                    set_bit(
+                                        self.piece_bitboards[Piece::new(
+                                            our_color,
+                                            piece_promoted_to,
+                                        )
+                                        .bitboard_index()],
+                                        this_move.to_square.index(),
+                                    )
                             }
                             PieceType::Knight => {
-                                self.piece_bitboards[our_piece_bitboard_offset
-                                    + piece_promoted_to.bitboard_index()] = set_bit(
-                                    self.piece_bitboards[our_piece_bitboard_offset
-                                        + piece_promoted_to.bitboard_index()],
-                                    this_move.to_square,
-                                )
+                                self.piece_bitboards
+                                    [Piece::new(our_color, piece_promoted_to).bitboard_index()] =
+                                    set_bit(
+                                        self.piece_bitboards[Piece::new(
+                                            our_color,
+                                            piece_promoted_to,
+                                        )
+                                        .bitboard_index()],
+                                        this_move.to_square.index(),
+                                    )
+                            }
+                            // Antichess only.
+                            PieceType::King => {
+                                self.piece_bitboards
+                                    [Piece::new(our_color, piece_promoted_to).bitboard_index()] =
+                                    set_bit(
+                                        self.piece_bitboards[Piece::new(
+                                            our_color,
+                                            piece_promoted_to,
+                                        )
+                                        .bitboard_index()],
+                                        this_move.to_square.index(),
+                                    )
                             }
                             _ => panic!("Tried to promote to an illegal piece."),
                         }
                         self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                            [our_piece_bitboard_offset + piece_promoted_to.bitboard_index()]
-                            [this_move.to_square];
+                            [Piece::new(our_color, piece_promoted_to).bitboard_index()]
+                            [this_move.to_square.index()];
                     }
 
                     // Otherwise, it's a normal pawn move.
                     None => {
                         self.piece_bitboards[our_piece_bitboard_index] = set_bit(
                             self.piece_bitboards[our_piece_bitboard_index],
-                            this_move.to_square,
+                            this_move.to_square.index(),
                         );
                         self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                            [our_piece_bitboard_index][this_move.to_square];
+                            [our_piece_bitboard_index][this_move.to_square.index()];
                     }
                 }
             }
 
             // Every other piece, remove it from the source, place it at the destination.
             _ => {
-                self.piece_bitboards[our_piece_bitboard_index] = pop_bit(
-                    self.piece_bitboards[our_piece_bitboard_index],
-                    this_move.from_square,
-                );
+                if !this_move.is_drop {
+                    self.piece_bitboards[our_piece_bitboard_index] = pop_bit(
+                        self.piece_bitboards[our_piece_bitboard_index],
+                        this_move.from_square.index(),
+                    );
+                    self.zobrist_hash ^= self.bitboard_constants.zobrist_table
+                        [our_piece_bitboard_index][this_move.from_square.index()];
+                }
                 self.piece_bitboards[our_piece_bitboard_index] = set_bit(
                     self.piece_bitboards[our_piece_bitboard_index],
-                    this_move.to_square,
+                    this_move.to_square.index(),
                 );
-
                 self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                    [our_piece_bitboard_index][this_move.from_square];
-                self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                    [our_piece_bitboard_index][this_move.to_square];
+                    [our_piece_bitboard_index][this_move.to_square.index()];
             }
         }
 
         // Update our occupancies.
         self.occupancy_bitboards[our_occupancies_index] = pop_bit(
             self.occupancy_bitboards[our_occupancies_index],
-            this_move.from_square,
+            this_move.from_square.index(),
         );
         self.occupancy_bitboards[our_occupancies_index] = set_bit(
             self.occupancy_bitboards[our_occupancies_index],
-            this_move.to_square,
+            this_move.to_square.index(),
         );
 
         // Update all occupancies, source piece always moves.
-        self.occupancy_bitboards[2] = pop_bit(self.occupancy_bitboards[2], this_move.from_square);
+        self.occupancy_bitboards[2] =
+            pop_bit(self.occupancy_bitboards[2], this_move.from_square.index());
 
         // Figure out if we are capturing.
         let is_capture = this_move.to_piece_type.is_some();
         if !is_capture {
-            self.occupancy_bitboards[2] = set_bit(self.occupancy_bitboards[2], this_move.to_square);
+            self.occupancy_bitboards[2] =
+                set_bit(self.occupancy_bitboards[2], this_move.to_square.index());
         } else {
             let their_piece = this_move
                 .to_piece_type
                 .expect("Should be here, thanks to guard.");
-            let their_piece_bitboard_index =
-                their_piece.bitboard_index() + their_piece_bitboard_offset;
+            let their_piece_bitboard_index = Piece::new(their_color, their_piece).bitboard_index();
 
             // Remove their piece from the square; and update their occupancies.
             match their_piece {
@@ -709,21 +1068,21 @@ impl<'a> ChessGame<'a> {
                     if this_move.is_en_passant_capture {
                         // Place our pawn on the target square. Normal captures do not need to update this, but en-passant does.
                         self.occupancy_bitboards[2] =
-                            set_bit(self.occupancy_bitboards[2], this_move.to_square);
+                            set_bit(self.occupancy_bitboards[2], this_move.to_square.index());
                         let en_passant_target_pawn_index: usize = match their_color {
-                            Color::White => this_move.to_square - 8,
-                            Color::Black => this_move.to_square + 8,
+                            Color::White => this_move.to_square.index() - 8,
+                            Color::Black => this_move.to_square.index() + 8,
                         };
 
                         // Remove their pawn we captured en-passant.
                         self.piece_bitboards
-                            [their_piece_bitboard_offset + their_piece.bitboard_index()] = pop_bit(
+                            [Piece::new(their_color, their_piece).bitboard_index()] = pop_bit(
                             self.piece_bitboards
-                                [their_piece_bitboard_offset + their_piece.bitboard_index()],
+                                [Piece::new(their_color, their_piece).bitboard_index()],
                             en_passant_target_pawn_index,
                         );
                         self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                            [their_piece_bitboard_offset + their_piece.bitboard_index()]
+                            [Piece::new(their_color, their_piece).bitboard_index()]
                             [en_passant_target_pawn_index];
 
                         // Remove their occupancy.
@@ -739,15 +1098,15 @@ impl<'a> ChessGame<'a> {
                         // Remove that piece from the board.
                         self.piece_bitboards[their_piece_bitboard_index] = pop_bit(
                             self.piece_bitboards[their_piece_bitboard_index],
-                            this_move.to_square,
+                            this_move.to_square.index(),
                         );
                         self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                            [their_piece_bitboard_index][this_move.to_square];
+                            [their_piece_bitboard_index][this_move.to_square.index()];
 
                         // Update their occupancies.
                         self.occupancy_bitboards[their_occupancies_index] = pop_bit(
                             self.occupancy_bitboards[their_occupancies_index],
-                            this_move.to_square,
+                            this_move.to_square.index(),
                         );
                     }
                 }
@@ -757,37 +1116,52 @@ impl<'a> ChessGame<'a> {
                     // Remove that piece from the board.
                     self.piece_bitboards[their_piece_bitboard_index] = pop_bit(
                         self.piece_bitboards[their_piece_bitboard_index],
-                        this_move.to_square,
+                        this_move.to_square.index(),
                     );
                     self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                        [their_piece_bitboard_index][this_move.to_square];
+                        [their_piece_bitboard_index][this_move.to_square.index()];
 
                     // Update their occupancies.
                     self.occupancy_bitboards[their_occupancies_index] = pop_bit(
                         self.occupancy_bitboards[their_occupancies_index],
-                        this_move.to_square,
+                        this_move.to_square.index(),
                     );
                 }
             }
         }
 
+        // Crazyhouse. Captures go to our pocket, and promoted pieces stay marked as they move.
+        if matches!(self.variant, Variant::Crazyhouse) {
+            if let Some(their_piece) = this_move.to_piece_type {
+                match this_move.captures_promoted_piece == Some(true) {
+                    true => self.add_to_pocket(our_color, PieceType::Pawn),
+                    false => self.add_to_pocket(our_color, their_piece),
+                }
+            }
+
+            self.promoted_pieces = pop_bit(self.promoted_pieces, this_move.from_square.index());
+            self.promoted_pieces = pop_bit(self.promoted_pieces, this_move.to_square.index());
+            if is_moving_promoted_piece || this_move.pawn_promoting_to.is_some() {
+                self.promoted_pieces = set_bit(self.promoted_pieces, this_move.to_square.index());
+            }
+        }
+
+        // Atomic. Captures blow up the capturing piece and everything but pawns around it.
+        if matches!(self.variant, Variant::Atomic) && this_move.to_piece_type.is_some() {
+            self.explode(this_move.to_square.index());
+        }
+
         // Lastly, handle castling.
         match this_move.castle_side {
             None => (),
             Some(side) => {
-                let king_from_position = this_move.from_square;
-                let rook_from_position = match side {
-                    CastleSides::Short => king_from_position + 3,
-                    CastleSides::Long => king_from_position - 4,
-                };
-                let rook_to_position = match side {
-                    CastleSides::Short => king_from_position + 1,
-                    CastleSides::Long => king_from_position - 1,
-                };
+                let rook_from_position =
+                    self.castling_rook_squares[castling_rights_index(our_color, side)];
+                let (_, rook_to_position) =
+                    castling_target_squares(this_move.from_square.index(), side);
 
                 // Move our rook over.
-                let rook_bitboard_index =
-                    our_piece_bitboard_offset + PieceType::Rook.bitboard_index();
+                let rook_bitboard_index = Piece::new(our_color, PieceType::Rook).bitboard_index();
                 self.piece_bitboards[rook_bitboard_index] = pop_bit(
                     self.piece_bitboards[rook_bitboard_index],
                     rook_from_position,
@@ -800,21 +1174,8 @@ impl<'a> ChessGame<'a> {
                 self.zobrist_hash ^=
                     self.bitboard_constants.zobrist_table[rook_bitboard_index][rook_to_position];
 
-                // Update our occupancies.
-                self.occupancy_bitboards[our_occupancies_index] = pop_bit(
-                    self.occupancy_bitboards[our_occupancies_index],
-                    rook_from_position,
-                );
-                self.occupancy_bitboards[our_occupancies_index] = set_bit(
-                    self.occupancy_bitboards[our_occupancies_index],
-                    rook_to_position,
-                );
-
-                // Update global occupancies.
-                self.occupancy_bitboards[2] =
-                    pop_bit(self.occupancy_bitboards[2], rook_from_position);
-                self.occupancy_bitboards[2] =
-                    set_bit(self.occupancy_bitboards[2], rook_to_position);
+                // In Chess960 the king and rook can land on each other's squares, so rebuild occupancies from scratch.
+                self.rebuild_occupancies();
             }
         }
 
@@ -842,13 +1203,15 @@ impl<'a> ChessGame<'a> {
         // Update zobrist hash based on en-passant file.
         match this_move.next_en_passant_target_coord {
             Some(square) => {
-                self.zobrist_hash ^= self.bitboard_constants.zobrist_en_passant[square % 8];
+                self.zobrist_hash ^=
+                    self.bitboard_constants.zobrist_en_passant[square.file().index()];
             }
             None => (),
         }
         match this_move.last_en_passant_target_coord {
             Some(square) => {
-                self.zobrist_hash ^= self.bitboard_constants.zobrist_en_passant[square % 8];
+                self.zobrist_hash ^=
+                    self.bitboard_constants.zobrist_en_passant[square.file().index()];
             }
             None => (),
         }
@@ -857,6 +1220,19 @@ impl<'a> ChessGame<'a> {
         self.white_to_move = !self.white_to_move;
         self.zobrist_hash ^= self.bitboard_constants.zobrist_to_move;
 
+        // Three-check. Count the check we just gave.
+        if matches!(self.variant, Variant::ThreeCheck) {
+            let (checking_side, checked_side) = match self.white_to_move {
+                true => (Color::Black, Color::White),
+                false => (Color::White, Color::Black),
+            };
+            if self.is_king_attacked(&checked_side) {
+                self.add_check(checking_side);
+            }
+        }
+
+        self.push_nnue_accumulator(&previous_piece_bitboards);
+
         if update_legal_moves {
             // Update our moves!
             self.set_legal_moves(None);
@@ -869,6 +1245,27 @@ impl<'a> ChessGame<'a> {
     pub fn unmake_move(&mut self, this_move: &Move) {
         //let debug_initial_game_state = self.clone();
 
+        // The accumulator from before this move is still on the stack.
+        if self.nnue_network.is_some() {
+            self.nnue_accumulators.pop();
+        }
+
+        // Three-check. Take back the check this move gave, while the board still shows it.
+        if matches!(self.variant, Variant::ThreeCheck) {
+            let (checking_side, checked_side) = match self.white_to_move {
+                true => (Color::Black, Color::White),
+                false => (Color::White, Color::Black),
+            };
+            if self.is_king_attacked(&checked_side) {
+                self.remove_check(checking_side);
+            }
+        }
+
+        // Atomic. Put back whatever the capture blew up first, so the board looks like it did right after the capture.
+        if matches!(self.variant, Variant::Atomic) && this_move.to_piece_type.is_some() {
+            self.unexplode();
+        }
+
         let source_piece = this_move
             .from_piece_type
             .expect("This should always be here.");
@@ -886,72 +1283,103 @@ impl<'a> ChessGame<'a> {
             their_color = Color::White;
         }
 
-        let our_piece_bitboard_offset: usize = our_color.piece_bitboard_offset();
-        let our_piece_bitboard_index: usize =
-            our_piece_bitboard_offset + source_piece.bitboard_index();
+        let our_piece_bitboard_index: usize = Piece::new(our_color, source_piece).bitboard_index();
         let our_occupancies_index: usize = our_color.occupancy_bitboard_index();
-        let their_piece_bitboard_offset: usize = their_color.piece_bitboard_offset();
         let their_occupancies_index: usize = their_color.occupancy_bitboard_index();
 
-        // Place the piece back it's starting square.
+        // Place the piece back it's starting square. Drops go back to our pocket instead.
+        if this_move.is_drop {
+            self.add_to_pocket(our_color, source_piece);
+        }
         match source_piece {
             PieceType::Pawn => {
-                self.piece_bitboards[our_piece_bitboard_index] = set_bit(
-                    self.piece_bitboards[our_piece_bitboard_index],
-                    this_move.from_square,
-                );
-                self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                    [our_piece_bitboard_index][this_move.from_square];
+                if !this_move.is_drop {
+                    self.piece_bitboards[our_piece_bitboard_index] = set_bit(
+                        self.piece_bitboards[our_piece_bitboard_index],
+                        this_move.from_square.index(),
+                    );
+                    self.zobrist_hash ^= self.bitboard_constants.zobrist_table
+                        [our_piece_bitboard_index][this_move.from_square.index()];
+                }
 
                 // Special logic for pawn DEMOTION.
                 match this_move.pawn_promoting_to {
                     Some(piece_promoted_to) => {
                         match piece_promoted_to {
                             PieceType::Queen => {
-                                self.piece_bitboards[our_piece_bitboard_offset
-                                    + piece_promoted_to.bitboard_index()] = pop_bit(
-                                    self.piece_bitboards[our_piece_bitboard_offset
-                                        + piece_promoted_to.bitboard_index()],
-                                    this_move.to_square,
-                                )
+                                self.piece_bitboards
+                                    [Piece::new(our_color, piece_promoted_to).bitboard_index()] =
+                                    pop_bit(
+                                        self.piece_bitboards[Piece::new(
+                                            our_color,
+                                            piece_promoted_to,
+                                        )
+                                        .bitboard_index()],
+                                        this_move.to_square.index(),
+                                    )
                             }
                             PieceType::Rook => {
-                                self.piece_bitboards[our_piece_bitboard_offset
-                                    + piece_promoted_to.bitboard_index()] = pop_bit(
-                                    self.piece_bitboards[our_piece_bitboard_offset
-                                        + piece_promoted_to.bitboard_index()],
-                                    this_move.to_square,
-                                )
+                                self.piece_bitboards
+                                    [Piece::new(our_color, piece_promoted_to).bitboard_index()] =
+                                    pop_bit(
+                                        self.piece_bitboards[Piece::new(
+                                            our_color,
+                                            piece_promoted_to,
+                                        )
+                                        .bitboard_index()],
+                                        this_move.to_square.index(),
+                                    )
                             }
                             PieceType::Bishop => {
-                                self.piece_bitboards[our_piece_bitboard_offset
-                                    + piece_promoted_to.bitboard_index()] = pop_bit(
-                                    self.piece_bitboards[our_piece_bitboard_offset
-                                        + piece_promoted_to.bitboard_index()],
-                                    this_move.to_square,
-                                )
+                                self.piece_bitboards
+                                    [Piece::new(our_color, piece_promoted_to).bitboard_index()] =
+                                    pop_bit(
+                                        self.piece_bitboards[Piece::new(
+                                            our_color,
+                                            piece_promoted_to,
+                                        )
+                                        .bitboard_index()],
+                                        this_move.to_square.index(),
+                                    )
                             }
                             PieceType::Knight => {
-                                self.piece_bitboards[our_piece_bitboard_offset
-                                    + piece_promoted_to.bitboard_index()] = pop_bit(
-                                    self.piece_bitboards[our_piece_bitboard_offset
-                                        + piece_promoted_to.bitboard_index()],
-                                    this_move.to_square,
-                                )
+                                self.piece_bitboards
+                                    [Piece::new(our_color, piece_promoted_to).bitboard_index()] =
+                                    pop_bit(
+                                        self.piece_bitboards[Piece::new(
+                                            our_color,
+                                            piece_promoted_to,
+                                        )
+                                        .bitboard_index()],
+                                        this_move.to_square.index(),
+                                    )
+                            }
+                            // Antichess only.
+                            PieceType::King => {
+                                self.piece_bitboards
+                                    [Piece::new(our_color, piece_promoted_to).bitboard_index()] =
+                                    pop_bit(
+                                        self.piece_bitboards[Piece::new(
+                                            our_color,
+                                            piece_promoted_to,
+                                        )
+                                        .bitboard_index()],
+                                        this_move.to_square.index(),
+                                    )
                             }
                             _ => panic!("Tried to promote to an illegal piece."),
                         }
                         self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                            [our_piece_bitboard_offset + piece_promoted_to.bitboard_index()]
-                            [this_move.to_square];
+                            [Piece::new(our_color, piece_promoted_to).bitboard_index()]
+                            [this_move.to_square.index()];
                     }
                     None => {
                         self.piece_bitboards[our_piece_bitboard_index] = pop_bit(
                             self.piece_bitboards[our_piece_bitboard_index],
-                            this_move.to_square,
+                            this_move.to_square.index(),
                         );
                         self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                            [our_piece_bitboard_index][this_move.to_square];
+                            [our_piece_bitboard_index][this_move.to_square.index()];
                     }
                 }
             }
@@ -960,42 +1388,47 @@ impl<'a> ChessGame<'a> {
             _ => {
                 self.piece_bitboards[our_piece_bitboard_index] = pop_bit(
                     self.piece_bitboards[our_piece_bitboard_index],
-                    this_move.to_square,
-                );
-                self.piece_bitboards[our_piece_bitboard_index] = set_bit(
-                    self.piece_bitboards[our_piece_bitboard_index],
-                    this_move.from_square,
+                    this_move.to_square.index(),
                 );
                 self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                    [our_piece_bitboard_index][this_move.to_square];
-                self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                    [our_piece_bitboard_index][this_move.from_square];
+                    [our_piece_bitboard_index][this_move.to_square.index()];
+                if !this_move.is_drop {
+                    self.piece_bitboards[our_piece_bitboard_index] = set_bit(
+                        self.piece_bitboards[our_piece_bitboard_index],
+                        this_move.from_square.index(),
+                    );
+                    self.zobrist_hash ^= self.bitboard_constants.zobrist_table
+                        [our_piece_bitboard_index][this_move.from_square.index()];
+                }
             }
         }
 
         // Update our occupancies.
         self.occupancy_bitboards[our_occupancies_index] = pop_bit(
             self.occupancy_bitboards[our_occupancies_index],
-            this_move.to_square,
-        );
-        self.occupancy_bitboards[our_occupancies_index] = set_bit(
-            self.occupancy_bitboards[our_occupancies_index],
-            this_move.from_square,
+            this_move.to_square.index(),
         );
+        if !this_move.is_drop {
+            self.occupancy_bitboards[our_occupancies_index] = set_bit(
+                self.occupancy_bitboards[our_occupancies_index],
+                this_move.from_square.index(),
+            );
 
-        // Update all occupancies, source piece always moves.
-        self.occupancy_bitboards[2] = set_bit(self.occupancy_bitboards[2], this_move.from_square);
+            // Update all occupancies, source piece always moves.
+            self.occupancy_bitboards[2] =
+                set_bit(self.occupancy_bitboards[2], this_move.from_square.index());
+        }
 
         // Figure out if we are capturing.
         let is_capture = this_move.to_piece_type.is_some();
         if !is_capture {
-            self.occupancy_bitboards[2] = pop_bit(self.occupancy_bitboards[2], this_move.to_square);
+            self.occupancy_bitboards[2] =
+                pop_bit(self.occupancy_bitboards[2], this_move.to_square.index());
         } else {
             let their_piece = this_move
                 .to_piece_type
                 .expect("Should be here, thanks to guard.");
-            let their_piece_bitboard_index =
-                their_piece.bitboard_index() + their_piece_bitboard_offset;
+            let their_piece_bitboard_index = Piece::new(their_color, their_piece).bitboard_index();
 
             // Place their piece on the square; and update their occupancies.
             match their_piece {
@@ -1004,21 +1437,21 @@ impl<'a> ChessGame<'a> {
                     if this_move.is_en_passant_capture {
                         // Remove our pawn from the target square. Normal captures do not need to update this, but en-passant does.
                         self.occupancy_bitboards[2] =
-                            pop_bit(self.occupancy_bitboards[2], this_move.to_square);
+                            pop_bit(self.occupancy_bitboards[2], this_move.to_square.index());
                         let en_passant_target_pawn_index: usize = match their_color {
-                            Color::White => this_move.to_square - 8,
-                            Color::Black => this_move.to_square + 8,
+                            Color::White => this_move.to_square.index() - 8,
+                            Color::Black => this_move.to_square.index() + 8,
                         };
 
                         // Add their pawn we captured en-passant.
                         self.piece_bitboards
-                            [their_piece_bitboard_offset + their_piece.bitboard_index()] = set_bit(
+                            [Piece::new(their_color, their_piece).bitboard_index()] = set_bit(
                             self.piece_bitboards
-                                [their_piece_bitboard_offset + their_piece.bitboard_index()],
+                                [Piece::new(their_color, their_piece).bitboard_index()],
                             en_passant_target_pawn_index,
                         );
                         self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                            [their_piece_bitboard_offset + their_piece.bitboard_index()]
+                            [Piece::new(their_color, their_piece).bitboard_index()]
                             [en_passant_target_pawn_index];
 
                         // Add their occupancy.
@@ -1034,15 +1467,15 @@ impl<'a> ChessGame<'a> {
                         // Add that piece from the board.
                         self.piece_bitboards[their_piece_bitboard_index] = set_bit(
                             self.piece_bitboards[their_piece_bitboard_index],
-                            this_move.to_square,
+                            this_move.to_square.index(),
                         );
                         self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                            [their_piece_bitboard_index][this_move.to_square];
+                            [their_piece_bitboard_index][this_move.to_square.index()];
 
                         // Update their occupancies.
                         self.occupancy_bitboards[their_occupancies_index] = set_bit(
                             self.occupancy_bitboards[their_occupancies_index],
-                            this_move.to_square,
+                            this_move.to_square.index(),
                         );
                     }
                 }
@@ -1052,64 +1485,66 @@ impl<'a> ChessGame<'a> {
                     // Add that piece from the board.
                     self.piece_bitboards[their_piece_bitboard_index] = set_bit(
                         self.piece_bitboards[their_piece_bitboard_index],
-                        this_move.to_square,
+                        this_move.to_square.index(),
                     );
                     self.zobrist_hash ^= self.bitboard_constants.zobrist_table
-                        [their_piece_bitboard_index][this_move.to_square];
+                        [their_piece_bitboard_index][this_move.to_square.index()];
 
                     // Update their occupancies.
                     self.occupancy_bitboards[their_occupancies_index] = set_bit(
                         self.occupancy_bitboards[their_occupancies_index],
-                        this_move.to_square,
+                        this_move.to_square.index(),
                     );
                 }
             }
         }
 
+        // Crazyhouse. Take the capture back out of our pocket, and put the promoted markers back.
+        if matches!(self.variant, Variant::Crazyhouse) {
+            if let Some(their_piece) = this_move.to_piece_type {
+                match this_move.captures_promoted_piece == Some(true) {
+                    true => self.remove_from_pocket(our_color, PieceType::Pawn),
+                    false => self.remove_from_pocket(our_color, their_piece),
+                }
+            }
+
+            let was_moving_promoted_piece = !this_move.is_drop
+                && this_move.pawn_promoting_to.is_none()
+                && get_bit(self.promoted_pieces, this_move.to_square.index()) != 0;
+            self.promoted_pieces = pop_bit(self.promoted_pieces, this_move.to_square.index());
+            if was_moving_promoted_piece {
+                self.promoted_pieces = set_bit(self.promoted_pieces, this_move.from_square.index());
+            }
+            if this_move.captures_promoted_piece == Some(true) {
+                self.promoted_pieces = set_bit(self.promoted_pieces, this_move.to_square.index());
+            }
+        }
+
         // Lastly, handle castling.
         match this_move.castle_side {
             None => (),
             Some(side) => {
-                let king_from_position = this_move.from_square;
-                let rook_from_position = match side {
-                    CastleSides::Short => king_from_position + 3,
-                    CastleSides::Long => king_from_position - 4,
-                };
-                let rook_to_position = match side {
-                    CastleSides::Short => king_from_position + 1,
-                    CastleSides::Long => king_from_position - 1,
-                };
+                let rook_from_position =
+                    self.castling_rook_squares[castling_rights_index(our_color, side)];
+                let (_, rook_to_position) =
+                    castling_target_squares(this_move.from_square.index(), side);
 
                 // Move our rook back.
-                let rook_bitboard_index =
-                    our_piece_bitboard_offset + PieceType::Rook.bitboard_index();
+                let rook_bitboard_index = Piece::new(our_color, PieceType::Rook).bitboard_index();
+                self.piece_bitboards[rook_bitboard_index] =
+                    pop_bit(self.piece_bitboards[rook_bitboard_index], rook_to_position);
                 self.piece_bitboards[rook_bitboard_index] = set_bit(
                     self.piece_bitboards[rook_bitboard_index],
                     rook_from_position,
                 );
-                self.piece_bitboards[rook_bitboard_index] =
-                    pop_bit(self.piece_bitboards[rook_bitboard_index], rook_to_position);
 
                 self.zobrist_hash ^=
                     self.bitboard_constants.zobrist_table[rook_bitboard_index][rook_from_position];
                 self.zobrist_hash ^=
                     self.bitboard_constants.zobrist_table[rook_bitboard_index][rook_to_position];
 
-                // Update our occupancies.
-                self.occupancy_bitboards[our_occupancies_index] = set_bit(
-                    self.occupancy_bitboards[our_occupancies_index],
-                    rook_from_position,
-                );
-                self.occupancy_bitboards[our_occupancies_index] = pop_bit(
-                    self.occupancy_bitboards[our_occupancies_index],
-                    rook_to_position,
-                );
-
-                // Update global occupancies.
-                self.occupancy_bitboards[2] =
-                    set_bit(self.occupancy_bitboards[2], rook_from_position);
-                self.occupancy_bitboards[2] =
-                    pop_bit(self.occupancy_bitboards[2], rook_to_position);
+                // In Chess960 the king and rook can land on each other's squares, so rebuild occupancies from scratch.
+                self.rebuild_occupancies();
             }
         }
 
@@ -1137,13 +1572,15 @@ impl<'a> ChessGame<'a> {
         // Update zobrist hash based on en-passant file.
         match this_move.next_en_passant_target_coord {
             Some(square) => {
-                self.zobrist_hash ^= self.bitboard_constants.zobrist_en_passant[square % 8];
+                self.zobrist_hash ^=
+                    self.bitboard_constants.zobrist_en_passant[square.file().index()];
             }
             None => (),
         }
         match this_move.last_en_passant_target_coord {
             Some(square) => {
-                self.zobrist_hash ^= self.bitboard_constants.zobrist_en_passant[square % 8];
+                self.zobrist_hash ^=
+                    self.bitboard_constants.zobrist_en_passant[square.file().index()];
             }
             None => (),
         }
@@ -1207,8 +1644,9 @@ impl<'a> ChessGame<'a> {
     }
 
     pub fn is_king_attacked(&self, side_attacked: &Color) -> bool {
-        let king_bitboard_index = 5 + side_attacked.piece_bitboard_offset();
-        let king_square = get_lsb_index(self.piece_bitboards[king_bitboard_index])
+        let king_square = self
+            .pieces(Piece::new(*side_attacked, PieceType::King))
+            .lsb()
             .expect("King must be on board.");
         return match side_attacked {
             Color::White => self.is_square_attacked(king_square, &Color::Black),
@@ -1216,61 +1654,857 @@ impl<'a> ChessGame<'a> {
         };
     }
 
-    pub fn is_checkmate(&self) -> bool {
-        // If you have a legal move, you are not in checkmate.
-        if self.legal_moves.len() != 0 {
-            return false;
-        }
+    // King square castling is measured from. Falls back to the e-file if the king isn't on its back rank.
+    fn castling_king_square(&self, side: Color) -> usize {
+        let kings = self.piece_bitboards[Piece::new(side, PieceType::King).bitboard_index()];
+        return match get_lsb_index(kings) {
+            Some(square) if square / 8 == back_rank_start(side) / 8 => square,
+            _ => back_rank_start(side) + 4,
+        };
+    }
 
-        let our_color: Color;
-        if self.white_to_move {
-            our_color = Color::White;
-        } else {
-            our_color = Color::Black;
+    // Outermost rook on the back rank, on the given side of the king. This is what K and Q mean in X-FEN.
+    fn find_castling_rook(&self, side: Color, castle_side: CastleSides) -> usize {
+        let rooks = self.piece_bitboards[Piece::new(side, PieceType::Rook).bitboard_index()];
+        let rank_start = back_rank_start(side);
+        let king_file = self.castling_king_square(side) % 8;
+
+        let files: Vec<usize> = match castle_side {
+            CastleSides::Short => (king_file + 1..8).rev().collect(),
+            CastleSides::Long => (0..king_file).collect(),
+        };
+        for file in files {
+            if get_bit(rooks, rank_start + file) != 0 {
+                return rank_start + file;
+            }
         }
 
-        return self.is_king_attacked(&our_color);
+        // No rook there, assume the corner like standard chess.
+        return match castle_side {
+            CastleSides::Short => rank_start + 7,
+            CastleSides::Long => rank_start,
+        };
     }
 
-    pub fn is_stalemate(&self) -> bool {
-        // If you have a legal move, you are not in checkmate.
-        if self.legal_moves.len() != 0 {
-            return false;
-        }
+    fn has_castling_right(&self, rights_index: usize) -> bool {
+        return match rights_index {
+            0 => self.can_white_castle_short,
+            1 => self.can_white_castle_long,
+            2 => self.can_black_castle_short,
+            _ => self.can_black_castle_long,
+        };
+    }
 
-        let our_color: Color;
-        if self.white_to_move {
-            our_color = Color::White;
-        } else {
-            our_color = Color::Black;
+    fn set_castling_right(&mut self, rights_index: usize, value: bool) {
+        match rights_index {
+            0 => self.can_white_castle_short = value,
+            1 => self.can_white_castle_long = value,
+            2 => self.can_black_castle_short = value,
+            _ => self.can_black_castle_long = value,
         }
-
-        return !self.is_king_attacked(&our_color);
     }
 
-    pub fn evaluate_board(&self) -> i64 {
-        // Variables shared by both functions.
-        let mut square: usize;
-        let mut occupancies: u64;
-        let mut white_piece_value_total: i64 = 0;
-        let mut black_piece_value_total: i64 = 0;
-
-        // Add up white pieces.
-        occupancies = self.occupancy_bitboards[Color::White.occupancy_bitboard_index()];
-        while occupancies != 0 {
-            square = get_lsb_index(occupancies).expect("Guard clause.");
-            let (piece_wrapped, _) = self.get_piece_at_square(square);
-            let piece = piece_wrapped.expect("Not empty (white piece).");
-            white_piece_value_total += piece.piece_base_value();
-            white_piece_value_total += piece.piece_happy_square_value(square, true);
-            occupancies = pop_bit(occupancies, square)
+    /**
+     * The zobrist hash of the position, worked out from scratch. `zobrist_hash` is kept up to date move by move
+     * instead, and should always match this.
+     */
+    pub fn compute_zobrist(&self) -> u64 {
+        let mut hash = 0;
+        for (bitboard_index, bitboard) in self.piece_bitboards.iter().enumerate() {
+            for square in Bitboard(*bitboard).squares() {
+                hash ^= self.bitboard_constants.zobrist_table[bitboard_index][square.index()];
+            }
+        }
+
+        if !self.white_to_move {
+            hash ^= self.bitboard_constants.zobrist_to_move;
+        }
+        for rights_index in 0..4 {
+            if self.has_castling_right(rights_index) {
+                hash ^= self.bitboard_constants.zobrist_castling_rights[rights_index];
+            }
+        }
+        if let Some(square) = self.en_passant_target {
+            hash ^= self.bitboard_constants.zobrist_en_passant[square.file().index()];
+        }
+
+        for side in [Color::White, Color::Black] {
+            for (piece_index, count) in self.pockets[side.idx()].iter().enumerate() {
+                hash ^= self.bitboard_constants.zobrist_pockets[side.idx()][piece_index][*count];
+            }
+            hash ^=
+                self.bitboard_constants.zobrist_checks[side.idx()][self.checks_given[side.idx()]];
+        }
+
+        return hash;
+    }
+
+    // Sizes the transposition table to roughly `megabytes` when full, and empties it.
+    pub fn set_transposition_table_mb(&mut self, megabytes: usize) {
+        let entry_size = std::mem::size_of::<(u64, TranspositionTableEntry)>();
+        self.transposition_table_size = ((megabytes * 1024 * 1024 / entry_size) as u64).max(1);
+        self.transposition_table.clear();
+    }
+
+    // Crazyhouse. Put a piece in `side`'s hand, keeping the hash up to date.
+    pub fn add_to_pocket(&mut self, side: Color, piece_type: PieceType) {
+        let count = &mut self.pockets[side.idx()][piece_type.bitboard_index()];
+        let keys =
+            &self.bitboard_constants.zobrist_pockets[side.idx()][piece_type.bitboard_index()];
+        self.zobrist_hash ^= keys[*count] ^ keys[*count + 1];
+        *count += 1;
+    }
+
+    // Crazyhouse. Take a piece out of `side`'s hand, keeping the hash up to date.
+    pub fn remove_from_pocket(&mut self, side: Color, piece_type: PieceType) {
+        let count = &mut self.pockets[side.idx()][piece_type.bitboard_index()];
+        let keys =
+            &self.bitboard_constants.zobrist_pockets[side.idx()][piece_type.bitboard_index()];
+        self.zobrist_hash ^= keys[*count] ^ keys[*count - 1];
+        *count -= 1;
+    }
+
+    pub fn has_empty_pockets(&self) -> bool {
+        return self.pockets.iter().flatten().all(|count| *count == 0);
+    }
+
+    // Three-check. Count a check given by `side`, keeping the hash up to date.
+    fn add_check(&mut self, side: Color) {
+        let count = &mut self.checks_given[side.idx()];
+        let keys = &self.bitboard_constants.zobrist_checks[side.idx()];
+        self.zobrist_hash ^= keys[*count] ^ keys[*count + 1];
+        *count += 1;
+    }
+
+    // Three-check. Undo `add_check`.
+    fn remove_check(&mut self, side: Color) {
+        let count = &mut self.checks_given[side.idx()];
+        let keys = &self.bitboard_constants.zobrist_checks[side.idx()];
+        self.zobrist_hash ^= keys[*count] ^ keys[*count - 1];
+        *count -= 1;
+    }
+
+    // Atomic. Removes every piece on `square`, and every non-pawn next to it.
+    fn explode(&mut self, square: usize) {
+        let blast_squares = set_bit(self.bitboard_constants.king_attacks[square], square);
+        let mut exploded: Vec<(usize, usize)> = vec![];
+
+        for bitboard_index in 0..12 {
+            let mut victims = self.piece_bitboards[bitboard_index] & blast_squares;
+            if bitboard_index % 6 == PieceType::Pawn.bitboard_index() {
+                victims &= set_bit(0, square);
+            }
+
+            while victims != 0 {
+                let victim_square =
+                    get_lsb_index(victims).expect("Guard before should handle this.");
+                self.piece_bitboards[bitboard_index] =
+                    pop_bit(self.piece_bitboards[bitboard_index], victim_square);
+                self.zobrist_hash ^=
+                    self.bitboard_constants.zobrist_table[bitboard_index][victim_square];
+                exploded.push((bitboard_index, victim_square));
+                victims = pop_bit(victims, victim_square);
+            }
+        }
+
+        self.rebuild_occupancies();
+        self.explosions.push(exploded);
+    }
+
+    // Atomic. Undoes the last `explode`.
+    fn unexplode(&mut self) {
+        let exploded = self
+            .explosions
+            .pop()
+            .expect("Unmaking a capture that never exploded.");
+        for (bitboard_index, square) in exploded.iter() {
+            self.piece_bitboards[*bitboard_index] =
+                set_bit(self.piece_bitboards[*bitboard_index], *square);
+            self.zobrist_hash ^= self.bitboard_constants.zobrist_table[*bitboard_index][*square];
+        }
+
+        self.rebuild_occupancies();
+    }
+
+    // Occupancies straight from the piece bitboards.
+    fn rebuild_occupancies(&mut self) {
+        self.occupancy_bitboards[0] = self.piece_bitboards[0..6].iter().fold(0, |a, b| a | b);
+        self.occupancy_bitboards[1] = self.piece_bitboards[6..12].iter().fold(0, |a, b| a | b);
+        self.occupancy_bitboards[2] = self.occupancy_bitboards[0] | self.occupancy_bitboards[1];
+    }
+
+    /**
+     * UCI notation for a move in this game. Standard chess castles with the king's destination (e1g1),
+     * Chess960 with the king taking its own rook (e1h1), since the destination can be ambiguous there.
+     */
+    pub fn move_to_uci(&self, this_move: &Move) -> String {
+        let castle_side = match (self.variant, this_move.castle_side) {
+            (Variant::Chess960, Some(castle_side)) => castle_side,
+            _ => return this_move.move_to_str(),
+        };
+
+        let side = match this_move.from_square.index() / 8 == back_rank_start(Color::White) / 8 {
+            true => Color::White,
+            false => Color::Black,
+        };
+        let rook_square = self.castling_rook_squares[castling_rights_index(side, castle_side)];
+
+        return format!(
+            "{}{}",
+            square_to_coord(this_move.from_square.index()),
+            square_to_coord(rook_square)
+        );
+    }
+
+    // Finds the legal move written as `uci` (see `move_to_uci`). Legal moves must already be set.
+    pub fn uci_to_legal_move(&self, uci: &str) -> Result<Move, IllegalMoveError> {
+        return match self.legal_moves.iter().find(|m| self.move_to_uci(m) == uci) {
+            Some(m) => Ok(*m),
+            None => Err(IllegalMoveError::NotLegal(uci.to_string())),
+        };
+    }
+
+    /**
+     * Standard Algebraic Notation for a legal move: `Nbd7`, `exd5`, `e8=Q+`, `O-O-O#`, or `N@f3` for drops.
+     * Legal moves must already be set, they're what tells us whether another piece could reach the same square.
+     */
+    pub fn move_to_san(&mut self, this_move: &Move) -> String {
+        let mut san = self.move_to_san_without_suffix(this_move);
+
+        // Play the move to see if it checks or mates. Making the move replaces the legal moves, so put ours back after.
+        let legal_moves = std::mem::take(&mut self.legal_moves);
+        self.make_move(this_move, true);
+        let their_side = match self.white_to_move {
+            true => Color::White,
+            false => Color::Black,
+        };
+        if self.is_checkmate() {
+            san.push('#');
+        } else if self.is_in_check(&their_side) {
+            san.push('+');
+        }
+        self.unmake_move(this_move);
+        self.legal_moves = legal_moves;
+
+        return san;
+    }
+
+    fn move_to_san_without_suffix(&self, this_move: &Move) -> String {
+        let to = this_move.to_square;
+        let piece_type = this_move
+            .from_piece_type
+            .expect("Legal moves know what is moving.");
+
+        // Pawn drops leave out the piece letter, like `@e4`.
+        if this_move.is_drop {
+            return match piece_type {
+                PieceType::Pawn => format!("@{to}"),
+                _ => format!("{}@{to}", piece_type.to_char(Color::White)),
+            };
+        }
+
+        match this_move.castle_side {
+            Some(CastleSides::Short) => return "O-O".to_string(),
+            Some(CastleSides::Long) => return "O-O-O".to_string(),
+            None => (),
+        }
+
+        let from = this_move.from_square;
+        let capture = match this_move.to_piece_type.is_some() {
+            true => "x",
+            false => "",
+        };
+        let promotion = match this_move.pawn_promoting_to {
+            Some(t) => format!("={}", t.to_char(Color::White)),
+            None => String::new(),
+        };
+
+        // Pawn captures always say which file they came from.
+        if piece_type == PieceType::Pawn {
+            return match capture.is_empty() {
+                true => format!("{to}{promotion}"),
+                false => format!("{}x{to}{promotion}", from.file().to_char()),
+            };
+        }
+
+        // Other pieces of the same type that could also go there.
+        let rivals: Vec<Square> = self
+            .legal_moves
+            .iter()
+            .filter(|m| {
+                !m.is_drop
+                    && m.castle_side.is_none()
+                    && m.from_piece_type == Some(piece_type)
+                    && m.to_square == to
+                    && m.from_square != from
+            })
+            .map(|m| m.from_square)
+            .collect();
+
+        // The file if that's enough, then the rank, then both.
+        let disambiguation = if rivals.is_empty() {
+            String::new()
+        } else if rivals.iter().all(|s| s.file() != from.file()) {
+            from.file().to_char().to_string()
+        } else if rivals.iter().all(|s| s.rank() != from.rank()) {
+            from.rank().to_char().to_string()
+        } else {
+            from.to_string()
+        };
+
+        return format!(
+            "{}{disambiguation}{capture}{to}{promotion}",
+            piece_type.to_char(Color::White)
+        );
+    }
+
+    /**
+     * Finds the legal move written as `san`, see `move_to_san`. Legal moves must already be set.
+     * Lenient about the usual variations: `0-0` castling, a missing `x`, check marks, annotations like `!?`,
+     * a missing `=` before the promotion piece, and long algebraic like `Ng1-f3`.
+     */
+    pub fn parse_san(&self, san: &str) -> Result<Move, IllegalMoveError> {
+        let not_legal = || IllegalMoveError::NotLegal(san.to_string());
+        let text = san
+            .trim()
+            .trim_end_matches(|c| "+#!?".contains(c))
+            .trim_end_matches("e.p.")
+            .trim();
+
+        // Castling.
+        let castle_side = match text.replace('0', "O").as_str() {
+            "O-O" => Some(CastleSides::Short),
+            "O-O-O" => Some(CastleSides::Long),
+            _ => None,
+        };
+        if castle_side.is_some() {
+            let castle_side_matches = |m: &&Move| {
+                matches!(
+                    (m.castle_side, castle_side),
+                    (Some(CastleSides::Short), Some(CastleSides::Short))
+                        | (Some(CastleSides::Long), Some(CastleSides::Long))
+                )
+            };
+            return match self.legal_moves.iter().find(castle_side_matches) {
+                Some(m) => Ok(*m),
+                None => Err(not_legal()),
+            };
+        }
+
+        // Drops.
+        if let Some((piece, square)) = text.split_once('@') {
+            let piece_type = match piece {
+                "" => PieceType::Pawn,
+                p if p.len() == 1 => PieceType::char_to_piece_type(p.chars().next().unwrap())
+                    .map_err(|_| not_legal())?,
+                _ => return Err(not_legal()),
+            };
+            let square: Square = square.parse().map_err(|_| not_legal())?;
+            return match self.legal_moves.iter().find(|m| {
+                m.is_drop && m.from_piece_type == Some(piece_type) && m.to_square == square
+            }) {
+                Some(m) => Ok(*m),
+                None => Err(not_legal()),
+            };
+        }
+
+        let mut chars: Vec<char> = text.chars().filter(|c| !"x:-=".contains(*c)).collect();
+
+        // Piece letters are upper case, so a lower case `b` is always the b-file.
+        let piece_type = match chars.first() {
+            Some(c) if "KQRBNP".contains(*c) => {
+                let piece_type = PieceType::char_to_piece_type(*c).map_err(|_| not_legal())?;
+                chars.remove(0);
+                piece_type
+            }
+            _ => PieceType::Pawn,
+        };
+
+        // Promotion piece at the end, with or without the `=`.
+        let mut promotion: Option<PieceType> = None;
+        if let Some(c) = chars.last() {
+            if c.is_ascii_alphabetic() && chars.len() > 2 {
+                promotion = Some(PieceType::char_to_piece_type(*c).map_err(|_| not_legal())?);
+                chars.pop();
+            }
+        }
+
+        // The destination is the last two characters, anything before it narrows down where the piece came from.
+        if chars.len() < 2 {
+            return Err(not_legal());
+        }
+        let destination: String = chars[chars.len() - 2..].iter().collect();
+        let to: Square = destination.parse().map_err(|_| not_legal())?;
+        let mut from_file: Option<File> = None;
+        let mut from_rank: Option<Rank> = None;
+        for c in chars[..chars.len() - 2].iter() {
+            if let Ok(file) = File::from_char(*c) {
+                from_file = Some(file);
+            } else if let Ok(rank) = Rank::from_char(*c) {
+                from_rank = Some(rank);
+            } else {
+                return Err(not_legal());
+            }
+        }
+
+        // Castling is only ever `O-O` or `O-O-O`, even when the king lands where an ordinary king move would.
+        let candidates: Vec<&Move> = self
+            .legal_moves
+            .iter()
+            .filter(|m| {
+                !m.is_drop
+                    && m.castle_side.is_none()
+                    && m.from_piece_type == Some(piece_type)
+                    && m.to_square == to
+                    && m.pawn_promoting_to == promotion
+                    && from_file.is_none_or(|f| m.from_square.file() == f)
+                    && from_rank.is_none_or(|r| m.from_square.rank() == r)
+            })
+            .collect();
+
+        return match candidates.len() {
+            0 => Err(not_legal()),
+            1 => Ok(*candidates[0]),
+            _ => Err(IllegalMoveError::Ambiguous(san.to_string())),
+        };
+    }
+
+    /**
+     * Plays `this_move` if it's legal here, without trusting the caller like `make_move` does.
+     * Only from/to squares, promotion and drop piece need to be filled in (see `Move::str_to_move`).
+     * Returns the move as it was played, with everything `unmake_move` needs.
+     */
+    pub fn try_make_move(&mut self, this_move: &Move) -> Result<Move, IllegalMoveError> {
+        let legal_moves = self.get_legal_moves();
+        if legal_moves.is_empty() {
+            return Err(IllegalMoveError::NoLegalMoves);
+        }
+
+        let legal_move = match legal_moves.iter().find(|m| *m == this_move) {
+            Some(m) => *m,
+            None => return Err(IllegalMoveError::NotLegal(this_move.move_to_str())),
+        };
+        self.make_move(&legal_move, true);
+
+        return Ok(legal_move);
+    }
+
+    // Number of leaf nodes `depth` plies down. Used to check the move generator against known counts.
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.get_legal_moves();
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        let mut nodes: u64 = 0;
+        for m in moves.iter() {
+            self.make_move(m, false);
+            nodes += self.perft(depth - 1);
+            self.unmake_move(m);
+        }
+
+        return nodes;
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        let our_color: Color;
+        if self.white_to_move {
+            our_color = Color::White;
+        } else {
+            our_color = Color::Black;
+        }
+
+        match self.variant {
+            // There is no royal king, running out of moves wins instead.
+            Variant::Antichess => return false,
+
+            // Getting your king blown up loses, same as being mated.
+            Variant::Atomic if !self.has_king(&our_color) => return true,
+            _ => (),
+        }
+
+        // Won some other way, see `variant_result`.
+        if self.variant_result().is_over() {
+            return false;
+        }
+
+        // If you have a legal move, you are not in checkmate.
+        if self.legal_moves.len() != 0 {
+            return false;
+        }
+
+        return self.is_in_check(&our_color);
+    }
+
+    pub fn is_stalemate(&self) -> bool {
+        // Antichess stalemates are wins for the side that can't move.
+        if matches!(self.variant, Variant::Antichess) {
+            return false;
+        }
+
+        // If you have a legal move, you are not in checkmate.
+        if self.legal_moves.len() != 0 {
+            return false;
+        }
+
+        let our_color: Color;
+        if self.white_to_move {
+            our_color = Color::White;
+        } else {
+            our_color = Color::Black;
+        }
+
+        // Games won by a variant rule (a king blown up, on the hill, ...) are not stalemates either.
+        if self.variant_result().is_over() {
+            return false;
+        }
+
+        return !self.is_in_check(&our_color);
+    }
+
+    // Antichess. The side to move wins once it has no legal moves, whether it lost all its pieces or is stalemated.
+    pub fn is_antichess_win(&self) -> bool {
+        return matches!(self.variant, Variant::Antichess) && self.legal_moves.len() == 0;
+    }
+
+    pub fn has_king(&self, side: &Color) -> bool {
+        return self.king_bitboard(side) != 0;
+    }
+
+    /**
+     * Whether `side` is in check under the rules of the variant we are playing.
+     * Antichess has no check, and in atomic a king next to the other king can't be taken (the capture would blow up both).
+     */
+    pub fn is_in_check(&self, side: &Color) -> bool {
+        return match self.variant {
+            Variant::Antichess => false,
+            Variant::Atomic => {
+                let their_side = match side {
+                    Color::White => Color::Black,
+                    Color::Black => Color::White,
+                };
+                let our_king =
+                    self.piece_bitboards[Piece::new(*side, PieceType::King).bitboard_index()];
+                let their_king =
+                    self.piece_bitboards[Piece::new(their_side, PieceType::King).bitboard_index()];
+                let our_king_square = match get_lsb_index(our_king) {
+                    Some(square) => square,
+                    None => return false,
+                };
+
+                their_king != 0
+                    && self.bitboard_constants.king_attacks[our_king_square] & their_king == 0
+                    && self.is_king_attacked(side)
+            }
+            _ => self.is_king_attacked(side),
+        };
+    }
+
+    // Called with the move already made. Could the side that just moved (`our_side`) actually play it?
+    fn is_legal_after_move(&self, our_side: &Color, their_side: &Color) -> bool {
+        return match self.variant {
+            Variant::Antichess => true,
+
+            // Nobody is allowed to give check either.
+            Variant::RacingKings => {
+                !self.is_king_attacked(our_side) && !self.is_king_attacked(their_side)
+            }
+
+            // Blowing up their king wins on the spot, even if ours is attacked. Blowing up our own never works.
+            Variant::Atomic => {
+                self.has_king(our_side)
+                    && (!self.has_king(their_side) || !self.is_in_check(our_side))
+            }
+            _ => !self.is_king_attacked(our_side),
+        };
+    }
+
+    /**
+     * Bare kings, or a single minor piece against a bare king, so neither side can ever mate.
+     * Only for standard rules. Crazyhouse pockets refill and the other variants are won in other ways.
+     */
+    pub fn is_insufficient_material(&self) -> bool {
+        if !matches!(self.variant, Variant::Standard | Variant::Chess960) {
+            return false;
+        }
+
+        let kings = self.pieces(Piece::new(Color::White, PieceType::King))
+            | self.pieces(Piece::new(Color::Black, PieceType::King));
+        let everything_else = self.occupied() & !kings;
+        if everything_else.is_empty() {
+            return true;
+        }
+
+        let mut minors = Bitboard::EMPTY;
+        for color in [Color::White, Color::Black] {
+            for piece_type in [PieceType::Bishop, PieceType::Knight] {
+                minors |= self.pieces(Piece::new(color, piece_type));
+            }
+        }
+
+        return everything_else == minors && minors.count() == 1;
+    }
+
+    // White-relative score for when the side to move has no legal moves.
+    fn no_legal_moves_evaluation(&self) -> i64 {
+        return match self.game_result() {
+            GameResult::WhiteWins => std::i64::MAX,
+            GameResult::BlackWins => std::i64::MIN,
+            _ => 0,
+        };
+    }
+
+    /**
+     * How the game stands. Checks the variant's own win conditions first, then mate and stalemate.
+     * Relies on `legal_moves` being up to date.
+     */
+    pub fn game_result(&self) -> GameResult {
+        let variant_result = self.variant_result();
+        if variant_result.is_over() {
+            return variant_result;
+        }
+
+        if self.legal_moves.len() != 0 {
+            return GameResult::Ongoing;
+        }
+
+        let (our_side, their_side) = match self.white_to_move {
+            true => (Color::White, Color::Black),
+            false => (Color::Black, Color::White),
+        };
+        if self.is_antichess_win() {
+            return GameResult::win_for(our_side);
+        }
+        if self.is_checkmate() {
+            return GameResult::win_for(their_side);
+        }
+
+        return GameResult::Draw;
+    }
+
+    /**
+     * Wins that don't need us to run out of moves: a king blown up (atomic), three checks, a king on the hill,
+     * or a king on the eighth rank (racing kings). Once one of these happens there are no legal moves left.
+     */
+    pub fn variant_result(&self) -> GameResult {
+        match self.variant {
+            Variant::Atomic => {
+                if !self.has_king(&Color::White) {
+                    return GameResult::BlackWins;
+                }
+                if !self.has_king(&Color::Black) {
+                    return GameResult::WhiteWins;
+                }
+            }
+            Variant::ThreeCheck => {
+                for side in [Color::White, Color::Black] {
+                    if self.checks_given[side.idx()] >= MAX_CHECK_COUNT {
+                        return GameResult::win_for(side);
+                    }
+                }
+            }
+            Variant::KingOfTheHill => {
+                for side in [Color::White, Color::Black] {
+                    if self.king_bitboard(&side) & CENTRE_SQUARES != 0 {
+                        return GameResult::win_for(side);
+                    }
+                }
+            }
+            Variant::RacingKings => return self.racing_kings_result(),
+            _ => (),
+        }
+
+        return GameResult::Ongoing;
+    }
+
+    // Racing Kings. Black moves second, so if white gets there first black has one move to reach the goal too and draw.
+    fn racing_kings_result(&self) -> GameResult {
+        let white_in_goal = self.king_bitboard(&Color::White) & EIGHTH_RANK != 0;
+        let black_in_goal = self.king_bitboard(&Color::Black) & EIGHTH_RANK != 0;
+
+        return match (white_in_goal, black_in_goal) {
+            (true, true) => GameResult::Draw,
+            (false, true) => GameResult::BlackWins,
+            (false, false) => GameResult::Ongoing,
+            (true, false) => {
+                if self.white_to_move {
+                    return GameResult::WhiteWins;
+                }
+
+                let black_king = match get_lsb_index(self.king_bitboard(&Color::Black)) {
+                    Some(square) => square,
+                    None => return GameResult::WhiteWins,
+                };
+                let mut targets = self.bitboard_constants.king_attacks[black_king]
+                    & EIGHTH_RANK
+                    & !self.occupancy_bitboards[Color::Black.occupancy_bitboard_index()];
+                while targets != 0 {
+                    let target = get_lsb_index(targets).expect("Guard before should handle this.");
+                    if !self.is_square_attacked(Square::new(target), &Color::White) {
+                        return GameResult::Ongoing;
+                    }
+                    targets = pop_bit(targets, target);
+                }
+
+                GameResult::WhiteWins
+            }
+        };
+    }
+
+    fn king_bitboard(&self, side: &Color) -> u64 {
+        return self.piece_bitboards[Piece::new(*side, PieceType::King).bitboard_index()];
+    }
+
+    // Sums up how much each piece counts towards the game phase. Capped, in case of early promotions.
+    pub fn get_game_phase(&self) -> i64 {
+        let mut phase: i64 = 0;
+
+        for piece_type in [
+            PieceType::Queen,
+            PieceType::Rook,
+            PieceType::Bishop,
+            PieceType::Knight,
+        ] {
+            let piece_count = count_bits(
+                self.piece_bitboards[Piece::new(Color::White, piece_type).bitboard_index()],
+            ) + count_bits(
+                self.piece_bitboards[Piece::new(Color::Black, piece_type).bitboard_index()],
+            );
+            phase += piece_count as i64 * piece_type.piece_phase_value();
+        }
+
+        return i64::min(phase, constants::GAME_PHASE_MAX);
+    }
+
+    pub fn evaluate_board(&self) -> i64 {
+        return match self.evaluation_mode {
+            EvaluationMode::Classical => self.evaluate(None),
+            EvaluationMode::Nnue => self.evaluate_nnue(),
+        };
+    }
+
+    // Same as `evaluate_board`, but keeps track of every term that went into the evaluation.
+    pub fn evaluate_trace(&self) -> EvaluationTrace {
+        let mut trace = EvaluationTrace::new(self.get_game_phase());
+        trace.total = match self.evaluation_mode {
+            EvaluationMode::Classical => self.evaluate(Some(&mut trace)),
+            EvaluationMode::Nnue => {
+                // The network is a black box, so it only gets one line.
+                let evaluation = self.evaluate_nnue();
+                trace.add("NNUE", Color::White, PhaseScore::flat(evaluation));
+                evaluation
+            }
+        };
+
+        return trace;
+    }
+
+    // Network output, converted to white's perspective like the rest of the engine.
+    pub fn evaluate_nnue(&self) -> i64 {
+        let network = self
+            .nnue_network
+            .as_ref()
+            .expect("NNUE evaluation requires a network. See `set_evaluation_mode`.");
+        let accumulator = self
+            .nnue_accumulators
+            .last()
+            .expect("Accumulators are built when the network is loaded.");
+
+        if self.white_to_move {
+            return network.evaluate(accumulator, Color::White);
+        } else {
+            return -network.evaluate(accumulator, Color::Black);
+        }
+    }
+
+    pub fn set_nnue_network(&mut self, network: Option<Arc<NnueNetwork>>) {
+        self.nnue_network = network;
+        if self.nnue_network.is_none() {
+            self.evaluation_mode = EvaluationMode::Classical;
+        }
+        self.refresh_nnue_accumulators();
+    }
+
+    pub fn set_evaluation_mode(&mut self, mode: EvaluationMode) -> Result<(), String> {
+        if mode == EvaluationMode::Nnue && self.nnue_network.is_none() {
+            return Err("Cannot switch to NNUE evaluation, no network is loaded.".to_string());
+        }
+
+        self.evaluation_mode = mode;
+        return Ok(());
+    }
+
+    // Throw away the accumulator stack, and build the current position from scratch.
+    pub fn refresh_nnue_accumulators(&mut self) {
+        self.nnue_accumulators.clear();
+        if let Some(network) = &self.nnue_network {
+            self.nnue_accumulators
+                .push(network.refresh_accumulator(&self.piece_bitboards));
+        }
+    }
+
+    fn push_nnue_accumulator(&mut self, previous_piece_bitboards: &[u64; 12]) {
+        let network = match &self.nnue_network {
+            Some(n) => n,
+            None => return,
+        };
+
+        let mut accumulator = self
+            .nnue_accumulators
+            .last()
+            .expect("Accumulators are built when the network is loaded.")
+            .clone();
+        network.update_accumulator(
+            &mut accumulator,
+            previous_piece_bitboards,
+            &self.piece_bitboards,
+        );
+        self.nnue_accumulators.push(accumulator);
+    }
+
+    fn evaluate(&self, mut trace: Option<&mut EvaluationTrace>) -> i64 {
+        // Variables shared by both functions.
+        let mut square: usize;
+        let mut occupancies: u64;
+        let mut white_piece_value_total: i64 = 0;
+        let mut black_piece_value_total: i64 = 0;
+
+        // Add up white pieces.
+        occupancies = self.occupancy_bitboards[Color::White.occupancy_bitboard_index()];
+        while occupancies != 0 {
+            square = get_lsb_index(occupancies).expect("Guard clause.");
+            let piece_wrapped = self
+                .get_piece_at_square(Square::new(square))
+                .map(|p| p.piece_type);
+            let piece = piece_wrapped.expect("Not empty (white piece).");
+            let base_value = self.evaluation_parameters.piece_value(piece);
+            let happy_square_value =
+                self.evaluation_parameters
+                    .happy_square_value(piece, square, Color::White);
+            white_piece_value_total += base_value;
+            white_piece_value_total += happy_square_value;
+            if let Some(t) = trace.as_deref_mut() {
+                t.add("Material", Color::White, PhaseScore::flat(base_value));
+                t.add(
+                    "Piece-square tables",
+                    Color::White,
+                    PhaseScore::flat(happy_square_value),
+                );
+            }
+            occupancies = pop_bit(occupancies, square)
         }
 
         // Add up black pieces.
         occupancies = self.occupancy_bitboards[Color::Black.occupancy_bitboard_index()];
         while occupancies != 0 {
             square = get_lsb_index(occupancies).expect("Guard clause.");
-            let (piece_wrapped, _) = self.get_piece_at_square(square);
+            let piece_wrapped = self
+                .get_piece_at_square(Square::new(square))
+                .map(|p| p.piece_type);
 
             if piece_wrapped.is_none() {
                 println!("Expecting piece at square {}; but did not find it.", square);
@@ -1282,170 +2516,579 @@ impl<'a> ChessGame<'a> {
             }
 
             let piece = piece_wrapped.expect("Not empty (black piece).");
-            black_piece_value_total += piece.piece_base_value();
-            black_piece_value_total += piece.piece_happy_square_value(square, false);
+            let base_value = self.evaluation_parameters.piece_value(piece);
+            let happy_square_value =
+                self.evaluation_parameters
+                    .happy_square_value(piece, square, Color::Black);
+            black_piece_value_total += base_value;
+            black_piece_value_total += happy_square_value;
+            if let Some(t) = trace.as_deref_mut() {
+                t.add("Material", Color::Black, PhaseScore::flat(base_value));
+                t.add(
+                    "Piece-square tables",
+                    Color::Black,
+                    PhaseScore::flat(happy_square_value),
+                );
+            }
             occupancies = pop_bit(occupancies, square)
         }
 
-        // Add up the pieces, return the sum?
-        return white_piece_value_total - black_piece_value_total;
+        // Crazyhouse. Pieces in hand can be dropped at any time, so they count as material too.
+        for side in [Color::White, Color::Black] {
+            for piece_type in POCKET_PIECE_TYPES.iter() {
+                let count = self.pockets[side.idx()][piece_type.bitboard_index()] as i64;
+                if count == 0 {
+                    continue;
+                }
+
+                let value = count * self.evaluation_parameters.piece_value(*piece_type);
+                match side {
+                    Color::White => white_piece_value_total += value,
+                    Color::Black => black_piece_value_total += value,
+                }
+                if let Some(t) = trace.as_deref_mut() {
+                    t.add("Pockets", side, PhaseScore::flat(value));
+                }
+            }
+        }
+
+        let mut evaluation = white_piece_value_total - black_piece_value_total;
+
+        // Endings we know the answer to get their own score, replacing everything above.
+        if let Some(endgame_evaluation) = endgame::evaluate_endgame(self) {
+            if let Some(t) = trace.as_deref_mut() {
+                let adjustment = endgame_evaluation - evaluation;
+                if adjustment >= 0 {
+                    t.add(
+                        "Endgame knowledge",
+                        Color::White,
+                        PhaseScore::flat(adjustment),
+                    );
+                } else {
+                    t.add(
+                        "Endgame knowledge",
+                        Color::Black,
+                        PhaseScore::flat(-adjustment),
+                    );
+                }
+            }
+            return endgame_evaluation;
+        }
+
+        // Otherwise, if someone is well ahead, help them finish the job.
+        if let Some((side, bonus)) = endgame::mop_up(self) {
+            let mop_up_score = PhaseScore::new(0, bonus);
+            if let Some(t) = trace {
+                t.add("Mop-up", side, mop_up_score);
+            }
+            let tapered = mop_up_score.tapered(self.get_game_phase());
+            evaluation += match side {
+                Color::White => tapered,
+                Color::Black => -tapered,
+            };
+        }
+
+        return evaluation;
+    }
+
+    // Meant for users/bots to pick a move, so it is populated with all the data we need.
+    pub fn choose_move_from_legal_move(&mut self, this_move: &Move) -> Option<Move> {
+        let moves = self.get_legal_moves();
+
+        for m in moves.iter() {
+            if m == this_move {
+                return Some(*m);
+            }
+        }
+
+        return None;
+    }
+
+    pub fn set_legal_moves(&mut self, moves: Option<Vec<Move>>) {
+        self.legal_moves.clear();
+        self.legal_moves = match moves {
+            Some(m) => m,
+            None => self.get_legal_moves(),
+        };
+    }
+
+    pub fn get_legal_moves(&mut self) -> Vec<Move> {
+        return self.get_legal_moves_for(MoveGenerationMode::All);
+    }
+
+    // Legal moves of one kind, see `MoveGenerationMode`. Sorted like `get_legal_moves`.
+    pub fn get_legal_moves_for(&mut self, mode: MoveGenerationMode) -> Vec<Move> {
+        // Nothing left to play once a variant rule has decided the game.
+        if self.variant_result().is_over() {
+            return vec![];
+        }
+
+        let our_side = match self.white_to_move {
+            true => Color::White,
+            false => Color::Black,
+        };
+        if mode == MoveGenerationMode::Evasions && !self.is_in_check(&our_side) {
+            return vec![];
+        }
+
+        // Antichess. Whether a quiet move is allowed depends on whether there's any capture, so start from all of them.
+        let generation_mode = match self.variant {
+            Variant::Antichess => MoveGenerationMode::All,
+            _ => mode,
+        };
+        let mut moves = match self.variant.has_standard_king_safety() {
+            true => self.get_legal_moves_with_masks(generation_mode),
+            false => self.get_legal_moves_by_make_unmake(generation_mode),
+        };
+
+        // Antichess. If we can take something, we have to.
+        if matches!(self.variant, Variant::Antichess) {
+            if moves.iter().any(|m| m.to_piece_type.is_some()) {
+                moves.retain(|m| m.to_piece_type.is_some());
+            }
+            moves.retain(|m| mode.includes_move(m));
+        }
+
+        self.sort_moves(&mut moves);
+
+        return moves;
+    }
+
+    /**
+     * Legal moves without making any of them. Works out what is giving check and which of our pieces are pinned first,
+     * then only keeps moves that deal with the check and stay on their pin line.
+     * Only for variants where the usual rules about the king apply, see `Variant::has_standard_king_safety`.
+     */
+    pub fn get_legal_moves_with_masks(&self, mode: MoveGenerationMode) -> Vec<Move> {
+        let mut moves: Vec<Move> = vec![];
+        let (our_side, their_side) = match self.white_to_move {
+            true => (&Color::White, &Color::Black),
+            false => (&Color::Black, &Color::White),
+        };
+
+        let king_square = match get_lsb_index(self.king_bitboard(our_side)) {
+            Some(square) => square,
+            None => return moves,
+        };
+        let their_king_square = get_lsb_index(self.king_bitboard(their_side));
+        let occupancy = self.occupancy_bitboards[2];
+
+        // In double check only the king can move. Otherwise, take the checker or get in the way.
+        let checkers = self.attackers(king_square, their_side, occupancy);
+        let evasion_mask = match count_bits(checkers) {
+            0 => u64::MAX,
+            1 => {
+                let checker = get_lsb_index(checkers).expect("One checker.");
+                set_bit(self.squares_between(checker, king_square), checker)
+            }
+            _ => 0,
+        };
+        let pin_rays = self.get_pin_rays(king_square, our_side, their_side);
+
+        for mut this_move in self.get_psuedo_legal_moves(mode) {
+            let is_legal = if this_move.is_drop {
+                // Dropping a piece can only block a check, never cause one.
+                get_bit(evasion_mask, this_move.to_square.index()) != 0
+            } else if this_move.from_piece_type == Some(PieceType::King) {
+                self.is_king_move_safe(&this_move, our_side, their_side)
+            } else if this_move.is_en_passant_capture {
+                // Both pawns leave the rank at once, which can uncover a rook on it. Just look again.
+                let captured_square = en_passant_captured_square(&this_move, our_side);
+                let occupancy_after = set_bit(
+                    pop_bit(
+                        pop_bit(occupancy, this_move.from_square.index()),
+                        captured_square,
+                    ),
+                    this_move.to_square.index(),
+                );
+                self.attackers(king_square, their_side, occupancy_after)
+                    & !set_bit(0, captured_square)
+                    == 0
+            } else {
+                get_bit(evasion_mask, this_move.to_square.index()) != 0
+                    && get_bit(
+                        pin_rays[this_move.from_square.index()],
+                        this_move.to_square.index(),
+                    ) != 0
+            };
+
+            if is_legal {
+                self.set_move_side_effects(&mut this_move, our_side, their_side);
+                this_move.is_check = Some(match their_king_square {
+                    Some(square) => self.gives_check(&this_move, our_side, square),
+                    None => false,
+                });
+                moves.push(this_move);
+            }
+        }
+
+        return moves;
+    }
+
+    /**
+     * Legal moves found by making every psuedo-legal move and checking the position afterwards.
+     * Slower, but handles every variant. Also the reference `get_legal_moves_with_masks` is tested against.
+     */
+    pub fn get_legal_moves_by_make_unmake(&mut self, mode: MoveGenerationMode) -> Vec<Move> {
+        let mut moves: Vec<Move> = vec![];
+        let our_side: &Color;
+        let their_side: &Color;
+
+        if self.white_to_move {
+            our_side = &Color::White;
+            their_side = &Color::Black;
+        } else {
+            our_side = &Color::Black;
+            their_side = &Color::White;
+        }
+
+        let mut possible_moves = self.get_psuedo_legal_moves(mode);
+
+        // Try the move, drop it if it's illegal.
+        for this_move in possible_moves.iter_mut() {
+            self.set_move_side_effects(this_move, our_side, their_side);
+
+            self.make_move(this_move, false);
+
+            // Does the move put us in check?
+            if self.is_legal_after_move(our_side, their_side) {
+                // Does it put them in check?
+                this_move.is_check = Some(self.is_in_check(their_side));
+                moves.push(*this_move);
+            }
+
+            self.unmake_move(this_move);
+        }
+
+        return moves;
+    }
+
+    // Fills in what a move does besides moving pieces: lost castling rights, and (crazyhouse) capturing a promoted piece.
+    fn set_move_side_effects(&self, this_move: &mut Move, our_side: &Color, their_side: &Color) {
+        // How does this move impact castling rights?
+        this_move.removes_white_castling_rights_short = Some(false);
+        this_move.removes_white_castling_rights_long = Some(false);
+        this_move.removes_black_castling_rights_short = Some(false);
+        this_move.removes_black_castling_rights_long = Some(false);
+
+        // Crazyhouse. Captured promoted pieces go back to a pocket as pawns.
+        this_move.captures_promoted_piece = Some(
+            this_move.to_piece_type.is_some()
+                && get_bit(self.promoted_pieces, this_move.to_square.index()) != 0,
+        );
+
+        // Revoke our castling rights based on our move.
+        match our_side {
+            Color::White => {
+                if self.can_white_castle_short {
+                    // If we are moving the king, remove this right.
+                    if this_move.from_piece_type == Some(PieceType::King) {
+                        this_move.removes_white_castling_rights_short = Some(true);
+                    }
+                    // If we are moving our rook from it's starting square, remove this right.
+                    else if this_move.from_piece_type == Some(PieceType::Rook)
+                        && this_move.from_square.index() == self.castling_rook_squares[0]
+                    {
+                        this_move.removes_white_castling_rights_short = Some(true);
+                    }
+                }
+
+                if self.can_white_castle_long {
+                    // If we are moving the king, remove this right.
+                    if this_move.from_piece_type == Some(PieceType::King) {
+                        this_move.removes_white_castling_rights_long = Some(true);
+                    }
+                    // If we are moving our rook from it's starting square, remove this right.
+                    else if this_move.from_piece_type == Some(PieceType::Rook)
+                        && this_move.from_square.index() == self.castling_rook_squares[1]
+                    {
+                        this_move.removes_white_castling_rights_long = Some(true);
+                    }
+                }
+            }
+            Color::Black => {
+                if self.can_black_castle_short {
+                    // If we are moving the king, remove this right.
+                    if this_move.from_piece_type == Some(PieceType::King) {
+                        this_move.removes_black_castling_rights_short = Some(true);
+                    }
+                    // If we are moving our rook from it's starting square, remove this right.
+                    else if this_move.from_piece_type == Some(PieceType::Rook)
+                        && this_move.from_square.index() == self.castling_rook_squares[2]
+                    {
+                        this_move.removes_black_castling_rights_short = Some(true);
+                    }
+                }
+
+                if self.can_black_castle_long {
+                    // If we are moving the king, remove this right.
+                    if this_move.from_piece_type == Some(PieceType::King) {
+                        this_move.removes_black_castling_rights_long = Some(true);
+                    }
+                    // If we are moving our rook from it's starting square, remove this right.
+                    else if this_move.from_piece_type == Some(PieceType::Rook)
+                        && this_move.from_square.index() == self.castling_rook_squares[3]
+                    {
+                        this_move.removes_black_castling_rights_long = Some(true);
+                    }
+                }
+            }
+        }
+
+        // Handle the edge case where we are capturing opponents rook on it's starting square. We need to revoke rights.
+        if this_move.to_piece_type == Some(PieceType::Rook) {
+            match their_side {
+                Color::Black => {
+                    // If black can castle short, but we are capturing the rook on it's starting square; revoke.
+                    if self.can_black_castle_short
+                        && this_move.to_square.index() == self.castling_rook_squares[2]
+                    {
+                        this_move.removes_black_castling_rights_short = Some(true);
+                    }
+                    // If black can castle long, but we are capturing the rook on it's starting square; revoke.
+                    else if self.can_black_castle_long
+                        && this_move.to_square.index() == self.castling_rook_squares[3]
+                    {
+                        this_move.removes_black_castling_rights_long = Some(true);
+                    }
+                }
+                Color::White => {
+                    // If white can castle short, but we are capturing the rook on it's starting square; revoke.
+                    if self.can_white_castle_short
+                        && this_move.to_square.index() == self.castling_rook_squares[0]
+                    {
+                        this_move.removes_white_castling_rights_short = Some(true);
+                    }
+                    // If white can castle long, but we are capturing the rook on it's starting square; revoke.
+                    else if self.can_white_castle_long
+                        && this_move.to_square.index() == self.castling_rook_squares[1]
+                    {
+                        this_move.removes_white_castling_rights_long = Some(true);
+                    }
+                }
+            }
+        }
+
+        // Atomic. Rooks caught in the explosion can't castle any more.
+        if matches!(self.variant, Variant::Atomic) && this_move.to_piece_type.is_some() {
+            let blast_squares = set_bit(
+                self.bitboard_constants.king_attacks[this_move.to_square.index()],
+                this_move.to_square.index(),
+            );
+            let is_blown_up = |rights_index: usize| {
+                get_bit(blast_squares, self.castling_rook_squares[rights_index]) != 0
+            };
+            if self.can_white_castle_short && is_blown_up(0) {
+                this_move.removes_white_castling_rights_short = Some(true);
+            }
+            if self.can_white_castle_long && is_blown_up(1) {
+                this_move.removes_white_castling_rights_long = Some(true);
+            }
+            if self.can_black_castle_short && is_blown_up(2) {
+                this_move.removes_black_castling_rights_short = Some(true);
+            }
+            if self.can_black_castle_long && is_blown_up(3) {
+                this_move.removes_black_castling_rights_long = Some(true);
+            }
+        }
+    }
+
+    // Every `attacking_side` piece that attacks `square`, if the board had `occupancy` for blockers.
+    pub fn attackers_of(
+        &self,
+        square: Square,
+        attacking_side: &Color,
+        occupancy: Bitboard,
+    ) -> Bitboard {
+        return Bitboard(self.attackers(square.index(), attacking_side, occupancy.0))
+            .without(square);
+    }
+
+    fn attackers(&self, square: usize, attacking_side: &Color, occupancy: u64) -> u64 {
+        let defending_side = match attacking_side {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        };
+        let bishops_and_queens = self
+            .pieces(Piece::new(*attacking_side, PieceType::Bishop))
+            .0
+            | self.pieces(Piece::new(*attacking_side, PieceType::Queen)).0;
+        let rooks_and_queens = self.pieces(Piece::new(*attacking_side, PieceType::Rook)).0
+            | self.pieces(Piece::new(*attacking_side, PieceType::Queen)).0;
+
+        return (self.bitboard_constants.pawn_attacks[defending_side.idx()][square]
+            & self.pieces(Piece::new(*attacking_side, PieceType::Pawn)).0)
+            | (self.bitboard_constants.knight_attacks[square]
+                & self
+                    .pieces(Piece::new(*attacking_side, PieceType::Knight))
+                    .0)
+            | (self.bitboard_constants.king_attacks[square]
+                & self.pieces(Piece::new(*attacking_side, PieceType::King)).0)
+            | (self.bishop_attacks(square, occupancy) & bishops_and_queens)
+            | (self.rook_attacks(square, occupancy) & rooks_and_queens);
     }
 
-    // Meant for users/bots to pick a move, so it is populated with all the data we need.
-    pub fn choose_move_from_legal_move(&mut self, this_move: &Move) -> Option<Move> {
-        let moves = self.get_legal_moves();
+    // Squares strictly between `a` and `b`. Empty if they don't share a rank, file or diagonal.
+    fn squares_between(&self, a: usize, b: usize) -> u64 {
+        let file_distance = (a % 8).abs_diff(b % 8);
+        let rank_distance = (a / 8).abs_diff(b / 8);
+        let ends = set_bit(set_bit(0, a), b);
 
-        for m in moves.iter() {
-            if m == this_move {
-                return Some(*m);
+        // Some attack table entries include the square they're for, so leave the ends out explicitly.
+        if file_distance == 0 || rank_distance == 0 {
+            return self.rook_attacks(a, ends) & self.rook_attacks(b, ends) & !ends;
+        }
+        if file_distance == rank_distance {
+            return self.bishop_attacks(a, ends) & self.bishop_attacks(b, ends) & !ends;
+        }
+        return 0;
+    }
+
+    /**
+     * Where each of our pieces is allowed to move without exposing the king, indexed by square.
+     * Pinned pieces get the line between the king and the pinning piece (taking it included), everything else gets every square.
+     */
+    fn get_pin_rays(&self, king_square: usize, our_side: &Color, their_side: &Color) -> [u64; 64] {
+        let mut pin_rays = [u64::MAX; 64];
+        let their_queens = self.pieces(Piece::new(*their_side, PieceType::Queen)).0;
+
+        // Their sliders that would see our king on an empty board.
+        let mut snipers = (self.rook_attacks(king_square, 0)
+            & (self.pieces(Piece::new(*their_side, PieceType::Rook)).0 | their_queens))
+            | (self.bishop_attacks(king_square, 0)
+                & (self.pieces(Piece::new(*their_side, PieceType::Bishop)).0 | their_queens));
+
+        while let Some(sniper) = get_lsb_index(snipers) {
+            let between = self.squares_between(sniper, king_square);
+            let blockers = between & self.occupancy_bitboards[2];
+
+            // Exactly one piece in the way, and it's ours.
+            if count_bits(blockers) == 1
+                && blockers & self.occupancy_bitboards[our_side.occupancy_bitboard_index()] != 0
+            {
+                let pinned = get_lsb_index(blockers).expect("One blocker.");
+                pin_rays[pinned] = set_bit(between, sniper);
             }
+
+            snipers = pop_bit(snipers, sniper);
         }
 
-        return None;
+        return pin_rays;
     }
 
-    pub fn set_legal_moves(&mut self, moves: Option<Vec<Move>>) {
-        self.legal_moves.clear();
-        self.legal_moves = match moves {
-            Some(m) => m,
-            None => self.get_legal_moves(),
-        };
+    // King moves, castling included. The king can't step onto an attacked square, including ones it was only shielding itself from.
+    fn is_king_move_safe(&self, this_move: &Move, our_side: &Color, their_side: &Color) -> bool {
+        let mut occupancy = pop_bit(self.occupancy_bitboards[2], this_move.from_square.index());
+
+        // Castling already checked the path. Where the king lands could have been covered by the rook though (Chess960).
+        if let Some(castle_side) = this_move.castle_side {
+            let rook_square =
+                self.castling_rook_squares[castling_rights_index(*our_side, castle_side)];
+            let (_, rook_target_square) =
+                castling_target_squares(this_move.from_square.index(), castle_side);
+            occupancy = set_bit(pop_bit(occupancy, rook_square), rook_target_square);
+        }
+
+        // Whatever we take on the destination doesn't count as an attacker.
+        let attackers = self.attackers(this_move.to_square.index(), their_side, occupancy)
+            & !set_bit(0, this_move.to_square.index());
+        return attackers == 0;
     }
 
-    pub fn get_legal_moves(&mut self) -> Vec<Move> {
-        let mut moves: Vec<Move> = vec![];
-        let mut possible_moves = self.get_psuedo_legal_moves();
-        let our_side: &Color;
-        let their_side: &Color;
+    // Whether a legal move checks the king on `their_king_square`, directly or by moving out of the way of one of our sliders.
+    fn gives_check(&self, this_move: &Move, our_side: &Color, their_king_square: usize) -> bool {
+        let king_bit = set_bit(0, their_king_square);
+        let mut occupancy = self.occupancy_bitboards[2];
+        let mut moved_from = set_bit(0, this_move.from_square.index());
 
-        if self.white_to_move {
-            our_side = &Color::White;
-            their_side = &Color::Black;
+        if this_move.is_drop {
+            occupancy = set_bit(occupancy, this_move.to_square.index());
+            moved_from = 0;
         } else {
-            our_side = &Color::Black;
-            their_side = &Color::White;
+            occupancy = set_bit(
+                pop_bit(occupancy, this_move.from_square.index()),
+                this_move.to_square.index(),
+            );
+        }
+        if this_move.is_en_passant_capture {
+            occupancy = pop_bit(occupancy, en_passant_captured_square(this_move, our_side));
         }
 
-        // Try the move, drop it if it's illegal.
-        for this_move in possible_moves.iter_mut() {
-            // How does this move impact castling rights?
-            this_move.removes_white_castling_rights_short = Some(false);
-            this_move.removes_white_castling_rights_long = Some(false);
-            this_move.removes_black_castling_rights_short = Some(false);
-            this_move.removes_black_castling_rights_long = Some(false);
-
-            // Revoke our castling rights based on our move.
-            match our_side {
-                Color::White => {
-                    if self.can_white_castle_short {
-                        // If we are moving the king, remove this right.
-                        if this_move.from_piece_type == Some(PieceType::King) {
-                            this_move.removes_white_castling_rights_short = Some(true);
-                        }
-                        // If we are moving our rook from it's starting square, remove this right.
-                        else if this_move.from_piece_type == Some(PieceType::Rook)
-                            && this_move.from_square == 63
-                        {
-                            this_move.removes_white_castling_rights_short = Some(true);
-                        }
-                    }
-
-                    if self.can_white_castle_long {
-                        // If we are moving the king, remove this right.
-                        if this_move.from_piece_type == Some(PieceType::King) {
-                            this_move.removes_white_castling_rights_long = Some(true);
-                        }
-                        // If we are moving our rook from it's starting square, remove this right.
-                        else if this_move.from_piece_type == Some(PieceType::Rook)
-                            && this_move.from_square == 56
-                        {
-                            this_move.removes_white_castling_rights_long = Some(true);
-                        }
-                    }
-                }
-                Color::Black => {
-                    if self.can_black_castle_short {
-                        // If we are moving the king, remove this right.
-                        if this_move.from_piece_type == Some(PieceType::King) {
-                            this_move.removes_black_castling_rights_short = Some(true);
-                        }
-                        // If we are moving our rook from it's starting square, remove this right.
-                        else if this_move.from_piece_type == Some(PieceType::Rook)
-                            && this_move.from_square == 7
-                        {
-                            this_move.removes_black_castling_rights_short = Some(true);
-                        }
-                    }
-
-                    if self.can_black_castle_long {
-                        // If we are moving the king, remove this right.
-                        if this_move.from_piece_type == Some(PieceType::King) {
-                            this_move.removes_black_castling_rights_long = Some(true);
-                        }
-                        // If we are moving our rook from it's starting square, remove this right.
-                        else if this_move.from_piece_type == Some(PieceType::Rook)
-                            && this_move.from_square == 0
-                        {
-                            this_move.removes_black_castling_rights_long = Some(true);
-                        }
-                    }
-                }
+        // Castling only ever checks with the rook.
+        if let Some(castle_side) = this_move.castle_side {
+            let rook_square =
+                self.castling_rook_squares[castling_rights_index(*our_side, castle_side)];
+            let (_, rook_target_square) =
+                castling_target_squares(this_move.from_square.index(), castle_side);
+            occupancy = set_bit(pop_bit(occupancy, rook_square), rook_target_square);
+            moved_from = set_bit(moved_from, rook_square);
+
+            if self.rook_attacks(rook_target_square, occupancy) & king_bit != 0 {
+                return true;
             }
+        }
 
-            // Handle the edge case where we are capturing opponents rook on it's starting square. We need to revoke rights.
-            if this_move.to_piece_type == Some(PieceType::Rook) {
-                match their_side {
-                    Color::Black => {
-                        // If black can castle short, but we are capturing the rook on it's starting square; revoke.
-                        if self.can_black_castle_short && this_move.to_square == 7 {
-                            this_move.removes_black_castling_rights_short = Some(true);
-                        }
-                        // If black can castle long, but we are capturing the rook on it's starting square; revoke.
-                        else if self.can_black_castle_long && this_move.to_square == 0 {
-                            this_move.removes_black_castling_rights_long = Some(true);
-                        }
-                    }
-                    Color::White => {
-                        // If white can castle short, but we are capturing the rook on it's starting square; revoke.
-                        if self.can_white_castle_short && this_move.to_square == 63 {
-                            this_move.removes_white_castling_rights_short = Some(true);
-                        }
-                        // If white can castle long, but we are capturing the rook on it's starting square; revoke.
-                        else if self.can_white_castle_long && this_move.to_square == 56 {
-                            this_move.removes_white_castling_rights_long = Some(true);
-                        }
-                    }
-                }
+        // Direct check from where the piece lands.
+        let landing_piece = match this_move.pawn_promoting_to {
+            Some(piece_type) => piece_type,
+            None => this_move
+                .from_piece_type
+                .expect("This should always be here."),
+        };
+        let direct_attacks = match landing_piece {
+            PieceType::Pawn => {
+                self.bitboard_constants.pawn_attacks[our_side.idx()][this_move.to_square.index()]
             }
-
-            self.make_move(this_move, false);
-
-            // Does the move put us in check?
-            if !self.is_king_attacked(our_side) {
-                // Does it put them in check?
-                this_move.is_check = Some(self.is_king_attacked(their_side));
-                moves.push(*this_move);
+            PieceType::Knight => {
+                self.bitboard_constants.knight_attacks[this_move.to_square.index()]
             }
-
-            self.unmake_move(this_move);
+            PieceType::Bishop => self.bishop_attacks(this_move.to_square.index(), occupancy),
+            PieceType::Rook => self.rook_attacks(this_move.to_square.index(), occupancy),
+            PieceType::Queen => self.queen_attacks(this_move.to_square.index(), occupancy),
+            PieceType::King => 0,
+        };
+        if direct_attacks & king_bit != 0 {
+            return true;
         }
 
-        self.sort_moves(&mut moves);
-
-        return moves;
+        // Discovered check from one of the sliders that stayed put.
+        let our_queens = self.pieces(Piece::new(*our_side, PieceType::Queen)).0;
+        let bishops_and_queens =
+            (self.pieces(Piece::new(*our_side, PieceType::Bishop)).0 | our_queens) & !moved_from;
+        let rooks_and_queens =
+            (self.pieces(Piece::new(*our_side, PieceType::Rook)).0 | our_queens) & !moved_from;
+        return self.bishop_attacks(their_king_square, occupancy) & bishops_and_queens != 0
+            || self.rook_attacks(their_king_square, occupancy) & rooks_and_queens != 0;
     }
 
     // Will generate moves that put self in check.
-    pub fn get_psuedo_legal_moves(&self) -> Vec<Move> {
+    pub fn get_psuedo_legal_moves(&self, mode: MoveGenerationMode) -> Vec<Move> {
         let mut moves: Vec<Move> = vec![];
 
         // Get all the moves.
-        moves.append(&mut self.get_moves_slider(PieceType::Queen));
-        moves.append(&mut self.get_moves_slider(PieceType::Rook));
-        moves.append(&mut self.get_moves_slider(PieceType::Bishop));
-        moves.append(&mut self.get_moves_knight());
-        moves.append(&mut self.get_moves_king());
-        moves.append(&mut self.get_moves_pawns());
+        moves.append(&mut self.get_moves_slider(PieceType::Queen, mode));
+        moves.append(&mut self.get_moves_slider(PieceType::Rook, mode));
+        moves.append(&mut self.get_moves_slider(PieceType::Bishop, mode));
+        moves.append(&mut self.get_moves_knight(mode));
+        moves.append(&mut self.get_moves_king(mode));
+        moves.append(&mut self.get_moves_pawns(mode));
+        if matches!(self.variant, Variant::Crazyhouse) && mode.includes_quiet() {
+            moves.append(&mut self.get_moves_drops());
+        }
+
+        // Antichess pawns can also promote to a king.
+        if matches!(self.variant, Variant::Antichess) {
+            let mut king_promotions: Vec<Move> = moves
+                .iter()
+                .filter(|m| m.pawn_promoting_to == Some(PieceType::Queen))
+                .map(|m| {
+                    let mut king_promotion = *m;
+                    king_promotion.pawn_promoting_to = Some(PieceType::King);
+                    return king_promotion;
+                })
+                .collect();
+            moves.append(&mut king_promotions);
+        }
 
         return moves;
     }
@@ -1460,7 +3103,11 @@ impl<'a> ChessGame<'a> {
         print!("\n");
     }
 
-    pub fn get_moves_slider(&self, slider_piece_type: PieceType) -> Vec<Move> {
+    pub fn get_moves_slider(
+        &self,
+        slider_piece_type: PieceType,
+        mode: MoveGenerationMode,
+    ) -> Vec<Move> {
         let mut moves: Vec<Move> = vec![];
         let mut source_square: usize;
         let mut target_square: usize;
@@ -1472,25 +3119,19 @@ impl<'a> ChessGame<'a> {
 
         let all_occupancies: u64 = self.occupancy_bitboards[2];
         let their_occupancies: u64;
-        let our_piece_bitboard_offset: usize;
+        let our_color: Color;
 
         if self.white_to_move {
             their_occupancies = self.occupancy_bitboards[Color::Black.occupancy_bitboard_index()];
-            our_piece_bitboard_offset = Color::White.piece_bitboard_offset();
+            our_color = Color::White;
         } else {
             their_occupancies = self.occupancy_bitboards[Color::White.occupancy_bitboard_index()];
-            our_piece_bitboard_offset = Color::Black.piece_bitboard_offset();
+            our_color = Color::Black;
         }
 
         slider_pieces = match slider_piece_type {
-            PieceType::Queen => {
-                self.piece_bitboards[our_piece_bitboard_offset + PieceType::Queen.bitboard_index()]
-            }
-            PieceType::Rook => {
-                self.piece_bitboards[our_piece_bitboard_offset + PieceType::Rook.bitboard_index()]
-            }
-            PieceType::Bishop => {
-                self.piece_bitboards[our_piece_bitboard_offset + PieceType::Bishop.bitboard_index()]
+            PieceType::Queen | PieceType::Rook | PieceType::Bishop => {
+                self.pieces(Piece::new(our_color, slider_piece_type)).0
             }
             _ => {
                 panic!("Attempted to get slider piece moves for non-slider piece.");
@@ -1503,28 +3144,28 @@ impl<'a> ChessGame<'a> {
             // Get moves and captures seperately.
             match slider_piece_type {
                 PieceType::Queen => {
-                    slider_piece_attacks = self.get_queen_attacks(source_square, all_occupancies);
+                    slider_piece_attacks = self.queen_attacks(source_square, all_occupancies);
                 }
                 PieceType::Rook => {
-                    slider_piece_attacks = self.get_rook_attacks(source_square, all_occupancies);
+                    slider_piece_attacks = self.rook_attacks(source_square, all_occupancies);
                 }
                 PieceType::Bishop => {
-                    slider_piece_attacks = self.get_bishop_attacks(source_square, all_occupancies);
+                    slider_piece_attacks = self.bishop_attacks(source_square, all_occupancies);
                 }
                 _ => {
                     panic!("Tried to get slider piece attacks for non-slider piece.");
                 }
             };
 
-            quiet_moves = slider_piece_attacks & (!all_occupancies);
-            captures = slider_piece_attacks & their_occupancies;
+            quiet_moves = slider_piece_attacks & (!all_occupancies) & mode.quiet_mask();
+            captures = slider_piece_attacks & their_occupancies & mode.capture_mask();
 
             while quiet_moves != 0 {
                 target_square = get_lsb_index(quiet_moves).expect("This should not be empty.");
                 moves.push(Move {
-                    from_square: source_square,
+                    from_square: Square::new(source_square),
                     from_piece_type: Some(slider_piece_type),
-                    to_square: target_square,
+                    to_square: Square::new(target_square),
                     to_piece_type: None,
                     last_en_passant_target_coord: self.en_passant_target,
                     next_en_passant_target_coord: None,
@@ -1535,6 +3176,8 @@ impl<'a> ChessGame<'a> {
                     removes_white_castling_rights_long: None,
                     removes_black_castling_rights_short: None,
                     removes_black_castling_rights_long: None,
+                    is_drop: false,
+                    captures_promoted_piece: None,
                     castle_side: None,
                 });
                 quiet_moves = pop_bit(quiet_moves, target_square);
@@ -1542,12 +3185,14 @@ impl<'a> ChessGame<'a> {
 
             while captures != 0 {
                 target_square = get_lsb_index(captures).expect("This should not be empty.");
-                (to_piece_type, _) = self.get_piece_at_square(target_square);
+                to_piece_type = self
+                    .get_piece_at_square(Square::new(target_square))
+                    .map(|p| p.piece_type);
 
                 moves.push(Move {
-                    from_square: source_square,
+                    from_square: Square::new(source_square),
                     from_piece_type: Some(slider_piece_type),
-                    to_square: target_square,
+                    to_square: Square::new(target_square),
                     to_piece_type: to_piece_type,
                     last_en_passant_target_coord: self.en_passant_target,
                     next_en_passant_target_coord: None,
@@ -1558,6 +3203,8 @@ impl<'a> ChessGame<'a> {
                     removes_white_castling_rights_long: None,
                     removes_black_castling_rights_short: None,
                     removes_black_castling_rights_long: None,
+                    is_drop: false,
+                    captures_promoted_piece: None,
                     castle_side: None,
                 });
                 captures = pop_bit(captures, target_square);
@@ -1569,7 +3216,7 @@ impl<'a> ChessGame<'a> {
         return moves;
     }
 
-    pub fn get_moves_knight(&self) -> Vec<Move> {
+    pub fn get_moves_knight(&self, mode: MoveGenerationMode) -> Vec<Move> {
         let mut moves: Vec<Move> = vec![];
         let mut source_square: usize;
         let mut target_square: usize;
@@ -1581,12 +3228,12 @@ impl<'a> ChessGame<'a> {
 
         if self.white_to_move {
             their_occupancies = self.occupancy_bitboards[Color::Black.occupancy_bitboard_index()];
-            knights = self.piece_bitboards
-                [Color::White.piece_bitboard_offset() + PieceType::Knight.bitboard_index()];
+            knights =
+                self.piece_bitboards[Piece::new(Color::White, PieceType::Knight).bitboard_index()];
         } else {
             their_occupancies = self.occupancy_bitboards[Color::White.occupancy_bitboard_index()];
-            knights = self.piece_bitboards
-                [Color::Black.piece_bitboard_offset() + PieceType::Knight.bitboard_index()];
+            knights =
+                self.piece_bitboards[Piece::new(Color::Black, PieceType::Knight).bitboard_index()];
         }
 
         while knights != 0 {
@@ -1594,15 +3241,18 @@ impl<'a> ChessGame<'a> {
 
             // Get moves and captures seperately.
             quiet_moves = self.bitboard_constants.knight_attacks[source_square]
-                & (!self.occupancy_bitboards[2]);
-            captures = self.bitboard_constants.knight_attacks[source_square] & their_occupancies;
+                & (!self.occupancy_bitboards[2])
+                & mode.quiet_mask();
+            captures = self.bitboard_constants.knight_attacks[source_square]
+                & their_occupancies
+                & mode.capture_mask();
 
             while quiet_moves != 0 {
                 target_square = get_lsb_index(quiet_moves).expect("This should not be empty.");
                 moves.push(Move {
-                    from_square: source_square,
+                    from_square: Square::new(source_square),
                     from_piece_type: Some(PieceType::Knight),
-                    to_square: target_square,
+                    to_square: Square::new(target_square),
                     to_piece_type: None,
                     is_check: None,
                     last_en_passant_target_coord: self.en_passant_target,
@@ -1614,18 +3264,22 @@ impl<'a> ChessGame<'a> {
                     removes_white_castling_rights_long: None,
                     removes_black_castling_rights_short: None,
                     removes_black_castling_rights_long: None,
+                    is_drop: false,
+                    captures_promoted_piece: None,
                 });
                 quiet_moves = pop_bit(quiet_moves, target_square);
             }
 
             while captures != 0 {
                 target_square = get_lsb_index(captures).expect("This should not be empty.");
-                (to_piece_type, _) = self.get_piece_at_square(target_square);
+                to_piece_type = self
+                    .get_piece_at_square(Square::new(target_square))
+                    .map(|p| p.piece_type);
 
                 moves.push(Move {
-                    from_square: source_square,
+                    from_square: Square::new(source_square),
                     from_piece_type: Some(PieceType::Knight),
-                    to_square: target_square,
+                    to_square: Square::new(target_square),
                     to_piece_type: to_piece_type,
                     is_check: None,
                     last_en_passant_target_coord: self.en_passant_target,
@@ -1637,6 +3291,8 @@ impl<'a> ChessGame<'a> {
                     removes_white_castling_rights_long: None,
                     removes_black_castling_rights_short: None,
                     removes_black_castling_rights_long: None,
+                    is_drop: false,
+                    captures_promoted_piece: None,
                 });
                 captures = pop_bit(captures, target_square);
             }
@@ -1647,164 +3303,210 @@ impl<'a> ChessGame<'a> {
         return moves;
     }
 
-    pub fn get_moves_king(&self) -> Vec<Move> {
+    pub fn get_moves_king(&self, mode: MoveGenerationMode) -> Vec<Move> {
         let mut moves: Vec<Move> = vec![];
-        let source_square: usize;
         let mut target_square: usize;
         let bitboard: u64;
         let mut to_piece_type: Option<PieceType>;
 
+        let our_color: Color;
         let their_color: &Color;
         let their_occupancies: u64;
         let can_castle_long: bool;
         let can_castle_short: bool;
-        let king_starting_square: usize;
         if self.white_to_move {
+            our_color = Color::White;
             their_color = &Color::Black;
             their_occupancies = self.occupancy_bitboards[Color::Black.occupancy_bitboard_index()];
-            bitboard = self.piece_bitboards
-                [Color::White.piece_bitboard_offset() + PieceType::King.bitboard_index()];
+            bitboard =
+                self.piece_bitboards[Piece::new(Color::White, PieceType::King).bitboard_index()];
             can_castle_short = self.can_white_castle_short;
             can_castle_long = self.can_white_castle_long;
-            king_starting_square = 60;
         } else {
+            our_color = Color::Black;
             their_color = &Color::White;
             their_occupancies = self.occupancy_bitboards[Color::White.occupancy_bitboard_index()];
-            bitboard = self.piece_bitboards
-                [Color::Black.piece_bitboard_offset() + PieceType::King.bitboard_index()];
+            bitboard =
+                self.piece_bitboards[Piece::new(Color::Black, PieceType::King).bitboard_index()];
             can_castle_short = self.can_black_castle_short;
             can_castle_long = self.can_black_castle_long;
-            king_starting_square = 4;
         }
 
         if bitboard == 0 {
             return moves;
         }
 
-        source_square = get_lsb_index(bitboard).expect("Guard before should handle this.");
-        let mut quiet_moves =
-            self.bitboard_constants.king_attacks[source_square] & (!self.occupancy_bitboards[2]);
-        let mut attacks = self.bitboard_constants.king_attacks[source_square] & their_occupancies;
-
-        // Moves
-        while quiet_moves != 0 {
-            target_square = get_lsb_index(quiet_moves).expect("Guard before should handle this.");
-            moves.push(Move {
-                from_square: source_square,
-                from_piece_type: Some(PieceType::King),
-                to_square: target_square,
-                to_piece_type: None,
-                is_check: None,
-                last_en_passant_target_coord: self.en_passant_target,
-                next_en_passant_target_coord: None,
-                is_en_passant_capture: false,
-                pawn_promoting_to: None,
-                castle_side: None,
-                removes_white_castling_rights_short: None,
-                removes_white_castling_rights_long: None,
-                removes_black_castling_rights_short: None,
-                removes_black_castling_rights_long: None,
-            });
-            quiet_moves = pop_bit(quiet_moves, target_square);
-        }
-
-        // Attacks
-        while attacks != 0 {
-            target_square = get_lsb_index(attacks).expect("Guard before should handle this.");
-            (to_piece_type, _) = self.get_piece_at_square(target_square);
-
-            moves.push(Move {
-                from_square: source_square,
-                from_piece_type: Some(PieceType::King),
-                to_square: target_square,
-                to_piece_type: to_piece_type,
-                is_check: None,
-                last_en_passant_target_coord: self.en_passant_target,
-                next_en_passant_target_coord: None,
-                is_en_passant_capture: false,
-                pawn_promoting_to: None,
-                castle_side: None,
-                removes_white_castling_rights_short: None,
-                removes_white_castling_rights_long: None,
-                removes_black_castling_rights_short: None,
-                removes_black_castling_rights_long: None,
-            });
-
-            attacks = pop_bit(attacks, target_square);
-        }
-
-        // Castling
-        if can_castle_short {
-            // 1. Make sure squares are empty.
-            let squares_should_be_empty =
-                set_bit(0, king_starting_square + 1) | set_bit(0, king_starting_square + 2);
-
-            // 2. Make sure intermediary square is not attacked. Our final check for pins will handle checking the destination square.
-            let is_intermediary_square_attacked =
-                self.is_square_attacked(king_starting_square + 1, their_color);
-
-            // If both conditions are met, we can castle.
-            target_square = king_starting_square + 2;
-            if (squares_should_be_empty & self.occupancy_bitboards[2]) == 0
-                && !is_intermediary_square_attacked
-            {
+        // Antichess pawns can promote to kings, so there may be more than one.
+        let mut kings = bitboard;
+        while kings != 0 {
+            let source_square = get_lsb_index(kings).expect("Guard before should handle this.");
+            let mut quiet_moves = self.bitboard_constants.king_attacks[source_square]
+                & (!self.occupancy_bitboards[2])
+                & mode.quiet_mask();
+            let mut attacks = self.bitboard_constants.king_attacks[source_square]
+                & their_occupancies
+                & mode.capture_mask();
+
+            // Moves
+            while quiet_moves != 0 {
+                target_square =
+                    get_lsb_index(quiet_moves).expect("Guard before should handle this.");
                 moves.push(Move {
-                    from_square: source_square,
+                    from_square: Square::new(source_square),
                     from_piece_type: Some(PieceType::King),
-                    to_square: target_square,
+                    to_square: Square::new(target_square),
                     to_piece_type: None,
                     is_check: None,
                     last_en_passant_target_coord: self.en_passant_target,
                     next_en_passant_target_coord: None,
                     is_en_passant_capture: false,
                     pawn_promoting_to: None,
-                    castle_side: Some(CastleSides::Short),
+                    castle_side: None,
                     removes_white_castling_rights_short: None,
                     removes_white_castling_rights_long: None,
                     removes_black_castling_rights_short: None,
                     removes_black_castling_rights_long: None,
+                    is_drop: false,
+                    captures_promoted_piece: None,
                 });
+                quiet_moves = pop_bit(quiet_moves, target_square);
             }
-        }
-
-        if can_castle_long {
-            // 1. Make sure squares are empty.
-            let squares_should_be_empty = set_bit(0, king_starting_square - 1)
-                | set_bit(0, king_starting_square - 2)
-                | set_bit(0, king_starting_square - 3);
 
-            // 2. Make sure intermediary square is not attacked. Our final check for pins will handle checking the destination square.
-            let is_intermediary_square_attacked =
-                self.is_square_attacked(king_starting_square - 1, their_color);
+            // Attacks
+            while attacks != 0 {
+                target_square = get_lsb_index(attacks).expect("Guard before should handle this.");
+                to_piece_type = self
+                    .get_piece_at_square(Square::new(target_square))
+                    .map(|p| p.piece_type);
 
-            // If both conditions are met, we can castle.
-            target_square = king_starting_square - 2;
-            if (squares_should_be_empty & self.occupancy_bitboards[2]) == 0
-                && !is_intermediary_square_attacked
-            {
                 moves.push(Move {
-                    from_square: source_square,
+                    from_square: Square::new(source_square),
                     from_piece_type: Some(PieceType::King),
-                    to_square: target_square,
-                    to_piece_type: None,
+                    to_square: Square::new(target_square),
+                    to_piece_type: to_piece_type,
                     is_check: None,
                     last_en_passant_target_coord: self.en_passant_target,
                     next_en_passant_target_coord: None,
                     is_en_passant_capture: false,
                     pawn_promoting_to: None,
-                    castle_side: Some(CastleSides::Long),
+                    castle_side: None,
                     removes_white_castling_rights_short: None,
                     removes_white_castling_rights_long: None,
                     removes_black_castling_rights_short: None,
                     removes_black_castling_rights_long: None,
+                    is_drop: false,
+                    captures_promoted_piece: None,
                 });
+
+                attacks = pop_bit(attacks, target_square);
+            }
+
+            kings = pop_bit(kings, source_square);
+        }
+
+        // Castling. Works for any king and rook files, so Chess960 too. Antichess doesn't castle.
+        if matches!(self.variant, Variant::Antichess) || !mode.includes_quiet() {
+            return moves;
+        }
+        let source_square = get_lsb_index(bitboard).expect("Guard before should handle this.");
+        let our_rooks =
+            self.piece_bitboards[Piece::new(our_color, PieceType::Rook).bitboard_index()];
+        for (castle_side, can_castle) in [
+            (CastleSides::Short, can_castle_short),
+            (CastleSides::Long, can_castle_long),
+        ] {
+            if !can_castle {
+                continue;
+            }
+
+            let rook_square =
+                self.castling_rook_squares[castling_rights_index(our_color, castle_side)];
+            if get_bit(our_rooks, rook_square) == 0 || rook_square / 8 != source_square / 8 {
+                continue;
+            }
+
+            let (king_target_square, rook_target_square) =
+                castling_target_squares(source_square, castle_side);
+
+            // 1. Everything the king and rook travel over has to be empty, apart from the king and rook themselves.
+            let mut squares_should_be_empty = rank_span(source_square, king_target_square)
+                | rank_span(rook_square, rook_target_square);
+            squares_should_be_empty = pop_bit(squares_should_be_empty, source_square);
+            squares_should_be_empty = pop_bit(squares_should_be_empty, rook_square);
+            if (squares_should_be_empty & self.occupancy_bitboards[2]) != 0 {
+                continue;
+            }
+
+            // 2. The king can't castle out of, or through, check. Our final check for pins handles the destination square.
+            let mut king_path = rank_span(source_square, king_target_square);
+            let mut is_king_path_attacked = false;
+            while king_path != 0 {
+                let square = get_lsb_index(king_path).expect("Guard before should handle this.");
+                if self.is_square_attacked(Square::new(square), their_color) {
+                    is_king_path_attacked = true;
+                    break;
+                }
+                king_path = pop_bit(king_path, square);
+            }
+            if is_king_path_attacked {
+                continue;
+            }
+
+            moves.push(Move {
+                from_square: Square::new(source_square),
+                from_piece_type: Some(PieceType::King),
+                to_square: Square::new(king_target_square),
+                to_piece_type: None,
+                is_check: None,
+                last_en_passant_target_coord: self.en_passant_target,
+                next_en_passant_target_coord: None,
+                is_en_passant_capture: false,
+                pawn_promoting_to: None,
+                castle_side: Some(castle_side),
+                removes_white_castling_rights_short: None,
+                removes_white_castling_rights_long: None,
+                removes_black_castling_rights_short: None,
+                removes_black_castling_rights_long: None,
+                is_drop: false,
+                captures_promoted_piece: None,
+            });
+        }
+
+        return moves;
+    }
+
+    // Crazyhouse drops. Anything in our pocket onto any empty square, but pawns not on the first or last rank.
+    pub fn get_moves_drops(&self) -> Vec<Move> {
+        let mut moves: Vec<Move> = vec![];
+        let our_color = match self.white_to_move {
+            true => Color::White,
+            false => Color::Black,
+        };
+        let empty_squares = !self.occupancy_bitboards[2];
+
+        for piece_type in POCKET_PIECE_TYPES.iter() {
+            if self.pockets[our_color.idx()][piece_type.bitboard_index()] == 0 {
+                continue;
+            }
+
+            let mut target_squares = match piece_type {
+                PieceType::Pawn => empty_squares & PAWN_DROP_SQUARES,
+                _ => empty_squares,
+            };
+            while target_squares != 0 {
+                let target_square =
+                    get_lsb_index(target_squares).expect("Guard before should handle this.");
+                let mut m = Move::new_drop(*piece_type, Square::new(target_square));
+                m.last_en_passant_target_coord = self.en_passant_target;
+                moves.push(m);
+                target_squares = pop_bit(target_squares, target_square);
             }
         }
 
         return moves;
     }
 
-    pub fn get_moves_pawns(&self) -> Vec<Move> {
+    pub fn get_moves_pawns(&self, mode: MoveGenerationMode) -> Vec<Move> {
         let mut moves: Vec<Move> = vec![];
         let mut source_square: usize;
         let mut target_square: usize;
@@ -1824,8 +3526,8 @@ impl<'a> ChessGame<'a> {
         if self.white_to_move {
             our_color = Color::White;
             their_occupancies = self.occupancy_bitboards[Color::Black.occupancy_bitboard_index()];
-            bitboard = self.piece_bitboards
-                [Color::White.piece_bitboard_offset() + PieceType::Pawn.bitboard_index()];
+            bitboard =
+                self.piece_bitboards[Piece::new(Color::White, PieceType::Pawn).bitboard_index()];
             pawn_move_offset = -8;
             promotion_rank_lower = 0;
             promotion_rank_upper = 7;
@@ -1834,8 +3536,8 @@ impl<'a> ChessGame<'a> {
         } else {
             our_color = Color::Black;
             their_occupancies = self.occupancy_bitboards[Color::White.occupancy_bitboard_index()];
-            bitboard = self.piece_bitboards
-                [Color::Black.piece_bitboard_offset() + PieceType::Pawn.bitboard_index()];
+            bitboard =
+                self.piece_bitboards[Piece::new(Color::Black, PieceType::Pawn).bitboard_index()];
             pawn_move_offset = 8;
             promotion_rank_lower = 56;
             promotion_rank_upper = 63;
@@ -1849,13 +3551,21 @@ impl<'a> ChessGame<'a> {
             // Handles forward moves.
             target_square = (source_square as i32 + pawn_move_offset) as usize;
             let mut is_occupied = get_bit(all_occupancies, target_square) != 0;
-            if !is_occupied {
+            let is_promotion =
+                target_square >= promotion_rank_lower && target_square <= promotion_rank_upper;
+
+            // Promotions count as captures, the rest of the pushes are quiet.
+            let include_push = match is_promotion {
+                true => mode.includes_captures(),
+                false => mode.includes_quiet(),
+            };
+            if !is_occupied && include_push {
                 // Check for promotions (no capture).
-                if target_square >= promotion_rank_lower && target_square <= promotion_rank_upper {
+                if is_promotion {
                     moves.push(Move {
-                        from_square: source_square,
+                        from_square: Square::new(source_square),
                         from_piece_type: Some(PieceType::Pawn),
-                        to_square: target_square,
+                        to_square: Square::new(target_square),
                         to_piece_type: None,
                         is_check: None,
                         last_en_passant_target_coord: self.en_passant_target,
@@ -1867,11 +3577,13 @@ impl<'a> ChessGame<'a> {
                         removes_white_castling_rights_long: None,
                         removes_black_castling_rights_short: None,
                         removes_black_castling_rights_long: None,
+                        is_drop: false,
+                        captures_promoted_piece: None,
                     });
                     moves.push(Move {
-                        from_square: source_square,
+                        from_square: Square::new(source_square),
                         from_piece_type: Some(PieceType::Pawn),
-                        to_square: target_square,
+                        to_square: Square::new(target_square),
                         to_piece_type: None,
                         is_check: None,
                         last_en_passant_target_coord: self.en_passant_target,
@@ -1883,11 +3595,13 @@ impl<'a> ChessGame<'a> {
                         removes_white_castling_rights_long: None,
                         removes_black_castling_rights_short: None,
                         removes_black_castling_rights_long: None,
+                        is_drop: false,
+                        captures_promoted_piece: None,
                     });
                     moves.push(Move {
-                        from_square: source_square,
+                        from_square: Square::new(source_square),
                         from_piece_type: Some(PieceType::Pawn),
-                        to_square: target_square,
+                        to_square: Square::new(target_square),
                         to_piece_type: None,
                         is_check: None,
                         last_en_passant_target_coord: self.en_passant_target,
@@ -1899,11 +3613,13 @@ impl<'a> ChessGame<'a> {
                         removes_white_castling_rights_long: None,
                         removes_black_castling_rights_short: None,
                         removes_black_castling_rights_long: None,
+                        is_drop: false,
+                        captures_promoted_piece: None,
                     });
                     moves.push(Move {
-                        from_square: source_square,
+                        from_square: Square::new(source_square),
                         from_piece_type: Some(PieceType::Pawn),
-                        to_square: target_square,
+                        to_square: Square::new(target_square),
                         to_piece_type: None,
                         is_check: None,
                         last_en_passant_target_coord: self.en_passant_target,
@@ -1915,12 +3631,14 @@ impl<'a> ChessGame<'a> {
                         removes_white_castling_rights_long: None,
                         removes_black_castling_rights_short: None,
                         removes_black_castling_rights_long: None,
+                        is_drop: false,
+                        captures_promoted_piece: None,
                     });
                 } else {
                     moves.push(Move {
-                        from_square: source_square,
+                        from_square: Square::new(source_square),
                         from_piece_type: Some(PieceType::Pawn),
-                        to_square: target_square,
+                        to_square: Square::new(target_square),
                         to_piece_type: None,
                         is_check: None,
                         last_en_passant_target_coord: self.en_passant_target,
@@ -1932,6 +3650,8 @@ impl<'a> ChessGame<'a> {
                         removes_white_castling_rights_long: None,
                         removes_black_castling_rights_short: None,
                         removes_black_castling_rights_long: None,
+                        is_drop: false,
+                        captures_promoted_piece: None,
                     });
 
                     // Check for the double move.
@@ -1945,15 +3665,15 @@ impl<'a> ChessGame<'a> {
                         && !is_occupied
                     {
                         moves.push(Move {
-                            from_square: source_square,
+                            from_square: Square::new(source_square),
                             from_piece_type: Some(PieceType::Pawn),
-                            to_square: target_square,
+                            to_square: Square::new(target_square),
                             to_piece_type: None,
                             is_check: None,
                             last_en_passant_target_coord: self.en_passant_target,
-                            next_en_passant_target_coord: Some(
+                            next_en_passant_target_coord: Some(Square::new(
                                 (source_square as i32 + pawn_move_offset) as usize,
-                            ),
+                            )),
                             is_en_passant_capture: false,
                             pawn_promoting_to: None,
                             castle_side: None,
@@ -1961,6 +3681,8 @@ impl<'a> ChessGame<'a> {
                             removes_white_castling_rights_long: None,
                             removes_black_castling_rights_short: None,
                             removes_black_castling_rights_long: None,
+                            is_drop: false,
+                            captures_promoted_piece: None,
                         });
                     }
                 }
@@ -1968,15 +3690,18 @@ impl<'a> ChessGame<'a> {
 
             // Handles captures (non-en-passant).
             attacks = self.bitboard_constants.pawn_attacks[our_color.idx()][source_square]
-                & their_occupancies;
+                & their_occupancies
+                & mode.capture_mask();
             while attacks != 0 {
                 target_square = get_lsb_index(attacks).expect("Should not be empty.");
-                (to_piece_type, _) = self.get_piece_at_square(target_square);
+                to_piece_type = self
+                    .get_piece_at_square(Square::new(target_square))
+                    .map(|p| p.piece_type);
                 if target_square >= promotion_rank_lower && target_square <= promotion_rank_upper {
                     moves.push(Move {
-                        from_square: source_square,
+                        from_square: Square::new(source_square),
                         from_piece_type: Some(PieceType::Pawn),
-                        to_square: target_square,
+                        to_square: Square::new(target_square),
                         to_piece_type: to_piece_type,
                         is_check: None,
                         last_en_passant_target_coord: self.en_passant_target,
@@ -1988,11 +3713,13 @@ impl<'a> ChessGame<'a> {
                         removes_white_castling_rights_long: None,
                         removes_black_castling_rights_short: None,
                         removes_black_castling_rights_long: None,
+                        is_drop: false,
+                        captures_promoted_piece: None,
                     });
                     moves.push(Move {
-                        from_square: source_square,
+                        from_square: Square::new(source_square),
                         from_piece_type: Some(PieceType::Pawn),
-                        to_square: target_square,
+                        to_square: Square::new(target_square),
                         to_piece_type: to_piece_type,
                         is_check: None,
                         last_en_passant_target_coord: self.en_passant_target,
@@ -2004,11 +3731,13 @@ impl<'a> ChessGame<'a> {
                         removes_white_castling_rights_long: None,
                         removes_black_castling_rights_short: None,
                         removes_black_castling_rights_long: None,
+                        is_drop: false,
+                        captures_promoted_piece: None,
                     });
                     moves.push(Move {
-                        from_square: source_square,
+                        from_square: Square::new(source_square),
                         from_piece_type: Some(PieceType::Pawn),
-                        to_square: target_square,
+                        to_square: Square::new(target_square),
                         to_piece_type: to_piece_type,
                         is_check: None,
                         last_en_passant_target_coord: self.en_passant_target,
@@ -2020,11 +3749,13 @@ impl<'a> ChessGame<'a> {
                         removes_white_castling_rights_long: None,
                         removes_black_castling_rights_short: None,
                         removes_black_castling_rights_long: None,
+                        is_drop: false,
+                        captures_promoted_piece: None,
                     });
                     moves.push(Move {
-                        from_square: source_square,
+                        from_square: Square::new(source_square),
                         from_piece_type: Some(PieceType::Pawn),
-                        to_square: target_square,
+                        to_square: Square::new(target_square),
                         to_piece_type: to_piece_type,
                         is_check: None,
                         last_en_passant_target_coord: self.en_passant_target,
@@ -2036,12 +3767,14 @@ impl<'a> ChessGame<'a> {
                         removes_white_castling_rights_long: None,
                         removes_black_castling_rights_short: None,
                         removes_black_castling_rights_long: None,
+                        is_drop: false,
+                        captures_promoted_piece: None,
                     });
                 } else {
                     moves.push(Move {
-                        from_square: source_square,
+                        from_square: Square::new(source_square),
                         from_piece_type: Some(PieceType::Pawn),
-                        to_square: target_square,
+                        to_square: Square::new(target_square),
                         to_piece_type: to_piece_type,
                         is_check: None,
                         last_en_passant_target_coord: self.en_passant_target,
@@ -2053,6 +3786,8 @@ impl<'a> ChessGame<'a> {
                         removes_white_castling_rights_long: None,
                         removes_black_castling_rights_short: None,
                         removes_black_castling_rights_long: None,
+                        is_drop: false,
+                        captures_promoted_piece: None,
                     });
                 }
                 attacks = pop_bit(attacks, target_square);
@@ -2062,14 +3797,15 @@ impl<'a> ChessGame<'a> {
             match self.en_passant_target {
                 Some(s) => {
                     attacks = self.bitboard_constants.pawn_attacks[our_color.idx()][source_square]
-                        & set_bit(0, s);
+                        & s.bitboard().0
+                        & mode.capture_mask();
 
                     if attacks != 0 {
                         target_square = get_lsb_index(attacks).expect("This should not be empty.");
                         moves.push(Move {
-                            from_square: source_square,
+                            from_square: Square::new(source_square),
                             from_piece_type: Some(PieceType::Pawn),
-                            to_square: target_square,
+                            to_square: Square::new(target_square),
                             to_piece_type: Some(PieceType::Pawn),
                             is_check: None,
                             last_en_passant_target_coord: self.en_passant_target,
@@ -2081,6 +3817,8 @@ impl<'a> ChessGame<'a> {
                             removes_white_castling_rights_long: None,
                             removes_black_castling_rights_short: None,
                             removes_black_castling_rights_long: None,
+                            is_drop: false,
+                            captures_promoted_piece: None,
                         });
                     }
                 }
@@ -2187,14 +3925,22 @@ impl<'a> ChessGame<'a> {
     }
 
     pub fn get_bot_move(&mut self) -> Move {
-        if self.legal_moves.len() == 0 {
-            panic!("Something has gone wrong, called get_bot_move when no legal moves were available...");
+        return self.try_get_bot_move().expect(
+            "Something has gone wrong, called get_bot_move when no legal moves were available...",
+        );
+    }
+
+    // Like `get_bot_move`, but an error instead of a panic when the game is already over.
+    pub fn try_get_bot_move(&mut self) -> Result<Move, IllegalMoveError> {
+        self.set_legal_moves(None);
+        if self.legal_moves.is_empty() {
+            return Err(IllegalMoveError::NoLegalMoves);
         }
 
         //let (evaluation, best_move) = self.minimax(4, std::i64::MIN, std::i64::MAX);
-        let (evaluation, best_move) = self.iterative_deepening_minimax();
+        let (_, best_move) = self.iterative_deepening_minimax();
 
-        return best_move.unwrap();
+        return best_move.ok_or(IllegalMoveError::NoLegalMoves);
     }
 
     pub fn get_bot_move_debug(&mut self) -> Move {
@@ -2210,43 +3956,98 @@ impl<'a> ChessGame<'a> {
     }
 
     pub fn iterative_deepening_minimax(&mut self) -> (i64, Option<Move>) {
-        let start_time = std::time::SystemTime::now();
+        return self.search_with_limits(&SearchLimits::time_ms(5_000));
+    }
+
+    // Deepen one ply at a time until we hit one of the limits. With no limits at all, stop at `UNLIMITED_SEARCH_DEPTH`.
+    pub fn search_with_limits(&mut self, limits: &SearchLimits) -> (i64, Option<Move>) {
+        let limits = match limits.is_unlimited() {
+            true => SearchLimits::depth(UNLIMITED_SEARCH_DEPTH),
+            false => *limits,
+        };
+        let start_time = std::time::Instant::now();
+        let start_nodes = self.debug_minimax_calls;
         let mut best_evaluation: i64 = 0;
         let mut best_move: Option<Move> = None;
         let mut search_depth = 1;
 
         // Iteratively deepen...
         loop {
-            //println!("Currently searching depth {search_depth}");
-
-            // Search at the current depth.
-            (best_evaluation, best_move) = self.minimax(search_depth, std::i64::MIN, std::i64::MAX);
+            // Past the first depth, give up partway through if we run out of nodes or time.
+            if search_depth > 1 {
+                self.search_node_limit = limits.nodes.map(|nodes| start_nodes + nodes);
+                self.search_deadline = limits
+                    .time_ms
+                    .map(|ms| start_time + std::time::Duration::from_millis(ms as u64));
+            }
 
-            // See how long that last operation took. If it was too long, stop the search.
-            let time_spent_ms = std::time::SystemTime::now()
-                .duration_since(start_time)
-                .expect("Time went back?")
-                .as_millis();
-            if time_spent_ms >= 5_000 {
+            // Search at the current depth. An unfinished depth is thrown away.
+            let (evaluation, this_move) = self.minimax(search_depth, std::i64::MIN, std::i64::MAX);
+            if self.search_aborted {
                 break;
             }
+            (best_evaluation, best_move) = (evaluation, this_move);
+
+            if let Some(max_depth) = limits.depth {
+                if search_depth >= max_depth {
+                    break;
+                }
+            }
+
+            if let Some(max_nodes) = limits.nodes {
+                if self.debug_minimax_calls - start_nodes >= max_nodes {
+                    break;
+                }
+            }
 
-            //println!("Currently spent {time_spent_ms}ms");
+            // See how long that last operation took. If it was too long, stop the search.
+            if let Some(max_time_ms) = limits.time_ms {
+                if start_time.elapsed().as_millis() >= max_time_ms {
+                    break;
+                }
+            }
 
             // Otherwise, increase our depth and continue!
             search_depth += 1;
         }
 
-        //println!("This search reached depth {search_depth}");
+        self.search_node_limit = None;
+        self.search_deadline = None;
+        self.search_aborted = false;
 
         // Return the best moves we found.
         return (best_evaluation, best_move);
     }
 
+    // Whether `search_with_limits` has run out of nodes or time. The clock is only read every 1024 nodes.
+    fn is_search_budget_spent(&self) -> bool {
+        if let Some(node_limit) = self.search_node_limit {
+            if self.debug_minimax_calls > node_limit {
+                return true;
+            }
+        }
+
+        if let Some(deadline) = self.search_deadline {
+            if self.debug_minimax_calls.is_multiple_of(1024)
+                && std::time::Instant::now() >= deadline
+            {
+                return true;
+            }
+        }
+
+        return false;
+    }
+
     pub fn minimax(&mut self, depth: u32, mut alpha: i64, mut beta: i64) -> (i64, Option<Move>) {
         self.debug_minimax_calls += 1;
 
-        let zobrist_hash_index = self.zobrist_hash % 10_000;
+        // Out of nodes or time. The caller throws this depth away, so the result doesn't matter.
+        if self.search_aborted || self.is_search_budget_spent() {
+            self.search_aborted = true;
+            return (0, None);
+        }
+
+        let zobrist_hash_index = self.zobrist_hash % self.transposition_table_size;
 
         if self.transposition_table.contains_key(&zobrist_hash_index) {
             let entry = self
@@ -2274,19 +4075,7 @@ impl<'a> ChessGame<'a> {
         }
 
         if self.legal_moves.len() == 0 {
-            if self.white_to_move {
-                if self.is_king_attacked(&Color::White) {
-                    return (std::i64::MIN, None);
-                } else {
-                    return (0, None);
-                }
-            } else {
-                if self.is_king_attacked(&Color::Black) {
-                    return (std::i64::MAX, None);
-                } else {
-                    return (0, None);
-                }
-            }
+            return (self.no_legal_moves_evaluation(), None);
         }
 
         if depth == 0 {
@@ -2308,6 +4097,10 @@ impl<'a> ChessGame<'a> {
 
                 // Get the evaluation of that position.
                 (temp_evaluation, _) = self.minimax(depth - 1, alpha, beta);
+                if self.search_aborted {
+                    self.unmake_move(legal_move);
+                    break;
+                }
 
                 // See if it's better.
                 if temp_evaluation > best_evaluation {
@@ -2333,6 +4126,10 @@ impl<'a> ChessGame<'a> {
 
                 // Get the evaluation of that position.
                 (temp_evaluation, _) = self.minimax(depth - 1, alpha, beta);
+                if self.search_aborted {
+                    self.unmake_move(legal_move);
+                    break;
+                }
 
                 // See if it's better.
                 if temp_evaluation < best_evaluation {
@@ -2355,6 +4152,11 @@ impl<'a> ChessGame<'a> {
         // Restore legal moves before exiting.
         self.set_legal_moves(Some(temp_legal_move_clone));
 
+        // Don't cache a half searched node.
+        if self.search_aborted {
+            return (0, None);
+        }
+
         // Find out transposition table node type.
         let node: TranspositionTableNodeType;
         if best_evaluation <= alpha {
@@ -2396,19 +4198,7 @@ impl<'a> ChessGame<'a> {
         self.debug_minimax_calls += 1;
 
         if self.legal_moves.len() == 0 {
-            if self.white_to_move {
-                if self.is_king_attacked(&Color::White) {
-                    return (std::i64::MIN, None);
-                } else {
-                    return (0, None);
-                }
-            } else {
-                if self.is_king_attacked(&Color::Black) {
-                    return (std::i64::MAX, None);
-                } else {
-                    return (0, None);
-                }
-            }
+            return (self.no_legal_moves_evaluation(), None);
         }
 
         if depth == 0 {
@@ -2513,7 +4303,7 @@ impl<'a> ChessGame<'a> {
 
         print!("En-Passant Target Square: ");
         match self.en_passant_target {
-            Some(square) => print!("{}.\n", square_to_coord(square)),
+            Some(square) => print!("{}.\n", square),
             None => print!("None.\n"),
         }
 
@@ -2529,3 +4319,43 @@ impl<'a> ChessGame<'a> {
         println!("FEN: {}", self.export_fen());
     }
 }
+
+// Index into `castling_rook_squares` and the zobrist castling keys.
+fn castling_rights_index(side: Color, castle_side: CastleSides) -> usize {
+    return match (side, castle_side) {
+        (Color::White, CastleSides::Short) => 0,
+        (Color::White, CastleSides::Long) => 1,
+        (Color::Black, CastleSides::Short) => 2,
+        (Color::Black, CastleSides::Long) => 3,
+    };
+}
+
+// Square of the a-file on `side`'s first rank.
+fn back_rank_start(side: Color) -> usize {
+    return match side {
+        Color::White => 56,
+        Color::Black => 0,
+    };
+}
+
+// Where the king and rook end up after castling: g and f files short, c and d files long.
+fn castling_target_squares(king_square: usize, castle_side: CastleSides) -> (usize, usize) {
+    let rank_start = king_square - king_square % 8;
+    return match castle_side {
+        CastleSides::Short => (rank_start + 6, rank_start + 5),
+        CastleSides::Long => (rank_start + 2, rank_start + 3),
+    };
+}
+
+// Every square from `a` to `b`, both included. Both have to be on the same rank.
+fn rank_span(a: usize, b: usize) -> u64 {
+    return (usize::min(a, b)..=usize::max(a, b)).fold(0, set_bit);
+}
+
+// Where the pawn taken en passant stood. Right behind the destination, from the capturer's point of view.
+fn en_passant_captured_square(this_move: &Move, our_side: &Color) -> usize {
+    return match our_side {
+        Color::White => this_move.to_square.index() + 8,
+        Color::Black => this_move.to_square.index() - 8,
+    };
+}
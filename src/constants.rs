@@ -1,6 +1,7 @@
 pub const INITIAL_GAME_STATE_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
-pub const LICHESS_BOT_USERNAME: &str = "botmasterj";
-pub const LICHESS_CHALLENGER_WHITELIST: [&str; 1] = ["The_Big_C"];
+
+// Game phase, used to blend middlegame and endgame scores. All minor and major pieces on the board is the max.
+pub const GAME_PHASE_MAX: i64 = 24;
 
 // Bitboard implementation.
 
@@ -0,0 +1,420 @@
+use crate::chess_game::ChessGame;
+use crate::color::Color;
+use crate::constants;
+use crate::helpers::*;
+use crate::piece::Piece;
+use crate::piece_type::PieceType;
+use crate::search_limits::SearchLimits;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+
+// A searched position. Score is in centipawns from white's perspective, result is 1.0 white won, 0.5 draw, 0.0 black won.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrainingPosition {
+    pub fen: String,
+    pub score: i64,
+    pub result: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct DatagenOptions {
+    pub limits: SearchLimits,
+
+    // Random moves played at the start of each game, so games do not all look the same.
+    pub random_plies: u32,
+
+    // Games that run this long are called a draw.
+    pub max_plies: u32,
+
+    pub seed: u64,
+}
+
+impl Default for DatagenOptions {
+    fn default() -> Self {
+        return DatagenOptions {
+            limits: SearchLimits::depth(3),
+            random_plies: 8,
+            max_plies: 300,
+            seed: 0,
+        };
+    }
+}
+
+/*
+    Packed binary format. One record per position, 32 bytes, little-endian.
+        u64         -> occupancy, bit N set if square N (a8 = 0) has a piece
+        16 bytes    -> one nibble per occupied square, in square order, holding the piece bitboard index (0-11)
+        u8          -> flags. Bit 0 white to move, bits 1-4 castling rights K, Q, k, q
+        u8          -> en passant square, 64 if none
+        i16         -> score, clamped to +-PACKED_SCORE_LIMIT
+        u8          -> result, 0 black won, 1 draw, 2 white won
+        3 bytes     -> reserved, zero
+*/
+pub const PACKED_POSITION_SIZE: usize = 32;
+pub const PACKED_SCORE_LIMIT: i64 = 32_000;
+const PACKED_NO_EN_PASSANT: u8 = 64;
+
+impl TrainingPosition {
+    // `<fen> | <score> | <result>`. The texel tuner reads this format too.
+    pub fn to_text_line(&self) -> String {
+        return format!("{} | {} | {:.1}", self.fen, self.score, self.result);
+    }
+
+    pub fn from_text_line(line: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = line.split('|').map(|p| p.trim()).collect();
+        if parts.len() != 3 {
+            return Err(format!(
+                "Expected `<fen> | <score> | <result>`. Got: `{line}`."
+            ));
+        }
+
+        let score = match parts[1].parse::<i64>() {
+            Ok(s) => s,
+            Err(_) => return Err(format!("Unable to parse score `{}`.", parts[1])),
+        };
+        let result = match parts[2].parse::<f64>() {
+            Ok(r) => r,
+            Err(_) => return Err(format!("Unable to parse result `{}`.", parts[2])),
+        };
+
+        return Ok(TrainingPosition {
+            fen: parts[0].to_string(),
+            score,
+            result,
+        });
+    }
+
+    pub fn to_packed(&self) -> Result<[u8; PACKED_POSITION_SIZE], String> {
+        let mut fen_parts = self.fen.split_whitespace();
+        let board_str = match fen_parts.next() {
+            Some(s) => s,
+            None => return Err("No board position found in FEN.".to_string()),
+        };
+
+        // Walk the board in FEN order, which is also our square order.
+        let mut occupancy: u64 = 0;
+        let mut pieces: Vec<u8> = vec![];
+        let mut square: usize = 0;
+        for c in board_str.chars() {
+            if c == '/' {
+                continue;
+            }
+            if let Some(n) = c.to_digit(10) {
+                square += n as usize;
+                continue;
+            }
+
+            let piece_type = PieceType::char_to_piece_type(c).map_err(|e| e.to_string())?;
+            let color = match c.is_ascii_uppercase() {
+                true => Color::White,
+                false => Color::Black,
+            };
+            if square >= 64 {
+                return Err(format!("Too many squares in FEN `{}`.", self.fen));
+            }
+
+            occupancy = set_bit(occupancy, square);
+            pieces.push((Piece::new(color, piece_type).bitboard_index()) as u8);
+            square += 1;
+        }
+        if square != 64 {
+            return Err(format!(
+                "Board in FEN `{}` does not have 64 squares.",
+                self.fen
+            ));
+        }
+        if pieces.len() > 32 {
+            return Err(format!("Too many pieces to pack in FEN `{}`.", self.fen));
+        }
+
+        let mut flags: u8 = match fen_parts.next() {
+            Some("w") => 1,
+            Some("b") => 0,
+            _ => return Err(format!("Unsure whose turn it is in FEN `{}`.", self.fen)),
+        };
+        for c in fen_parts.next().unwrap_or("-").chars() {
+            flags |= match c {
+                'K' => 1 << 1,
+                'Q' => 1 << 2,
+                'k' => 1 << 3,
+                'q' => 1 << 4,
+                _ => 0,
+            };
+        }
+
+        let en_passant = match str_coord_to_square(fen_parts.next().unwrap_or("-")) {
+            Ok(s) => s as u8,
+            Err(_) => PACKED_NO_EN_PASSANT,
+        };
+
+        let result: u8 = if self.result > 0.75 {
+            2
+        } else if self.result < 0.25 {
+            0
+        } else {
+            1
+        };
+
+        let mut bytes = [0_u8; PACKED_POSITION_SIZE];
+        bytes[0..8].copy_from_slice(&occupancy.to_le_bytes());
+        for (i, piece) in pieces.iter().enumerate() {
+            bytes[8 + i / 2] |= piece << ((i % 2) * 4);
+        }
+        bytes[24] = flags;
+        bytes[25] = en_passant;
+        let score = self.score.clamp(-PACKED_SCORE_LIMIT, PACKED_SCORE_LIMIT) as i16;
+        bytes[26..28].copy_from_slice(&score.to_le_bytes());
+        bytes[28] = result;
+
+        return Ok(bytes);
+    }
+
+    pub fn from_packed(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != PACKED_POSITION_SIZE {
+            return Err(format!(
+                "Packed position must be {PACKED_POSITION_SIZE} bytes. Got: {}.",
+                bytes.len()
+            ));
+        }
+
+        let occupancy = u64::from_le_bytes(bytes[0..8].try_into().expect("Length checked above."));
+        if count_bits(occupancy) > 32 {
+            return Err("Packed position has more than 32 pieces.".to_string());
+        }
+
+        let mut fen = String::new();
+        let mut empty_count = 0;
+        let mut piece_number = 0;
+        for square in 0..64 {
+            if get_bit(occupancy, square) != 0 {
+                if empty_count > 0 {
+                    fen += &empty_count.to_string();
+                    empty_count = 0;
+                }
+
+                let piece = (bytes[8 + piece_number / 2] >> ((piece_number % 2) * 4)) & 0xF;
+                if piece >= 12 {
+                    return Err(format!("Invalid piece {piece} in packed position."));
+                }
+                let color = match piece < 6 {
+                    true => Color::White,
+                    false => Color::Black,
+                };
+                fen.push(PieceType::bitboard_index_to_piece_type(piece as usize).to_char(color));
+                piece_number += 1;
+            } else {
+                empty_count += 1;
+            }
+
+            if square % 8 == 7 {
+                if empty_count > 0 {
+                    fen += &empty_count.to_string();
+                    empty_count = 0;
+                }
+                if square != 63 {
+                    fen += "/";
+                }
+            }
+        }
+
+        let flags = bytes[24];
+        fen += match flags & 1 {
+            1 => " w ",
+            _ => " b ",
+        };
+
+        let mut castling = String::new();
+        for (bit, c) in [(1, 'K'), (2, 'Q'), (3, 'k'), (4, 'q')] {
+            if flags & (1 << bit) != 0 {
+                castling.push(c);
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        fen += &castling;
+
+        fen += " ";
+        match bytes[25] {
+            PACKED_NO_EN_PASSANT => fen += "-",
+            s if s < 64 => fen += &square_to_coord(s as usize),
+            s => return Err(format!("Invalid en passant square {s} in packed position.")),
+        }
+
+        let score = i16::from_le_bytes([bytes[26], bytes[27]]) as i64;
+        let result = match bytes[28] {
+            0 => 0.0,
+            1 => 0.5,
+            2 => 1.0,
+            r => return Err(format!("Invalid result {r} in packed position.")),
+        };
+
+        return Ok(TrainingPosition { fen, score, result });
+    }
+}
+
+pub fn append_text_positions(path: &str, positions: &[TrainingPosition]) -> Result<(), String> {
+    let mut contents = String::new();
+    for position in positions.iter() {
+        contents += &position.to_text_line();
+        contents += "\n";
+    }
+
+    return append_to_file(path, contents.as_bytes());
+}
+
+pub fn append_packed_positions(path: &str, positions: &[TrainingPosition]) -> Result<(), String> {
+    let mut contents: Vec<u8> = vec![];
+    for position in positions.iter() {
+        contents.extend_from_slice(&position.to_packed()?);
+    }
+
+    return append_to_file(path, &contents);
+}
+
+pub fn load_text_positions(path: &str) -> Result<Vec<TrainingPosition>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) => return Err(format!("Unable to read data file `{path}`. Error: {e}")),
+    };
+
+    let mut positions: Vec<TrainingPosition> = vec![];
+    for (line_number, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match TrainingPosition::from_text_line(line) {
+            Ok(p) => positions.push(p),
+            Err(e) => return Err(format!("Line {}: {e}", line_number + 1)),
+        }
+    }
+
+    return Ok(positions);
+}
+
+pub fn load_packed_positions(path: &str) -> Result<Vec<TrainingPosition>, String> {
+    let bytes = match fs::read(path) {
+        Ok(b) => b,
+        Err(e) => return Err(format!("Unable to read data file `{path}`. Error: {e}")),
+    };
+
+    if bytes.len() % PACKED_POSITION_SIZE != 0 {
+        return Err(format!(
+            "Data file `{path}` is not a whole number of {PACKED_POSITION_SIZE} byte records."
+        ));
+    }
+
+    let mut positions: Vec<TrainingPosition> = vec![];
+    for (i, record) in bytes.chunks(PACKED_POSITION_SIZE).enumerate() {
+        match TrainingPosition::from_packed(record) {
+            Ok(p) => positions.push(p),
+            Err(e) => return Err(format!("Record {i}: {e}")),
+        }
+    }
+
+    return Ok(positions);
+}
+
+fn append_to_file(path: &str, contents: &[u8]) -> Result<(), String> {
+    let mut file = match fs::OpenOptions::new().create(true).append(true).open(path) {
+        Ok(f) => f,
+        Err(e) => return Err(format!("Unable to open data file `{path}`. Error: {e}")),
+    };
+
+    return match file.write_all(contents) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(format!("Unable to write data file `{path}`. Error: {e}")),
+    };
+}
+
+/**
+ * Plays self-play games and hands back the quiet positions from each one.
+ * Openings are picked by a seeded rng, so the same options always produce the same games.
+ */
+pub struct SelfPlay {
+    game: ChessGame,
+    options: DatagenOptions,
+    rng: ChaCha8Rng,
+}
+
+impl SelfPlay {
+    pub fn new(game: ChessGame, options: DatagenOptions) -> Self {
+        let rng = ChaCha8Rng::seed_from_u64(options.seed);
+        return SelfPlay { game, options, rng };
+    }
+
+    // Returns an empty list if the random opening ran into a finished game.
+    pub fn play_game(&mut self) -> Vec<TrainingPosition> {
+        let game = &mut self.game;
+        let _ = game.import_fen(constants::INITIAL_GAME_STATE_FEN);
+        game.transposition_table.clear();
+        game.set_legal_moves(None);
+
+        for _ in 0..self.options.random_plies {
+            if game.legal_moves.is_empty() {
+                return vec![];
+            }
+            let random_move = game.legal_moves[self.rng.gen_range(0..game.legal_moves.len())];
+            game.make_move(&random_move, true);
+        }
+        if game.legal_moves.is_empty() {
+            return vec![];
+        }
+
+        let mut scored_fens: Vec<(String, i64)> = vec![];
+        let mut seen_positions: HashMap<u64, u32> = HashMap::new();
+        let mut plies: u32 = 0;
+        let result: f64;
+
+        loop {
+            let side_to_move = match game.white_to_move {
+                true => Color::White,
+                false => Color::Black,
+            };
+
+            if game.legal_moves.is_empty() {
+                result = match (game.is_king_attacked(&side_to_move), side_to_move) {
+                    (true, Color::White) => 0.0,
+                    (true, Color::Black) => 1.0,
+                    (false, _) => 0.5,
+                };
+                break;
+            }
+
+            let repetitions = seen_positions.entry(game.zobrist_hash).or_insert(0);
+            *repetitions += 1;
+            if *repetitions >= 3
+                || plies >= self.options.max_plies
+                || game.is_insufficient_material()
+            {
+                result = 0.5;
+                break;
+            }
+
+            let (score, best_move) = game.search_with_limits(&self.options.limits);
+            let best_move = best_move.expect("We have legal moves, so search finds a move.");
+
+            // Only keep quiet positions. Tactics and mate scores make for noisy labels.
+            let is_quiet = !game.is_king_attacked(&side_to_move)
+                && best_move.to_piece_type.is_none()
+                && !best_move.is_en_passant_capture
+                && best_move.pawn_promoting_to.is_none();
+            let is_mate_score = score == i64::MIN || score == i64::MAX;
+            if is_quiet && !is_mate_score {
+                scored_fens.push((game.export_fen(), score));
+            }
+
+            game.make_move(&best_move, true);
+            plies += 1;
+        }
+
+        return scored_fens
+            .into_iter()
+            .map(|(fen, score)| TrainingPosition { fen, score, result })
+            .collect();
+    }
+}
@@ -0,0 +1,325 @@
+use crate::chess_game::ChessGame;
+use crate::color::Color;
+use crate::evaluation_parameters::EvaluationParameters;
+use crate::helpers::*;
+use crate::kpk_bitbase::KpkBitbase;
+use crate::piece::Piece;
+use crate::piece_type::PieceType;
+
+// Added on top of material for endings we know are won, so the engine goes for them.
+pub const KNOWN_WIN: i64 = 2_000;
+
+// Mop-up only kicks in once one side is this far ahead on material.
+const MOP_UP_MATERIAL_ADVANTAGE: i64 = 400;
+
+// Light squares in our square order (a8 = 0 is light).
+const LIGHT_SQUARES: u64 = 0xAA55_AA55_AA55_AA55;
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = FILE_A << 7;
+
+// Piece counts for one side. Kings are assumed.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct MaterialCount {
+    pub pawns: u32,
+    pub knights: u32,
+    pub bishops: u32,
+    pub rooks: u32,
+    pub queens: u32,
+}
+
+impl MaterialCount {
+    pub fn from_game(game: &ChessGame, side: Color) -> Self {
+        let count = |piece_type: PieceType| -> u32 {
+            return count_bits(game.pieces(Piece::new(side, piece_type)).0) as u32;
+        };
+
+        return MaterialCount {
+            pawns: count(PieceType::Pawn),
+            knights: count(PieceType::Knight),
+            bishops: count(PieceType::Bishop),
+            rooks: count(PieceType::Rook),
+            queens: count(PieceType::Queen),
+        };
+    }
+
+    pub fn is_bare_king(&self) -> bool {
+        return *self == MaterialCount::default();
+    }
+
+    pub fn minors(&self) -> u32 {
+        return self.knights + self.bishops;
+    }
+
+    pub fn pieces(&self) -> u32 {
+        return self.minors() + self.rooks + self.queens;
+    }
+
+    pub fn value(&self, parameters: &EvaluationParameters) -> i64 {
+        return self.pawns as i64 * parameters.pawn_value
+            + self.knights as i64 * parameters.knight_value
+            + self.bishops as i64 * parameters.bishop_value
+            + self.rooks as i64 * parameters.rook_value
+            + self.queens as i64 * parameters.queen_value;
+    }
+}
+
+/**
+ * Scores for endings where the normal evaluation does not know what it is doing.
+ * Returns the whole evaluation (white's perspective), or `None` if this is not one of those endings.
+ */
+pub fn evaluate_endgame(game: &ChessGame) -> Option<i64> {
+    // None of this holds with other win conditions, or if pieces can still be dropped.
+    if !game.variant.has_standard_endgames() || !game.has_empty_pockets() {
+        return None;
+    }
+
+    let white = MaterialCount::from_game(game, Color::White);
+    let black = MaterialCount::from_game(game, Color::Black);
+
+    if is_drawn_material(&white, &black) {
+        return Some(0);
+    }
+
+    for (strong_side, strong, weak) in [(Color::White, white, black), (Color::Black, black, white)]
+    {
+        if !weak.is_bare_king() {
+            continue;
+        }
+
+        let score = if strong
+            == (MaterialCount {
+                queens: 1,
+                ..Default::default()
+            })
+            || strong
+                == (MaterialCount {
+                    rooks: 1,
+                    ..Default::default()
+                }) {
+            Some(evaluate_kxk(game, strong_side, &strong))
+        } else if strong
+            == (MaterialCount {
+                bishops: 1,
+                knights: 1,
+                ..Default::default()
+            })
+        {
+            Some(evaluate_kbnk(game, strong_side, &strong))
+        } else if strong
+            == (MaterialCount {
+                pawns: 1,
+                ..Default::default()
+            })
+        {
+            evaluate_kpk(game, strong_side)
+        } else if is_wrong_bishop_draw(game, strong_side, &strong) {
+            Some(0)
+        } else {
+            None
+        };
+
+        if let Some(s) = score {
+            return Some(match strong_side {
+                Color::White => s,
+                Color::Black => -s,
+            });
+        }
+    }
+
+    return None;
+}
+
+/**
+ * When one side is well ahead, push the losing king to the edge and walk our king over to help.
+ * Endgame only, returned as (side it helps, bonus).
+ */
+pub fn mop_up(game: &ChessGame) -> Option<(Color, i64)> {
+    if !game.variant.has_standard_endgames() || !game.has_empty_pockets() {
+        return None;
+    }
+
+    let white_material =
+        MaterialCount::from_game(game, Color::White).value(&game.evaluation_parameters);
+    let black_material =
+        MaterialCount::from_game(game, Color::Black).value(&game.evaluation_parameters);
+
+    let strong_side = if white_material - black_material >= MOP_UP_MATERIAL_ADVANTAGE {
+        Color::White
+    } else if black_material - white_material >= MOP_UP_MATERIAL_ADVANTAGE {
+        Color::Black
+    } else {
+        return None;
+    };
+
+    let (strong_king, weak_king) = king_squares(game, strong_side)?;
+    let bonus = (47 * center_manhattan_distance(weak_king)
+        + 16 * (14 - manhattan_distance(strong_king, weak_king)))
+        / 10;
+
+    return Some((strong_side, bonus));
+}
+
+// Neither side can force mate, or it is close enough that we should not play for it.
+fn is_drawn_material(white: &MaterialCount, black: &MaterialCount) -> bool {
+    if white.pawns != 0 || black.pawns != 0 || white.rooks != 0 || black.rooks != 0 {
+        return false;
+    }
+    if white.queens != 0 || black.queens != 0 {
+        return false;
+    }
+
+    // KK, KNK, KBK.
+    if white.minors() + black.minors() <= 1 {
+        return true;
+    }
+
+    // KNNK.
+    for (strong, weak) in [(white, black), (black, white)] {
+        if weak.is_bare_king() && strong.knights == 2 && strong.bishops == 0 {
+            return true;
+        }
+    }
+
+    // One minor each.
+    return white.minors() == 1 && black.minors() == 1;
+}
+
+// KQK and KRK. Drive the king to the edge, bring ours along.
+fn evaluate_kxk(game: &ChessGame, strong_side: Color, strong: &MaterialCount) -> i64 {
+    let (strong_king, weak_king) = match king_squares(game, strong_side) {
+        Some(k) => k,
+        None => return 0,
+    };
+
+    return KNOWN_WIN
+        + strong.value(&game.evaluation_parameters)
+        + 20 * center_manhattan_distance(weak_king)
+        + 10 * (7 - chebyshev_distance(strong_king, weak_king));
+}
+
+// KBNK. Mate only happens in a corner the bishop can reach, so push the king there.
+fn evaluate_kbnk(game: &ChessGame, strong_side: Color, strong: &MaterialCount) -> i64 {
+    let (strong_king, weak_king) = match king_squares(game, strong_side) {
+        Some(k) => k,
+        None => return 0,
+    };
+
+    let bishops = game.pieces(Piece::new(strong_side, PieceType::Bishop)).0;
+    let corners: [usize; 2] = match bishops & LIGHT_SQUARES != 0 {
+        true => [0, 63],
+        false => [7, 56],
+    };
+    let corner_distance = corners
+        .iter()
+        .map(|c| manhattan_distance(weak_king, *c))
+        .min()
+        .expect("Two corners.");
+
+    return KNOWN_WIN
+        + strong.value(&game.evaluation_parameters)
+        + 10 * center_manhattan_distance(weak_king)
+        + 20 * (14 - corner_distance)
+        + 10 * (7 - chebyshev_distance(strong_king, weak_king));
+}
+
+// KPK is solved, so just look it up. Winning positions still prefer a more advanced pawn.
+fn evaluate_kpk(game: &ChessGame, strong_side: Color) -> Option<i64> {
+    let (strong_king, weak_king) = king_squares(game, strong_side)?;
+    let pawn = get_lsb_index(game.pieces(Piece::new(strong_side, PieceType::Pawn)).0)?;
+
+    // Pawns can't really be on the back ranks, but FENs can say anything.
+    let pawn_rank = relative_rank(pawn, strong_side);
+    if pawn_rank == 0 || pawn_rank == 7 {
+        return None;
+    }
+
+    let strong_to_move = game.white_to_move == matches!(strong_side, Color::White);
+    if KpkBitbase::get().probe(strong_side, strong_king, pawn, weak_king, strong_to_move) {
+        return Some(KNOWN_WIN + game.evaluation_parameters.pawn_value + 10 * pawn_rank as i64);
+    }
+
+    return Some(0);
+}
+
+// Bishop and rook pawns, where the bishop can not cover the promotion square and the king got there first.
+fn is_wrong_bishop_draw(game: &ChessGame, strong_side: Color, strong: &MaterialCount) -> bool {
+    if strong.bishops != 1 || strong.pawns == 0 || strong.pieces() != 1 {
+        return false;
+    }
+
+    let pawns = game.pieces(Piece::new(strong_side, PieceType::Pawn)).0;
+    let file = if pawns & !FILE_A == 0 {
+        0
+    } else if pawns & !FILE_H == 0 {
+        7
+    } else {
+        return false;
+    };
+
+    let promotion_square = match strong_side {
+        Color::White => file,
+        Color::Black => 56 + file,
+    };
+    let bishops = game.pieces(Piece::new(strong_side, PieceType::Bishop)).0;
+    let bishop_is_light = bishops & LIGHT_SQUARES != 0;
+    let promotion_is_light = get_bit(LIGHT_SQUARES, promotion_square) != 0;
+    if bishop_is_light == promotion_is_light {
+        return false;
+    }
+
+    return match king_squares(game, strong_side) {
+        Some((_, weak_king)) => chebyshev_distance(weak_king, promotion_square) <= 1,
+        None => false,
+    };
+}
+
+// (strong king, weak king)
+fn king_squares(game: &ChessGame, strong_side: Color) -> Option<(usize, usize)> {
+    let weak_side = match strong_side {
+        Color::White => Color::Black,
+        Color::Black => Color::White,
+    };
+    let strong_king = game
+        .pieces(Piece::new(strong_side, PieceType::King))
+        .lsb()?;
+    let weak_king = game.pieces(Piece::new(weak_side, PieceType::King)).lsb()?;
+
+    return Some((strong_king.index(), weak_king.index()));
+}
+
+fn file_of(square: usize) -> usize {
+    return square % 8;
+}
+
+// 0 is the first rank, 7 the eighth. Our squares start at a8.
+fn rank_of(square: usize) -> usize {
+    return 7 - square / 8;
+}
+
+// Rank counted from `side`'s end of the board.
+fn relative_rank(square: usize, side: Color) -> usize {
+    return match side {
+        Color::White => rank_of(square),
+        Color::Black => 7 - rank_of(square),
+    };
+}
+
+fn chebyshev_distance(a: usize, b: usize) -> i64 {
+    let file_distance = (file_of(a) as i64 - file_of(b) as i64).abs();
+    let rank_distance = (rank_of(a) as i64 - rank_of(b) as i64).abs();
+    return i64::max(file_distance, rank_distance);
+}
+
+fn manhattan_distance(a: usize, b: usize) -> i64 {
+    let file_distance = (file_of(a) as i64 - file_of(b) as i64).abs();
+    let rank_distance = (rank_of(a) as i64 - rank_of(b) as i64).abs();
+    return file_distance + rank_distance;
+}
+
+// 0 in the middle four squares, 6 in the corners.
+fn center_manhattan_distance(square: usize) -> i64 {
+    let file = file_of(square) as i64;
+    let rank = rank_of(square) as i64;
+    return i64::max(3 - file, file - 4) + i64::max(3 - rank, rank - 4);
+}
@@ -0,0 +1,400 @@
+use crate::chess_game::ChessGame;
+use crate::color::Color;
+use crate::evaluation_mode::EvaluationMode;
+use crate::evaluation_parameters::EvaluationParameters;
+use crate::fen_error::FenError;
+use crate::game_result::GameResult;
+use crate::nnue::NnueNetwork;
+use crate::pgn::PgnGame;
+use crate::piece_type::PieceType;
+use crate::r#move::Move;
+use crate::search_limits::SearchLimits;
+use crate::variant::Variant;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+// How far past its clock an engine can answer before it loses on time. Covers timer granularity, and for UCI engines,
+// passing the messages back and forth.
+pub const TIME_FORFEIT_MARGIN_MS: u128 = 100;
+
+// One side of a match: a name for the reports, and how its `ChessGame` gets set up.
+#[derive(Clone, Debug)]
+pub struct MatchEngine {
+    pub name: String,
+    pub evaluation_parameters: EvaluationParameters,
+
+    // Evaluates with this network when set, classically otherwise.
+    pub nnue_network: Option<Arc<NnueNetwork>>,
+}
+
+// Sudden death plus an increment, per side. Used to share out thinking time, see `play_game`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TimeControl {
+    pub base_ms: u128,
+    pub increment_ms: u128,
+}
+
+/**
+ * When to call a game early. Scores are the engines' own, in centipawns from white's perspective.
+ * Setting any of the `_plies` counts to 0 turns that rule off.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Adjudication {
+    // Lost once the score has been at least `resign_score` against one side for `resign_plies` plies in a row.
+    // Both engines search in turn, so both have to agree.
+    pub resign_score: i64,
+    pub resign_plies: u32,
+
+    // Drawn once the score has stayed within `draw_score` of level for `draw_plies` plies, from `draw_after_ply` on.
+    pub draw_score: i64,
+    pub draw_plies: u32,
+    pub draw_after_ply: u32,
+
+    // Drawn after this many plies, whatever the score.
+    pub max_plies: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MatchOptions {
+    pub variant: Variant,
+    pub time_control: TimeControl,
+    pub adjudication: Adjudication,
+}
+
+// Why a match game ended.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Termination {
+    // Mate, stalemate, or a variant win.
+    OnTheBoard,
+
+    Repetition,
+    FiftyMoves,
+    InsufficientMaterial,
+
+    ResignAdjudication,
+    DrawAdjudication,
+    MaxPlies,
+
+    // The loser broke the rules: ran out of time, sent an illegal move, or stopped answering.
+    TimeForfeit,
+    IllegalMove,
+    EngineFailure,
+}
+
+/**
+ * Keeps the game between two players: the board they both play on, the draw rules, adjudication and the PGN.
+ * Players ask `game_over` before each move and hand theirs to `play_move`.
+ */
+pub struct Referee {
+    // Always has its legal moves set.
+    pub game: ChessGame,
+    pub pgn: PgnGame,
+
+    adjudication: Adjudication,
+    seen_positions: HashMap<u64, u32>,
+    plies: u32,
+    plies_since_progress: u32,
+    resign_streak: (Option<Color>, u32),
+    draw_streak: u32,
+    adjudicated: Option<(GameResult, Termination)>,
+}
+
+// A finished game, with the PGN of it. `result` is by color, `pgn` says who was white.
+#[derive(Clone, Debug)]
+pub struct MatchGame {
+    pub result: GameResult,
+    pub termination: Termination,
+    pub pgn: PgnGame,
+}
+
+impl MatchEngine {
+    // Default weights, classical evaluation.
+    pub fn new(name: &str) -> Self {
+        return MatchEngine {
+            name: name.to_string(),
+            evaluation_parameters: EvaluationParameters::default(),
+            nnue_network: None,
+        };
+    }
+
+    // A fresh game that searches and evaluates the way this engine does. Import a position before using it.
+    pub fn new_game(&self, variant: Variant) -> ChessGame {
+        let mut game = ChessGame::new();
+        game.variant = variant;
+        game.evaluation_parameters = self.evaluation_parameters.clone();
+        if self.nnue_network.is_some() {
+            game.set_nnue_network(self.nnue_network.clone());
+            game.set_evaluation_mode(EvaluationMode::Nnue)
+                .expect("Network was just set.");
+        }
+        return game;
+    }
+}
+
+impl TimeControl {
+    // `<base seconds>+<increment seconds>`, like `10+0.1`. The increment is optional.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (base, increment) = s.split_once('+').unwrap_or((s, "0"));
+        let to_ms = |seconds: &str| -> Result<u128, String> {
+            return match seconds.trim().parse::<f64>() {
+                Ok(seconds) if seconds >= 0.0 => Ok((seconds * 1000.0).round() as u128),
+                _ => Err(format!(
+                    "Invalid time control `{s}`. Expected something like `10+0.1`."
+                )),
+            };
+        };
+        return Ok(TimeControl {
+            base_ms: to_ms(base)?,
+            increment_ms: to_ms(increment)?,
+        });
+    }
+
+    // The search always finishes its first depth, which can overshoot a small budget, so it aims well short.
+    pub fn move_limits(&self, remaining_ms: u128) -> SearchLimits {
+        return SearchLimits::time_ms((remaining_ms / 40 + self.increment_ms / 2).max(1));
+    }
+}
+
+impl Default for Adjudication {
+    fn default() -> Self {
+        return Adjudication {
+            resign_score: 1_000,
+            resign_plies: 8,
+            draw_score: 10,
+            draw_plies: 16,
+            draw_after_ply: 80,
+            max_plies: 400,
+        };
+    }
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            Termination::OnTheBoard => "on the board",
+            Termination::Repetition => "threefold repetition",
+            Termination::FiftyMoves => "fifty-move rule",
+            Termination::InsufficientMaterial => "insufficient material",
+            Termination::ResignAdjudication => "resign adjudication",
+            Termination::DrawAdjudication => "draw adjudication",
+            Termination::MaxPlies => "move limit",
+            Termination::TimeForfeit => "time forfeit",
+            Termination::IllegalMove => "illegal move",
+            Termination::EngineFailure => "engine failure",
+        };
+        return write!(f, "{reason}");
+    }
+}
+
+impl Referee {
+    // Adjudication as in `adjudication`, from `opening_fen` in `variant`. The fifty-move count picks up from the
+    // FEN's halfmove clock.
+    pub fn new(
+        variant: Variant,
+        opening_fen: &str,
+        adjudication: Adjudication,
+    ) -> Result<Self, FenError> {
+        let mut game = ChessGame::new();
+        game.variant = variant;
+        game.import_fen(opening_fen)?;
+        game.set_legal_moves(None);
+
+        let pgn = PgnGame::starting_from(&game);
+        let mut seen_positions = HashMap::new();
+        seen_positions.insert(game.zobrist_hash, 1);
+        return Ok(Referee {
+            game,
+            pgn,
+            adjudication,
+            seen_positions,
+            plies: 0,
+            plies_since_progress: halfmove_clock(opening_fen),
+            resign_streak: (None, 0),
+            draw_streak: 0,
+            adjudicated: None,
+        });
+    }
+
+    pub fn side_to_move(&self) -> Color {
+        return match self.game.white_to_move {
+            true => Color::White,
+            false => Color::Black,
+        };
+    }
+
+    // The result, and why, once the game is over. `None` while the side to move still has to play.
+    pub fn game_over(&self) -> Option<(GameResult, Termination)> {
+        if self.adjudicated.is_some() {
+            return self.adjudicated;
+        }
+
+        let game_result = self.game.game_result();
+        if game_result.is_over() {
+            return Some((game_result, Termination::OnTheBoard));
+        }
+        if self.seen_positions.get(&self.game.zobrist_hash) >= Some(&3) {
+            return Some((GameResult::Draw, Termination::Repetition));
+        }
+        if self.plies_since_progress >= 100 {
+            return Some((GameResult::Draw, Termination::FiftyMoves));
+        }
+        if self.game.is_insufficient_material() {
+            return Some((GameResult::Draw, Termination::InsufficientMaterial));
+        }
+        if self.adjudication.max_plies > 0 && self.plies >= self.adjudication.max_plies {
+            return Some((GameResult::Draw, Termination::MaxPlies));
+        }
+        return None;
+    }
+
+    /**
+     * Plays a legal move for the side to move. `score` is what the mover thought of the position before it, from
+     * white's perspective. Moves played without one break the adjudication streaks.
+     */
+    pub fn play_move(&mut self, this_move: &Move, score: Option<i64>) {
+        self.pgn.push_move(&mut self.game, this_move, score);
+        self.game.make_move(this_move, true);
+        self.plies += 1;
+        *self
+            .seen_positions
+            .entry(self.game.zobrist_hash)
+            .or_insert(0) += 1;
+
+        let is_progress = this_move.from_piece_type == Some(PieceType::Pawn)
+            || this_move.to_piece_type.is_some()
+            || this_move.is_en_passant_capture;
+        self.plies_since_progress = match is_progress {
+            true => 0,
+            false => self.plies_since_progress + 1,
+        };
+
+        let adjudication = self.adjudication;
+        if adjudication.resign_plies > 0 {
+            let winning_side = match score {
+                Some(s) if s >= adjudication.resign_score => Some(Color::White),
+                Some(s) if s <= -adjudication.resign_score => Some(Color::Black),
+                _ => None,
+            };
+            self.resign_streak = match (winning_side, self.resign_streak) {
+                (Some(side), (Some(streak_side), count)) if side == streak_side => {
+                    (Some(side), count + 1)
+                }
+                (Some(side), _) => (Some(side), 1),
+                (None, _) => (None, 0),
+            };
+            if let (Some(side), count) = self.resign_streak {
+                if count >= adjudication.resign_plies {
+                    self.adjudicated =
+                        Some((GameResult::win_for(side), Termination::ResignAdjudication));
+                }
+            }
+        }
+
+        if adjudication.draw_plies > 0 {
+            let is_level = score
+                .is_some_and(|s| s >= -adjudication.draw_score && s <= adjudication.draw_score);
+            self.draw_streak = match is_level && self.plies > adjudication.draw_after_ply {
+                true => self.draw_streak + 1,
+                false => 0,
+            };
+            if self.draw_streak >= adjudication.draw_plies {
+                self.adjudicated = Some((GameResult::Draw, Termination::DrawAdjudication));
+            }
+        }
+    }
+
+    // Writes the result into the PGN. Players that break the rules lose with whatever termination says so.
+    pub fn finish(mut self, result: GameResult, termination: Termination) -> MatchGame {
+        self.pgn.set_result(result);
+        self.pgn.set_tag("Termination", &termination.to_string());
+        return MatchGame {
+            result,
+            termination,
+            pgn: self.pgn,
+        };
+    }
+
+    // `loser` broke the rules. `detail` goes in a comment after the last move.
+    pub fn forfeit(mut self, loser: Color, termination: Termination, detail: &str) -> MatchGame {
+        match self.pgn.moves.last_mut() {
+            Some(last_move) => last_move.comments.push(detail.to_string()),
+            None => self.pgn.comments.push(detail.to_string()),
+        }
+        let winner = match loser {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        };
+        return self.finish(GameResult::win_for(winner), termination);
+    }
+}
+
+/**
+ * Plays one game between `white` and `black` from `opening_fen`. Each engine gets its own `ChessGame`, so it keeps its
+ * own transposition table through the game, and every move is made on both.
+ */
+pub fn play_game(
+    white: &MatchEngine,
+    black: &MatchEngine,
+    opening_fen: &str,
+    options: &MatchOptions,
+) -> Result<MatchGame, FenError> {
+    let mut referee = Referee::new(options.variant, opening_fen, options.adjudication)?;
+    referee.pgn.set_tag("Event", "Engine match");
+    referee.pgn.set_tag("White", &white.name);
+    referee.pgn.set_tag("Black", &black.name);
+
+    // Indexed by color, like the clocks.
+    let mut games = [
+        white.new_game(options.variant),
+        black.new_game(options.variant),
+    ];
+    for game in games.iter_mut() {
+        game.import_fen(opening_fen)?;
+        game.set_legal_moves(None);
+    }
+
+    let time_control = options.time_control;
+    let mut clocks = [time_control.base_ms; 2];
+
+    let (result, termination) = loop {
+        if let Some(game_over) = referee.game_over() {
+            break game_over;
+        }
+        let side = referee.side_to_move();
+        let mover = side.idx();
+
+        let start_time = std::time::SystemTime::now();
+        let (score, best_move) =
+            games[mover].search_with_limits(&time_control.move_limits(clocks[mover]));
+        let time_spent_ms = std::time::SystemTime::now()
+            .duration_since(start_time)
+            .expect("Time went back?")
+            .as_millis();
+        if time_spent_ms > clocks[mover] + TIME_FORFEIT_MARGIN_MS {
+            let name = [&white.name, &black.name][mover];
+            let detail = format!("{name} ran out of time.");
+            return Ok(referee.forfeit(side, Termination::TimeForfeit, &detail));
+        }
+        clocks[mover] = clocks[mover].saturating_sub(time_spent_ms) + time_control.increment_ms;
+
+        let best_move = best_move.expect("The game isn't over, so there's a move to find.");
+        referee.play_move(&best_move, Some(score));
+        for game in games.iter_mut() {
+            game.make_move(&best_move, true);
+        }
+    };
+
+    return Ok(referee.finish(result, termination));
+}
+
+// The halfmove clock after the four position fields. Three-check counts (`2+3`) can come before it, and openings
+// without one, like EPD positions, start from 0.
+fn halfmove_clock(fen: &str) -> u32 {
+    return fen
+        .split_whitespace()
+        .skip(4)
+        .find(|field| !field.contains('+'))
+        .and_then(|field| field.parse().ok())
+        .unwrap_or(0);
+}
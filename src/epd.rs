@@ -0,0 +1,219 @@
+use crate::chess_game::ChessGame;
+use crate::epd_error::EpdError;
+use crate::r#move::Move;
+use crate::search_limits::SearchLimits;
+
+/**
+ * One line of an EPD test suite, like WAC or STS: a position, then operations such as `bm Qg6; id "WAC.001";`.
+ * `bm` lists the moves that solve it, `am` the moves that fail it.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct EpdPosition {
+    // The four position fields, which `import_fen` reads as a FEN without move counters.
+    pub fen: String,
+
+    // Every operation as written, opcode then operands with their quotes taken off.
+    pub operations: Vec<(String, Vec<String>)>,
+
+    // The operations we understand, pulled out of `operations`. Moves are as written, normally SAN.
+    pub best_moves: Vec<String>,
+    pub avoid_moves: Vec<String>,
+    pub id: Option<String>,
+    pub comment: Option<String>,
+}
+
+// How a search did on one position, see `EpdPosition::solve`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EpdOutcome {
+    // The move the search picked, in SAN. `None` if it didn't pick one.
+    pub found: Option<String>,
+    pub solved: bool,
+    pub time_ms: u128,
+    pub nodes: u64,
+}
+
+impl EpdPosition {
+    pub fn parse(line: &str) -> Result<Self, EpdError> {
+        let mut rest = line.trim();
+        let mut position_fields = vec![];
+        while position_fields.len() < 4 {
+            let (field, after) = next_word(rest);
+            if field.is_empty() {
+                return Err(EpdError::MissingFields(position_fields.len()));
+            }
+            position_fields.push(field);
+            rest = after;
+        }
+
+        // Some suites keep the FEN move counters. They're just numbers, opcodes never are.
+        let (halfmoves, after_halfmoves) = next_word(rest);
+        let (fullmoves, after_fullmoves) = next_word(after_halfmoves);
+        if halfmoves.parse::<u32>().is_ok() && fullmoves.parse::<u32>().is_ok() {
+            rest = after_fullmoves;
+        }
+
+        let operations = parse_operations(rest)?;
+        let mut best_moves = vec![];
+        let mut avoid_moves = vec![];
+        let mut id = None;
+        let mut comment = None;
+        for (opcode, operands) in operations.iter() {
+            match opcode.as_str() {
+                "bm" => best_moves.extend(operands.iter().cloned()),
+                "am" => avoid_moves.extend(operands.iter().cloned()),
+                "id" => id = operands.first().cloned(),
+                "c0" => comment = Some(operands.join(" ")),
+                _ => {}
+            }
+        }
+
+        return Ok(EpdPosition {
+            fen: position_fields.join(" "),
+            operations,
+            best_moves,
+            avoid_moves,
+            id,
+            comment,
+        });
+    }
+
+    // Sets `game` up at this position, with legal moves filled in. The variant is left as it is.
+    pub fn set_up(&self, game: &mut ChessGame) -> Result<(), EpdError> {
+        game.import_fen(&self.fen)?;
+        game.set_legal_moves(None);
+        return Ok(());
+    }
+
+    // Whether playing `this_move` in this position (`game` has to be set up there) passes `bm` and `am`.
+    pub fn is_solved_by(&self, game: &ChessGame, this_move: &Move) -> Result<bool, EpdError> {
+        let (best_moves, avoid_moves) = self.resolve_moves(game)?;
+        return Ok(passes(&best_moves, &avoid_moves, this_move));
+    }
+
+    /**
+     * Searches the position with `limits` and checks the move against `bm` and `am`.
+     * The transposition table is cleared first, so earlier positions in the suite don't help.
+     */
+    pub fn solve(
+        &self,
+        game: &mut ChessGame,
+        limits: &SearchLimits,
+    ) -> Result<EpdOutcome, EpdError> {
+        self.set_up(game)?;
+
+        // Make sure the answers make sense before spending time on a search.
+        let (best_moves, avoid_moves) = self.resolve_moves(game)?;
+
+        game.transposition_table.clear();
+        let start_time = std::time::SystemTime::now();
+        let start_nodes = game.debug_minimax_calls;
+        let (_, found) = game.search_with_limits(limits);
+        let time_ms = std::time::SystemTime::now()
+            .duration_since(start_time)
+            .expect("Time went back?")
+            .as_millis();
+        let nodes = game.debug_minimax_calls - start_nodes;
+
+        let solved = found.is_some_and(|m| passes(&best_moves, &avoid_moves, &m));
+        game.set_legal_moves(None);
+        let found = found.map(|m| game.move_to_san(&m));
+        return Ok(EpdOutcome {
+            found,
+            solved,
+            time_ms,
+            nodes,
+        });
+    }
+
+    // `bm` and `am` as legal moves. Moves are read as SAN, or as UCI for the suites that write them that way.
+    fn resolve_moves(&self, game: &ChessGame) -> Result<(Vec<Move>, Vec<Move>), EpdError> {
+        if self.best_moves.is_empty() && self.avoid_moves.is_empty() {
+            return Err(EpdError::NothingToCheck);
+        }
+
+        let resolve = |opcode: &str, moves: &[String]| -> Result<Vec<Move>, EpdError> {
+            let mut resolved = vec![];
+            for written in moves {
+                let this_move = match game.parse_san(written) {
+                    Ok(m) => m,
+                    Err(error) => {
+                        game.uci_to_legal_move(written)
+                            .map_err(|_| EpdError::InvalidMove {
+                                opcode: opcode.to_string(),
+                                error,
+                            })?
+                    }
+                };
+                resolved.push(this_move);
+            }
+            return Ok(resolved);
+        };
+
+        return Ok((
+            resolve("bm", &self.best_moves)?,
+            resolve("am", &self.avoid_moves)?,
+        ));
+    }
+}
+
+// One of the best moves, if there are any, and none of the ones to avoid.
+fn passes(best_moves: &[Move], avoid_moves: &[Move], this_move: &Move) -> bool {
+    return (best_moves.is_empty() || best_moves.contains(this_move))
+        && !avoid_moves.contains(this_move);
+}
+
+// The first whitespace separated word of `s`, and what comes after it.
+fn next_word(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    let end = s.find(char::is_whitespace).unwrap_or(s.len());
+    return (&s[..end], &s[end..]);
+}
+
+// `opcode operand operand;` repeated. Quoted operands can hold spaces and `;`. The last `;` is optional.
+fn parse_operations(s: &str) -> Result<Vec<(String, Vec<String>)>, EpdError> {
+    let mut operations = vec![];
+    let mut words: Vec<String> = vec![];
+    let mut word = String::new();
+    let mut chars = s.chars();
+
+    let end_operation = |words: &mut Vec<String>, operations: &mut Vec<(String, Vec<String>)>| {
+        if !words.is_empty() {
+            let opcode = words.remove(0);
+            operations.push((opcode, std::mem::take(words)));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut quoted = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => quoted.push(c),
+                        None => return Err(EpdError::UnterminatedString),
+                    }
+                }
+                words.push(quoted);
+            }
+            ';' => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                end_operation(&mut words, &mut operations);
+            }
+            c if c.is_whitespace() => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    end_operation(&mut words, &mut operations);
+
+    return Ok(operations);
+}
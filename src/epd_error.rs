@@ -0,0 +1,55 @@
+use crate::fen_error::FenError;
+use crate::illegal_move_error::IllegalMoveError;
+use std::error::Error;
+use std::fmt;
+
+// Why an EPD line couldn't be read or checked.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EpdError {
+    // An EPD needs the first four FEN fields. How many we found.
+    MissingFields(usize),
+
+    // A `"` with no closing `"`.
+    UnterminatedString,
+
+    InvalidFen(FenError),
+
+    // The opcode (`bm` or `am`) and why its move doesn't fit the position.
+    InvalidMove {
+        opcode: String,
+        error: IllegalMoveError,
+    },
+
+    // No `bm` or `am`, so there's no way to tell if a search got it right.
+    NothingToCheck,
+}
+
+impl fmt::Display for EpdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            EpdError::MissingFields(count) => {
+                write!(f, "EPD needs 4 position fields. We parsed: {count}.")
+            }
+            EpdError::UnterminatedString => write!(f, "Found `\"` without a closing `\"`."),
+            EpdError::InvalidFen(e) => write!(f, "Unable to import the position. {e}"),
+            EpdError::InvalidMove { opcode, error } => write!(f, "Bad `{opcode}` move. {error}"),
+            EpdError::NothingToCheck => write!(f, "No `bm` or `am` to check the search against."),
+        };
+    }
+}
+
+impl Error for EpdError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            EpdError::InvalidFen(e) => Some(e),
+            EpdError::InvalidMove { error, .. } => Some(error),
+            _ => None,
+        };
+    }
+}
+
+impl From<FenError> for EpdError {
+    fn from(e: FenError) -> Self {
+        return EpdError::InvalidFen(e);
+    }
+}
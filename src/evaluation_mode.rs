@@ -0,0 +1,8 @@
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EvaluationMode {
+    // Hand-written evaluation, see `evaluate_board`.
+    Classical,
+
+    // Efficiently updatable neural network. Needs a network to be loaded first.
+    Nnue,
+}
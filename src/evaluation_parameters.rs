@@ -0,0 +1,237 @@
+use crate::color::Color;
+use crate::constants;
+use crate::piece_type::PieceType;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/**
+ * Every weight used by `evaluate_board`. Defaults are the hand-picked values from `constants.rs`.
+ * Happy square tables are written from white's perspective (a8 first), and mirrored for black.
+ */
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EvaluationParameters {
+    pub pawn_value: i64,
+    pub knight_value: i64,
+    pub bishop_value: i64,
+    pub rook_value: i64,
+    pub queen_value: i64,
+
+    pub king_happy_squares: Vec<i64>,
+    pub queen_happy_squares: Vec<i64>,
+    pub rook_happy_squares: Vec<i64>,
+    pub bishop_happy_squares: Vec<i64>,
+    pub knight_happy_squares: Vec<i64>,
+    pub pawn_happy_squares: Vec<i64>,
+}
+
+// Piece values we tune. Kings are priceless, so they are not part of the parameter set.
+const TUNABLE_PIECE_VALUES: [PieceType; 5] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+];
+
+// Order of the happy square tables when the parameters are flattened.
+const HAPPY_SQUARE_TABLES: [PieceType; 6] = [
+    PieceType::King,
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+impl Default for EvaluationParameters {
+    fn default() -> Self {
+        return EvaluationParameters {
+            pawn_value: 100,
+            knight_value: 300,
+            bishop_value: 320,
+            rook_value: 500,
+            queen_value: 900,
+            king_happy_squares: constants::KING_HAPPY_SQUARES_NON_ENDGAME.to_vec(),
+            queen_happy_squares: constants::QUEEN_HAPPY_SQUARES.to_vec(),
+            rook_happy_squares: constants::ROOK_HAPPY_SQUARES.to_vec(),
+            bishop_happy_squares: constants::BISHOP_HAPPY_SQUARES.to_vec(),
+            knight_happy_squares: constants::KNIGHT_HAPPY_SQUARES.to_vec(),
+            pawn_happy_squares: constants::PAWN_HAPPY_SQUARES.to_vec(),
+        };
+    }
+}
+
+impl EvaluationParameters {
+    pub fn load_from_file(path: &str) -> Result<Self, String> {
+        let contents = match fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) => {
+                return Err(format!(
+                    "Unable to read parameter file `{path}`. Error: {e}"
+                ))
+            }
+        };
+
+        return Self::from_json(&contents);
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), String> {
+        let contents = self.to_json()?;
+
+        return match fs::write(path, contents) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!(
+                "Unable to write parameter file `{path}`. Error: {e}"
+            )),
+        };
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        let parameters: EvaluationParameters = match serde_json::from_str(s) {
+            Ok(p) => p,
+            Err(e) => return Err(format!("Unable to parse evaluation parameters. Error: {e}")),
+        };
+
+        parameters.validate()?;
+
+        return Ok(parameters);
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        return match serde_json::to_string_pretty(self) {
+            Ok(s) => Ok(s),
+            Err(e) => Err(format!(
+                "Unable to serialize evaluation parameters. Error: {e}"
+            )),
+        };
+    }
+
+    // Serde can't check the table sizes for us, so we do it here.
+    pub fn validate(&self) -> Result<(), String> {
+        for piece_type in HAPPY_SQUARE_TABLES.iter() {
+            let table = self.happy_square_table(*piece_type);
+            if table.len() != 64 {
+                return Err(format!(
+                    "Happy square table for `{}` must have exactly 64 entries. Got: {}.",
+                    piece_type.to_char_side_agnostic(),
+                    table.len()
+                ));
+            }
+        }
+
+        return Ok(());
+    }
+
+    pub fn piece_value(&self, piece_type: PieceType) -> i64 {
+        return match piece_type {
+            PieceType::King => 0,
+            PieceType::Queen => self.queen_value,
+            PieceType::Rook => self.rook_value,
+            PieceType::Bishop => self.bishop_value,
+            PieceType::Knight => self.knight_value,
+            PieceType::Pawn => self.pawn_value,
+        };
+    }
+
+    pub fn happy_square_value(&self, piece_type: PieceType, square: usize, side: Color) -> i64 {
+        return self.happy_square_table(piece_type)[Self::perspective_square(square, side)];
+    }
+
+    fn happy_square_table(&self, piece_type: PieceType) -> &Vec<i64> {
+        return match piece_type {
+            PieceType::King => &self.king_happy_squares,
+            PieceType::Queen => &self.queen_happy_squares,
+            PieceType::Rook => &self.rook_happy_squares,
+            PieceType::Bishop => &self.bishop_happy_squares,
+            PieceType::Knight => &self.knight_happy_squares,
+            PieceType::Pawn => &self.pawn_happy_squares,
+        };
+    }
+
+    fn happy_square_table_mut(&mut self, piece_type: PieceType) -> &mut Vec<i64> {
+        return match piece_type {
+            PieceType::King => &mut self.king_happy_squares,
+            PieceType::Queen => &mut self.queen_happy_squares,
+            PieceType::Rook => &mut self.rook_happy_squares,
+            PieceType::Bishop => &mut self.bishop_happy_squares,
+            PieceType::Knight => &mut self.knight_happy_squares,
+            PieceType::Pawn => &mut self.pawn_happy_squares,
+        };
+    }
+
+    // Tables are from white's perspective. Flip the rank for black.
+    fn perspective_square(square: usize, side: Color) -> usize {
+        return match side {
+            Color::White => square,
+            Color::Black => (7 - (square / 8)) * 8 + (square % 8),
+        };
+    }
+
+    /*
+        Flattened view of the parameters, used by the tuner.
+        0..5    -> piece values (pawn, knight, bishop, rook, queen)
+        5..389  -> happy square tables (king, queen, rook, bishop, knight, pawn), 64 each
+    */
+    pub fn parameter_count() -> usize {
+        return TUNABLE_PIECE_VALUES.len() + HAPPY_SQUARE_TABLES.len() * 64;
+    }
+
+    pub fn piece_value_parameter_index(piece_type: PieceType) -> Option<usize> {
+        return TUNABLE_PIECE_VALUES.iter().position(|p| *p == piece_type);
+    }
+
+    pub fn happy_square_parameter_index(
+        piece_type: PieceType,
+        square: usize,
+        side: Color,
+    ) -> usize {
+        let table_index = HAPPY_SQUARE_TABLES
+            .iter()
+            .position(|p| *p == piece_type)
+            .expect("Every piece type has a happy square table.");
+
+        return TUNABLE_PIECE_VALUES.len()
+            + table_index * 64
+            + Self::perspective_square(square, side);
+    }
+
+    pub fn to_vec(&self) -> Vec<i64> {
+        let mut values: Vec<i64> = vec![];
+
+        for piece_type in TUNABLE_PIECE_VALUES.iter() {
+            values.push(self.piece_value(*piece_type));
+        }
+        for piece_type in HAPPY_SQUARE_TABLES.iter() {
+            values.extend_from_slice(self.happy_square_table(*piece_type));
+        }
+
+        return values;
+    }
+
+    pub fn from_vec(values: &[i64]) -> Result<Self, String> {
+        if values.len() != Self::parameter_count() {
+            return Err(format!(
+                "Expected {} parameters. Got: {}.",
+                Self::parameter_count(),
+                values.len()
+            ));
+        }
+
+        let mut parameters = EvaluationParameters {
+            pawn_value: values[0],
+            knight_value: values[1],
+            bishop_value: values[2],
+            rook_value: values[3],
+            queen_value: values[4],
+            ..Default::default()
+        };
+
+        let mut offset = TUNABLE_PIECE_VALUES.len();
+        for piece_type in HAPPY_SQUARE_TABLES.iter() {
+            *parameters.happy_square_table_mut(*piece_type) = values[offset..offset + 64].to_vec();
+            offset += 64;
+        }
+
+        return Ok(parameters);
+    }
+}
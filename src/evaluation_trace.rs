@@ -0,0 +1,137 @@
+use crate::color::Color;
+use crate::constants;
+use serde::Serialize;
+
+// A score split by game phase. Terms that do not care about the phase use the same value for both.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PhaseScore {
+    pub middlegame: i64,
+    pub endgame: i64,
+}
+
+impl PhaseScore {
+    pub fn new(middlegame: i64, endgame: i64) -> Self {
+        return PhaseScore {
+            middlegame,
+            endgame,
+        };
+    }
+
+    // Same value in the middlegame and the endgame.
+    pub fn flat(value: i64) -> Self {
+        return PhaseScore {
+            middlegame: value,
+            endgame: value,
+        };
+    }
+
+    // Blend the two values together, based on how much material is left on the board.
+    pub fn tapered(&self, phase: i64) -> i64 {
+        return (self.middlegame * phase + self.endgame * (constants::GAME_PHASE_MAX - phase))
+            / constants::GAME_PHASE_MAX;
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EvaluationTerm {
+    pub name: String,
+    pub white: PhaseScore,
+    pub black: PhaseScore,
+}
+
+impl EvaluationTerm {
+    // White minus black, blended for the current phase.
+    pub fn net(&self, phase: i64) -> i64 {
+        return self.white.tapered(phase) - self.black.tapered(phase);
+    }
+}
+
+/**
+ * Breakdown of `evaluate_board`, one entry per evaluation term.
+ * Scores are from white's perspective, like the rest of the engine.
+ */
+#[derive(Clone, Debug, Serialize)]
+pub struct EvaluationTrace {
+    pub phase: i64,
+    pub max_phase: i64,
+    pub terms: Vec<EvaluationTerm>,
+    pub total: i64,
+}
+
+impl EvaluationTrace {
+    pub fn new(phase: i64) -> Self {
+        return EvaluationTrace {
+            phase,
+            max_phase: constants::GAME_PHASE_MAX,
+            terms: vec![],
+            total: 0,
+        };
+    }
+
+    // Adds to the term with this name, creating it the first time we see it.
+    pub fn add(&mut self, name: &str, side: Color, score: PhaseScore) {
+        let index = match self.terms.iter().position(|t| t.name == name) {
+            Some(i) => i,
+            None => {
+                self.terms.push(EvaluationTerm {
+                    name: name.to_string(),
+                    white: PhaseScore::default(),
+                    black: PhaseScore::default(),
+                });
+                self.terms.len() - 1
+            }
+        };
+
+        let term_side = match side {
+            Color::White => &mut self.terms[index].white,
+            Color::Black => &mut self.terms[index].black,
+        };
+        term_side.middlegame += score.middlegame;
+        term_side.endgame += score.endgame;
+    }
+
+    pub fn get_term(&self, name: &str) -> Option<&EvaluationTerm> {
+        return self.terms.iter().find(|t| t.name == name);
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        return match serde_json::to_string_pretty(self) {
+            Ok(s) => Ok(s),
+            Err(e) => Err(format!("Unable to serialize evaluation trace. Error: {e}")),
+        };
+    }
+
+    pub fn to_table_string(&self) -> String {
+        let mut table = String::new();
+        let divider = format!(
+            "{:-<24}+{:->11}+{:->11}+{:->11}+{:->11}+{:->11}\n",
+            "", "", "", "", "", ""
+        );
+
+        table += &format!(
+            "{:<24}|{:>10} |{:>10} |{:>10} |{:>10} |{:>10}\n",
+            "Term", "White MG", "White EG", "Black MG", "Black EG", "Net"
+        );
+        table += &divider;
+
+        for term in self.terms.iter() {
+            table += &format!(
+                "{:<24}|{:>10} |{:>10} |{:>10} |{:>10} |{:>10}\n",
+                term.name,
+                term.white.middlegame,
+                term.white.endgame,
+                term.black.middlegame,
+                term.black.endgame,
+                term.net(self.phase)
+            );
+        }
+
+        table += &divider;
+        table += &format!(
+            "Phase: {}/{} (middlegame weight)\nTotal: {}\n",
+            self.phase, self.max_phase, self.total
+        );
+
+        return table;
+    }
+}
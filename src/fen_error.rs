@@ -0,0 +1,72 @@
+use crate::color::Color;
+use crate::square::Square;
+use std::error::Error;
+use std::fmt;
+
+// Why `ChessGame::import_fen` rejected a FEN.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FenError {
+    MissingBoard,
+    InvalidEmptyCount(char),
+    PromotedMarkerWithoutPiece,
+    InvalidPiece(char),
+
+    // How many columns or rows we actually found.
+    WrongColumnCount(usize),
+    WrongRowCount(usize),
+
+    // Positions move generation can't handle.
+    PawnOnBackRank(Square),
+    WrongKingCount { side: Color, count: usize },
+
+    // Crazyhouse pockets.
+    KingInPocket,
+    PocketOverflow(char),
+
+    MissingSideToMove,
+    InvalidSideToMove(String),
+
+    // Three-check counters.
+    InvalidCheckCounts(String),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            FenError::MissingBoard => write!(f, "No board position found in FEN."),
+            FenError::InvalidEmptyCount(c) => write!(f, "Invalid number of empty spaces: {c}."),
+            FenError::PromotedMarkerWithoutPiece => {
+                write!(f, "Found `~` without a piece before it.")
+            }
+            FenError::InvalidPiece(c) => write!(f, "Unexpected piece letter {c}."),
+            FenError::WrongColumnCount(count) => {
+                write!(f, "Board must have exactly 8 columns. We parsed: {count}.")
+            }
+            FenError::WrongRowCount(count) => {
+                write!(f, "Board must have exactly 8 rows. We parsed: {count}.")
+            }
+            FenError::PawnOnBackRank(square) => {
+                write!(
+                    f,
+                    "Pawns can not be on the first or last rank. Found one on {square}."
+                )
+            }
+            FenError::WrongKingCount { side, count } => write!(
+                f,
+                "Wrong number of {side:?} kings for this variant. We parsed: {count}."
+            ),
+            FenError::KingInPocket => write!(f, "Kings can not be in a pocket."),
+            FenError::PocketOverflow(c) => write!(f, "Too many `{c}` in the pocket."),
+            FenError::MissingSideToMove => {
+                write!(f, "Unsure whose turn it is. Cannot proceed.")
+            }
+            FenError::InvalidSideToMove(s) => write!(
+                f,
+                "Unexpected character for whose turn it is: {s}. Should be 'w' or 'b'."
+            ),
+            FenError::InvalidCheckCounts(s) => write!(f, "Invalid check counts: `{s}`."),
+        };
+    }
+}
+
+impl Error for FenError {}
@@ -0,0 +1,33 @@
+use crate::move_parse_error::MoveParseError;
+
+// A column of the board, `a` is 0 and `h` is 7.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct File(u8);
+
+impl File {
+    pub const A: File = File(0);
+    pub const H: File = File(7);
+
+    pub fn new(index: usize) -> Option<Self> {
+        if index >= 8 {
+            return None;
+        }
+        return Some(File(index as u8));
+    }
+
+    pub fn from_char(c: char) -> Result<Self, MoveParseError> {
+        let lower = c.to_ascii_lowercase();
+        if !('a'..='h').contains(&lower) {
+            return Err(MoveParseError::InvalidFile(c));
+        }
+        return Ok(File(lower as u8 - b'a'));
+    }
+
+    pub fn index(&self) -> usize {
+        return self.0 as usize;
+    }
+
+    pub fn to_char(&self) -> char {
+        return (b'a' + self.0) as char;
+    }
+}
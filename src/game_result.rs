@@ -0,0 +1,24 @@
+use crate::color::Color;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GameResult {
+    // Still moves to play.
+    Ongoing,
+
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+impl GameResult {
+    pub fn win_for(side: Color) -> Self {
+        return match side {
+            Color::White => GameResult::WhiteWins,
+            Color::Black => GameResult::BlackWins,
+        };
+    }
+
+    pub fn is_over(&self) -> bool {
+        return !matches!(self, GameResult::Ongoing);
+    }
+}
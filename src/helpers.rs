@@ -1,3 +1,6 @@
+use crate::move_parse_error::MoveParseError;
+use crate::square::Square;
+
 // Should these be macros? Or something similar?
 pub fn get_bit(bitboard: u64, square: usize) -> u64 {
     return bitboard & (1 << square);
@@ -56,11 +59,7 @@ pub fn debug_depth_to_tabs(mut depth: u32) -> String {
 }
 
 pub fn square_to_coord(square: usize) -> String {
-    let rank = 8 - (square / 8);
-    let file_number = square % 8;
-    let file_char = ('a' as u8 + file_number as u8) as char;
-
-    return format!("{}{}", file_char, rank);
+    return Square::new(square).to_string();
 }
 
 pub fn print_bitboard(bitboard: u64) {
@@ -88,35 +87,6 @@ pub fn print_bitboard(bitboard: u64) {
     println!("Bitboard Value: {bitboard}");
 }
 
-// Should this be an enum?
-pub fn str_coord_to_square(s: &str) -> Result<usize, String> {
-    if s.len() != 2 {
-        return Err(format!(
-            "Invalid input detected. Expected 2 chars. Got: `{}`.",
-            s.len()
-        ));
-    }
-
-    // We know the length is 2, so we can safely unwrap here.
-    let file_str = s.chars().nth(0).unwrap().to_ascii_lowercase();
-    let rank_str = s.chars().nth(1).unwrap();
-
-    // Attempt conversion for file letter.
-    let (file, overflowed) = (file_str as usize).overflowing_sub('a' as usize);
-    if file >= 8 || overflowed {
-        return Err(format!("Invalid file letter: `{}`, should be between `a` and `h`.", file_str));
-    }
-
-    let rank: usize = match rank_str.to_digit(10) {
-        Some(n) => n.try_into().unwrap(),
-        None => return Err(format!("Unable to convert `{}` to a digit.", rank_str)),
-    };
-
-    if rank < 1 || rank > 8 {
-        return Err("Invalid rank, it should be a value between 1 and 8 inclusive.".to_string());
-    }
-
-    let rank_normalized = 8 - rank;
-
-    return Ok(rank_normalized * 8 + file);
+pub fn str_coord_to_square(s: &str) -> Result<usize, MoveParseError> {
+    return s.parse::<Square>().map(|square| square.index());
 }
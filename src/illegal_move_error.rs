@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::fmt;
+
+// Why a move couldn't be played in the current position.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IllegalMoveError {
+    // The move, as written in `move_to_uci`.
+    NotLegal(String),
+
+    // Written so loosely that more than one legal move fits, like `Nd7` with knights on b8 and f6.
+    Ambiguous(String),
+
+    // Checkmate, stalemate or a variant rule ended the game.
+    NoLegalMoves,
+}
+
+impl fmt::Display for IllegalMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            IllegalMoveError::NotLegal(uci) => write!(f, "`{uci}` is not a legal move."),
+            IllegalMoveError::Ambiguous(san) => {
+                write!(f, "`{san}` could be more than one legal move.")
+            }
+            IllegalMoveError::NoLegalMoves => {
+                write!(f, "There are no legal moves in this position.")
+            }
+        };
+    }
+}
+
+impl Error for IllegalMoveError {}
@@ -0,0 +1,268 @@
+use crate::color::Color;
+use std::sync::OnceLock;
+
+/*
+    King and pawn versus king, solved by retrograde analysis the first time it is needed.
+    Positions are stored with white holding the pawn, and the pawn on files a-d (anything else is mirrored).
+    Squares in this file count from a1 = 0, unlike the rest of the engine.
+
+    Index layout:
+        white to move (1 bit) -> white king (64) -> black king (64) -> pawn (24, rank 2-7 by file a-d)
+*/
+const PAWN_FILES: usize = 4;
+const PAWN_RANKS: usize = 6;
+const POSITION_COUNT: usize = 2 * 64 * 64 * PAWN_FILES * PAWN_RANKS;
+
+static KPK_BITBASE: OnceLock<KpkBitbase> = OnceLock::new();
+
+#[derive(Copy, Clone, PartialEq)]
+enum KpkResult {
+    Invalid,
+    Unknown,
+    Draw,
+    Win,
+}
+
+// One bit per position, set if the side with the pawn wins.
+pub struct KpkBitbase {
+    wins: Vec<u64>,
+}
+
+impl KpkBitbase {
+    // Shared copy, built on first use.
+    pub fn get() -> &'static KpkBitbase {
+        return KPK_BITBASE.get_or_init(KpkBitbase::generate);
+    }
+
+    /**
+     * Mark the positions we can decide straight away (illegal, promotions, stalemates, captured pawns),
+     * then keep passing over the rest until nothing changes. Whatever is still unknown at the end is a draw.
+     */
+    pub fn generate() -> Self {
+        let mut results: Vec<KpkResult> = (0..POSITION_COUNT).map(classify_initial).collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in 0..POSITION_COUNT {
+                if results[index] != KpkResult::Unknown {
+                    continue;
+                }
+
+                let result = classify_from_children(index, &results);
+                if result != KpkResult::Unknown {
+                    results[index] = result;
+                    changed = true;
+                }
+            }
+        }
+
+        let mut wins: Vec<u64> = vec![0; POSITION_COUNT.div_ceil(64)];
+        for (index, result) in results.iter().enumerate() {
+            if *result == KpkResult::Win {
+                wins[index / 64] |= 1 << (index % 64);
+            }
+        }
+
+        return KpkBitbase { wins };
+    }
+
+    /**
+     * Whether the side with the pawn wins. Squares are regular engine squares (a8 = 0).
+     * Kings and pawn must not overlap, and the pawn must not be on the first or last rank.
+     */
+    pub fn probe(
+        &self,
+        strong_side: Color,
+        strong_king: usize,
+        strong_pawn: usize,
+        weak_king: usize,
+        strong_to_move: bool,
+    ) -> bool {
+        // Engine squares start at a8. Flip to a1 = 0, which also puts black's pawn going up the board.
+        let orient = |square: usize| -> usize {
+            return match strong_side {
+                Color::White => square ^ 56,
+                Color::Black => square,
+            };
+        };
+        let mut white_king = orient(strong_king);
+        let mut pawn = orient(strong_pawn);
+        let mut black_king = orient(weak_king);
+
+        if pawn % 8 >= PAWN_FILES {
+            white_king ^= 7;
+            pawn ^= 7;
+            black_king ^= 7;
+        }
+
+        let index = position_index(strong_to_move, white_king, black_king, pawn);
+        return self.wins[index / 64] & (1 << (index % 64)) != 0;
+    }
+
+    pub fn win_count(&self) -> usize {
+        return self.wins.iter().map(|w| w.count_ones() as usize).sum();
+    }
+}
+
+fn position_index(white_to_move: bool, white_king: usize, black_king: usize, pawn: usize) -> usize {
+    let pawn_index = (pawn / 8 - 1) * PAWN_FILES + pawn % 8;
+    return ((white_to_move as usize * 64 + white_king) * 64 + black_king)
+        * PAWN_FILES
+        * PAWN_RANKS
+        + pawn_index;
+}
+
+// (white to move, white king, black king, pawn)
+fn decode_index(index: usize) -> (bool, usize, usize, usize) {
+    let pawn_index = index % (PAWN_FILES * PAWN_RANKS);
+    let rest = index / (PAWN_FILES * PAWN_RANKS);
+    let pawn = (pawn_index / PAWN_FILES + 1) * 8 + pawn_index % PAWN_FILES;
+
+    return (rest / (64 * 64) == 1, (rest / 64) % 64, rest % 64, pawn);
+}
+
+fn distance(a: usize, b: usize) -> usize {
+    let file_distance = (a % 8).abs_diff(b % 8);
+    let rank_distance = (a / 8).abs_diff(b / 8);
+    return usize::max(file_distance, rank_distance);
+}
+
+fn king_moves(square: usize) -> Vec<usize> {
+    let mut moves: Vec<usize> = vec![];
+    let file = (square % 8) as i64;
+    let rank = (square / 8) as i64;
+
+    for rank_step in -1..=1 {
+        for file_step in -1..=1 {
+            if rank_step == 0 && file_step == 0 {
+                continue;
+            }
+
+            let new_file = file + file_step;
+            let new_rank = rank + rank_step;
+            if (0..8).contains(&new_file) && (0..8).contains(&new_rank) {
+                moves.push((new_rank * 8 + new_file) as usize);
+            }
+        }
+    }
+
+    return moves;
+}
+
+// White pawns only, since the bitbase always has white holding the pawn.
+fn pawn_attacks(pawn: usize, square: usize) -> bool {
+    return square / 8 == pawn / 8 + 1 && (square % 8).abs_diff(pawn % 8) == 1;
+}
+
+fn classify_initial(index: usize) -> KpkResult {
+    let (white_to_move, white_king, black_king, pawn) = decode_index(index);
+
+    if white_king == black_king
+        || white_king == pawn
+        || black_king == pawn
+        || distance(white_king, black_king) <= 1
+    {
+        return KpkResult::Invalid;
+    }
+
+    // Black can not be in check on white's move.
+    if white_to_move && pawn_attacks(pawn, black_king) {
+        return KpkResult::Invalid;
+    }
+
+    // Promote, and the queen can not be taken.
+    if white_to_move && pawn / 8 == 6 {
+        let promotion_square = pawn + 8;
+        if promotion_square != white_king
+            && promotion_square != black_king
+            && (distance(black_king, promotion_square) > 1
+                || distance(white_king, promotion_square) == 1)
+        {
+            return KpkResult::Win;
+        }
+    }
+
+    return KpkResult::Unknown;
+}
+
+fn classify_from_children(index: usize, results: &[KpkResult]) -> KpkResult {
+    let (white_to_move, white_king, black_king, pawn) = decode_index(index);
+
+    if white_to_move {
+        // White wins if any move wins, draws if every move draws.
+        let mut all_draws = true;
+
+        for to in king_moves(white_king) {
+            if to == pawn || distance(to, black_king) <= 1 {
+                continue;
+            }
+            match results[position_index(false, to, black_king, pawn)] {
+                KpkResult::Win => return KpkResult::Win,
+                KpkResult::Unknown => all_draws = false,
+                _ => (),
+            }
+        }
+
+        let one_step = pawn + 8;
+        if one_step != white_king && one_step != black_king {
+            // Promotions that lose the queen were not marked as wins up front, so they count as draws.
+            if one_step / 8 < 7 {
+                match results[position_index(false, white_king, black_king, one_step)] {
+                    KpkResult::Win => return KpkResult::Win,
+                    KpkResult::Unknown => all_draws = false,
+                    _ => (),
+                }
+            }
+
+            let two_steps = pawn + 16;
+            if pawn / 8 == 1 && two_steps != white_king && two_steps != black_king {
+                match results[position_index(false, white_king, black_king, two_steps)] {
+                    KpkResult::Win => return KpkResult::Win,
+                    KpkResult::Unknown => all_draws = false,
+                    _ => (),
+                }
+            }
+        }
+
+        // No legal moves at all is stalemate, also a draw.
+        return match all_draws {
+            true => KpkResult::Draw,
+            false => KpkResult::Unknown,
+        };
+    }
+
+    // Black draws if any move draws, loses if every move loses.
+    let mut all_wins = true;
+    let mut has_moves = false;
+
+    for to in king_moves(black_king) {
+        if distance(to, white_king) <= 1 || pawn_attacks(pawn, to) {
+            continue;
+        }
+        has_moves = true;
+
+        // Taking the pawn. It is not defended, we checked the white king above.
+        if to == pawn {
+            return KpkResult::Draw;
+        }
+
+        match results[position_index(true, white_king, to, pawn)] {
+            KpkResult::Draw => return KpkResult::Draw,
+            KpkResult::Unknown => all_wins = false,
+            _ => (),
+        }
+    }
+
+    if !has_moves {
+        return match pawn_attacks(pawn, black_king) {
+            true => KpkResult::Win,
+            false => KpkResult::Draw,
+        };
+    }
+
+    return match all_wins {
+        true => KpkResult::Win,
+        false => KpkResult::Unknown,
+    };
+}
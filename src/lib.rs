@@ -1,11 +1,47 @@
+pub mod bench;
+pub mod bitboard;
+pub mod bot_config;
+pub mod bot_config_error;
 pub mod castle_sides;
 pub mod chess_game;
 pub mod color;
 pub mod constants;
+pub mod datagen;
+pub mod endgame;
+pub mod engine_match;
+pub mod epd;
+pub mod epd_error;
+pub mod evaluation_mode;
+pub mod evaluation_parameters;
+pub mod evaluation_trace;
+pub mod fen_error;
+pub mod file;
+pub mod game_result;
 pub mod helpers;
+pub mod illegal_move_error;
+pub mod kpk_bitbase;
 pub mod lichess;
+pub mod lichess_error;
 pub mod lichess_structs;
+pub mod log_level;
+pub mod nnue;
+pub mod pgn;
+pub mod pgn_error;
+pub mod pgn_read_error;
+pub mod pgn_reader;
 pub mod r#move;
+pub mod move_generation_mode;
+pub mod move_parse_error;
+pub mod piece;
 pub mod piece_type;
+pub mod rank;
 pub mod runtime_calculated_constants;
-pub mod transposition_table_entry;
\ No newline at end of file
+pub mod search_limits;
+pub mod sprt;
+pub mod square;
+pub mod texel;
+pub mod tournament;
+pub mod transposition_table_entry;
+pub mod uci_engine;
+pub mod uci_error;
+pub mod variant;
@@ -1,27 +1,30 @@
-use crate::r#move::Move;
-use crate::{chess_game, constants, lichess_structs, runtime_calculated_constants};
+use crate::bot_config::BotConfig;
+use crate::evaluation_parameters::EvaluationParameters;
+use crate::illegal_move_error::IllegalMoveError;
+use crate::lichess_error::LichessError;
+use crate::log_level::{log, LogLevel};
+use crate::variant::Variant;
+use crate::{chess_game, constants, lichess_structs};
 use core::str;
 use std::collections::HashMap;
 use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
-pub async fn main() -> Result<(), String> {
+pub async fn main(config: BotConfig) -> Result<(), LichessError> {
     // Try to get the bearer auth token.
     let lichess_auth_token = match env::var("LICHESS_BOT_API_TOKEN") {
         Ok(s) => s,
-        Err(e) => {
-            return Err(format!(
-                "Error reading ENV var: `LICHESS_BOT_API_TOKEN`. Make sure it is set! Detail: {e}"
-            ));
-        }
+        Err(e) => return Err(LichessError::MissingToken(e.to_string())),
     };
 
-    println!("Calling run function for Lichess Bot...");
-    run(&lichess_auth_token).await;
+    log(LogLevel::Info, "Calling run function for Lichess Bot...");
+    run(&lichess_auth_token, Arc::new(config)).await;
 
     return Ok(());
 }
 
-async fn play_game(token: &str, game_id: &str, fen: &str) {
+async fn play_game(token: &str, game_id: &str, config: &BotConfig) {
     let lichess_url = format!("https://lichess.org/api/bot/game/stream/{game_id}");
     let client: reqwest::Client = reqwest::Client::new();
     let response_result: Result<reqwest::Response, reqwest::Error> =
@@ -31,20 +34,31 @@ async fn play_game(token: &str, game_id: &str, fen: &str) {
     let mut response = match response_result {
         Ok(r) => r,
         Err(e) => {
-            print!("Error: {}", e);
+            log(LogLevel::Error, &format!("Error: {}", e));
             return;
         }
     };
 
     // This function will run forever, when calling a streamed API.
     let mut lichess_game: lichess_structs::GameFull = lichess_structs::GameFull::default();
-    let runtime_constant = runtime_calculated_constants::Constants::new();
-    let mut game = chess_game::ChessGame::new(&runtime_constant);
+    let mut game = chess_game::ChessGame::new();
+    game.set_transposition_table_mb(config.engine.hash_mb);
+    if let Some(path) = &config.engine.evaluation_parameters_path {
+        // Checked at startup, but the file may have changed since. Play on with the defaults if so.
+        match EvaluationParameters::load_from_file(path) {
+            Ok(parameters) => game.evaluation_parameters = parameters,
+            Err(e) => log(
+                LogLevel::Error,
+                &format!("{e} Using the default evaluation."),
+            ),
+        }
+    }
     let mut is_bot_white: bool = true;
+    let mut moves_played: usize = 0;
     while let Some(chunk) = response.chunk().await.unwrap() {
         // We just received the '\n' from the API to keep the connection alive. Ignore processing.
         if chunk.len() == 1 {
-            println!("Play Game Thread: Waiting for new data...");
+            log(LogLevel::Debug, "Play Game Thread: Waiting for new data...");
             continue;
         }
 
@@ -52,14 +66,20 @@ async fn play_game(token: &str, game_id: &str, fen: &str) {
         let full_str = match str::from_utf8(&chunk) {
             Ok(s) => s,
             Err(e) => {
-                println!("Unable to convert byte array to string (utf8). Error: {e}");
+                log(
+                    LogLevel::Warn,
+                    &format!("Unable to convert byte array to string (utf8). Error: {e}"),
+                );
                 continue;
             }
         };
 
         // Look through the bytes we got, se expect a few possible items here.
         if full_str.contains("\"type\":\"opponentGone\"") {
-            println!("Opponent left. Code not set up to handle this.");
+            log(
+                LogLevel::Info,
+                "Opponent left. Code not set up to handle this.",
+            );
             continue;
         } else if full_str.contains("\"type\":\"chatLine\"") {
             let lichess_chat_line_parse_attempt: Result<
@@ -69,47 +89,96 @@ async fn play_game(token: &str, game_id: &str, fen: &str) {
             let chat_event: lichess_structs::ChatLineEvent = match lichess_chat_line_parse_attempt {
                 Ok(g) => g,
                 Err(e) => {
-                    println!("Unable to parse lichess chat line. Error: {e}");
+                    log(
+                        LogLevel::Warn,
+                        &format!("Unable to parse lichess chat line. Error: {e}"),
+                    );
                     continue;
                 }
             };
 
-            // If player types debug in the chat, print some info to the screen.
+            // If player types debug in the chat, print some info to the screen. Only at the debug log level.
             if chat_event.text == "debug" {
-                game.print_debug_game_state_str();
+                if LogLevel::Debug.is_enabled() {
+                    game.print_debug_game_state_str();
+                }
+
+                // Break the evaluation down, so we can see why the bot likes the position.
+                let trace = game.evaluate_trace();
+                log(
+                    LogLevel::Debug,
+                    &format!("Evaluation breakdown:\n{}", trace.to_table_string()),
+                );
+                match trace.to_json() {
+                    Ok(s) => log(
+                        LogLevel::Debug,
+                        &format!("Evaluation breakdown (JSON):\n{s}"),
+                    ),
+                    Err(e) => log(LogLevel::Warn, &e),
+                }
+
                 let _ = write_chat_message(
                     token,
                     &lichess_game.id,
-                    "Message recieved. Check the console.",
+                    &format!(
+                        "Message recieved. Evaluation: {} (phase {}/{}). Check the console for the breakdown.",
+                        trace.total, trace.phase, trace.max_phase
+                    ),
                 )
                 .await;
             }
             continue;
         } else if full_str.contains("\"type\":\"gameFull\"") {
-            println!("Handling game state full...");
+            log(LogLevel::Debug, "Handling game state full...");
             let lichess_game_parse_attempt: Result<lichess_structs::GameFull, serde_json::Error> =
                 serde_json::from_str(full_str);
             lichess_game = match lichess_game_parse_attempt {
                 Ok(g) => g,
                 Err(e) => {
-                    println!("Unable to parse lichess full game. Error: {}", e);
+                    log(
+                        LogLevel::Warn,
+                        &format!("Unable to parse lichess full game. Error: {}", e),
+                    );
                     continue;
                 }
             };
 
             // The first time we load the game, we need go get the game state aligned...
-            if lichess_game.initial_fen != "startpos" {
-                println!("Game did not start in the initial condition. Cannot continue.");
-                break;
-            }
+            game.variant = match Variant::from_lichess_key(&lichess_game.variant.key) {
+                Ok(v) => v,
+                Err(e) => {
+                    log(LogLevel::Error, &format!("{e} Cannot continue."));
+                    break;
+                }
+            };
 
-            is_bot_white = lichess_game.white.id == constants::LICHESS_BOT_USERNAME;
+            is_bot_white = config.is_bot(&lichess_game.white.id);
 
-            // Import the FEN, let the rest below handle the rest. Game state is now set.
-            _ = game.import_fen(fen);
+            // Start from the initial position, and replay whatever was played before we joined.
+            let initial_fen = match lichess_game.initial_fen.as_str() {
+                "startpos" => constants::INITIAL_GAME_STATE_FEN,
+                fen => fen,
+            };
+            if let Err(e) = game.import_fen(initial_fen) {
+                log(
+                    LogLevel::Error,
+                    &format!("Unable to import the initial position. Error: {e}"),
+                );
+                break;
+            }
             game.set_legal_moves(None);
+
+            moves_played = 0;
+            if let Err(e) = play_new_moves(
+                &mut game,
+                &lichess_game.state.moves_to_vec(),
+                &mut moves_played,
+            ) {
+                log(LogLevel::Error, &e.to_string());
+                break;
+            }
         } else if full_str.contains("\"type\":\"gameState\"") {
-            println!("Handling game state...");
+            log(LogLevel::Debug, "Handling game state...");
 
             let lichess_game_state_parse_attempt: Result<
                 lichess_structs::GameState,
@@ -118,97 +187,111 @@ async fn play_game(token: &str, game_id: &str, fen: &str) {
             let lichess_game_state = match lichess_game_state_parse_attempt {
                 Ok(g) => g,
                 Err(e) => {
-                    println!("Unable to parse lichess game state. Error: {}", e);
+                    log(
+                        LogLevel::Warn,
+                        &format!("Unable to parse lichess game state. Error: {}", e),
+                    );
                     continue;
                 }
             };
 
             // Someone made a move. Update the local copy of our board.
             let moves = lichess_game_state.moves_to_vec();
-            let last_move_str = match moves.last() {
-                Some(m) => m as &str,
-                None => {
-                    println!("Cannot get the most recent move, none exist!");
-                    continue;
-                }
-            };
-
             lichess_game.state = lichess_game_state;
 
             // TODO: Check for resignation?
 
-            // Handle errors later...
-            let last_move = Move::str_to_move(&last_move_str)
-                .expect("API returned a move we could not convert.");
-            let cloned_legal_moves = game.legal_moves.clone();
-            for m in cloned_legal_moves.iter() {
-                if *m == last_move {
-                    game.make_move(m, true);
-                    break;
-                }
+            if let Err(e) = play_new_moves(&mut game, &moves, &mut moves_played) {
+                log(LogLevel::Error, &e.to_string());
+                break;
             }
 
             // Print our evaluation after each move.
-            println!("Our evaluation of the position: {}", game.evaluate_board());
+            log(
+                LogLevel::Debug,
+                &format!("Our evaluation of the position: {}", game.evaluate_board()),
+            );
         } else {
-            println!("Unexpected event type. See what went wrong.\n{}", full_str);
+            log(
+                LogLevel::Warn,
+                &format!("Unexpected event type. See what went wrong.\n{}", full_str),
+            );
             continue;
         }
 
         // If we reach this point, see if it's our turn.
         if game.white_to_move != is_bot_white {
-            println!("It is the opponents turn. Waiting for our turn.");
+            log(
+                LogLevel::Debug,
+                "It is the opponents turn. Waiting for our turn.",
+            );
             continue;
         }
 
         if lichess_game.state.status != "started" {
-            println!("Game is over by: {}", lichess_game.state.status);
+            log(
+                LogLevel::Info,
+                &format!("Game is over by: {}", lichess_game.state.status),
+            );
             break;
         }
 
         // We know it is our turn. Run minimax to find a good move.
         let bot_move = game.get_bot_move();
-        println!("Bot thinks we should play: {}", bot_move.move_to_str());
+        let bot_move_str = game.move_to_uci(&bot_move);
+        log(
+            LogLevel::Info,
+            &format!("Bot thinks we should play: {}", bot_move_str),
+        );
 
         // Try to make the move.
-        let move_result = make_move(token, &lichess_game.id, &bot_move.move_to_str()).await;
+        let move_result = make_move(token, &lichess_game.id, &bot_move_str).await;
 
         // Handle errors in the console.
         let _ = match move_result {
             Ok(_) => (),
             Err(e) => {
-                println!("{e}");
+                log(LogLevel::Error, &e.to_string());
                 break;
             }
         };
     }
 }
 
-async fn make_move(token: &str, game_id: &str, r#move: &str) -> Result<(), String> {
+// Play the moves from the game stream that we haven't played on our board yet.
+fn play_new_moves(
+    game: &mut chess_game::ChessGame,
+    moves: &[String],
+    moves_played: &mut usize,
+) -> Result<(), IllegalMoveError> {
+    for m in moves.iter().skip(*moves_played) {
+        let legal_move = game.uci_to_legal_move(m)?;
+        game.make_move(&legal_move, true);
+        *moves_played += 1;
+    }
+
+    return Ok(());
+}
+
+async fn make_move(token: &str, game_id: &str, r#move: &str) -> Result<(), LichessError> {
     let lichess_url = format!("https://lichess.org/api/bot/game/{game_id}/move/{move}");
     let client: reqwest::Client = reqwest::Client::new();
     let response_result: Result<reqwest::Response, reqwest::Error> =
         client.post(lichess_url).bearer_auth(token).send().await;
 
-    let parsed_response = match response_result {
-        Ok(r) => r,
-        Err(e) => {
-            return Err(e.to_string());
-        }
-    };
+    let parsed_response = response_result?;
 
     if parsed_response.status() != 200 {
-        return Err(format!(
-            "Something went wrong trying to make a move: {}.\nAPI response: {:#?}.",
-            r#move,
-            parsed_response.text().await
-        ));
+        return Err(LichessError::BadResponse {
+            action: format!("make a move: {}", r#move),
+            response: format!("{:#?}", parsed_response.text().await),
+        });
     }
 
     return Ok(());
 }
 
-async fn run(token: &str) {
+async fn run(token: &str, config: Arc<BotConfig>) {
     let lichess_event_url = "https://lichess.org/api/stream/event";
     let client: reqwest::Client = reqwest::Client::new();
     let response_result: Result<reqwest::Response, reqwest::Error> = client
@@ -220,16 +303,19 @@ async fn run(token: &str) {
     let mut response = match response_result {
         Ok(r) => r,
         Err(e) => {
-            print!("Error: {}", e);
+            log(LogLevel::Error, &format!("Error: {}", e));
             return;
         }
     };
 
+    // Games we're playing right now, so we stop accepting challenges at `max_concurrent_games`.
+    let active_games = Arc::new(AtomicUsize::new(0));
+
     // The API will stream us data.
     while let Some(chunk) = response.chunk().await.unwrap() {
         // We just received the '\n' from the API to keep the connection alive. Ignore processing.
         if chunk.len() == 1 {
-            println!("Main Thread: Waiting for new data...");
+            log(LogLevel::Debug, "Main Thread: Waiting for new data...");
             continue;
         }
 
@@ -237,9 +323,12 @@ async fn run(token: &str) {
         let full_str = match str::from_utf8(&chunk) {
             Ok(s) => s,
             Err(e) => {
-                println!(
-                    "Unable to convert byte array to string (utf8). Error: {}",
-                    e
+                log(
+                    LogLevel::Warn,
+                    &format!(
+                        "Unable to convert byte array to string (utf8). Error: {}",
+                        e
+                    ),
                 );
                 continue;
             }
@@ -253,18 +342,34 @@ async fn run(token: &str) {
             let lichess_challenge = match lichess_challenge_raw {
                 Ok(c) => c.challenge,
                 Err(e) => {
-                    println!("Unable to parse lichess challenge. Error: {}", e);
+                    log(
+                        LogLevel::Warn,
+                        &format!("Unable to parse lichess challenge. Error: {}", e),
+                    );
                     continue;
                 }
             };
 
-            // For now, only accept challenges from me.
-            if !constants::LICHESS_CHALLENGER_WHITELIST
-                .contains(&lichess_challenge.challenger.name.as_str())
-            {
-                println!(
-                    "Challenger {} is not on the whitelist. Ignoring for now.",
-                    lichess_challenge.challenger.name
+            // Only accept challenges the config allows.
+            if let Err(reason) = config.check_challenge(&lichess_challenge) {
+                log(
+                    LogLevel::Info,
+                    &format!(
+                        "Ignoring challenge from {}. {reason}",
+                        lichess_challenge.challenger.name
+                    ),
+                );
+                continue;
+            }
+
+            let playing = active_games.load(Ordering::SeqCst);
+            if playing >= config.max_concurrent_games {
+                log(
+                    LogLevel::Info,
+                    &format!(
+                        "Already playing {playing} games. Ignoring challenge from {}.",
+                        lichess_challenge.challenger.name
+                    ),
                 );
                 continue;
             }
@@ -280,59 +385,88 @@ async fn run(token: &str) {
             let lichess_game_full = match lichess_challenge_start {
                 Ok(c) => c.game,
                 Err(e) => {
-                    println!("Unable to parse lichess challenge. Error: {}", e);
+                    log(
+                        LogLevel::Warn,
+                        &format!("Unable to parse lichess challenge. Error: {}", e),
+                    );
                     continue;
                 }
             };
 
-            // Make a copy of the token to pass to the thread.
+            // Make a copy of the token and config to pass to the thread. The game counts as active until it ends.
             let cloned_token = token.to_string();
+            let cloned_config = Arc::clone(&config);
+            let active_game = ActiveGame::start(&active_games);
             tokio::spawn(async move {
-                println!("Spawning thread to play game...");
-                play_game(&cloned_token, &lichess_game_full.id, &lichess_game_full.fen).await;
+                log(LogLevel::Debug, "Spawning thread to play game...");
+                play_game(&cloned_token, &lichess_game_full.id, &cloned_config).await;
+                drop(active_game);
             });
 
             continue;
         } else if full_str.contains("\"type\":\"gameFinish\"") {
-            println!("Game finish event. Not coded to handle this yet.");
+            log(
+                LogLevel::Debug,
+                "Game finish event. Not coded to handle this yet.",
+            );
             continue;
         } else if full_str.contains("\"type\":\"challengeCanceled\"") {
-            println!("Challenge cancelled event. Not coded to handle this yet.");
+            log(
+                LogLevel::Debug,
+                "Challenge cancelled event. Not coded to handle this yet.",
+            );
             continue;
         } else if full_str.contains("\"type\":\"challengeDeclined\"") {
-            println!("Challenge declined event. Not coded to handle this yet.");
+            log(
+                LogLevel::Debug,
+                "Challenge declined event. Not coded to handle this yet.",
+            );
             continue;
         } else {
-            println!("Unexpected event type. See what went wrong.\n{}", full_str);
+            log(
+                LogLevel::Warn,
+                &format!("Unexpected event type. See what went wrong.\n{}", full_str),
+            );
             continue;
         }
     }
 }
 
-async fn accept_challenge(token: &str, game_id: &str) -> Result<(), String> {
+// Counts a game as active until dropped. A game that panics drops it while unwinding, so its slot isn't lost.
+struct ActiveGame(Arc<AtomicUsize>);
+
+impl ActiveGame {
+    fn start(active_games: &Arc<AtomicUsize>) -> Self {
+        active_games.fetch_add(1, Ordering::SeqCst);
+        return ActiveGame(Arc::clone(active_games));
+    }
+}
+
+impl Drop for ActiveGame {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+async fn accept_challenge(token: &str, game_id: &str) -> Result<(), LichessError> {
     let lichess_url = format!("https://lichess.org/api/challenge/{game_id}/accept");
     let client: reqwest::Client = reqwest::Client::new();
     let response_result: Result<reqwest::Response, reqwest::Error> =
         client.post(lichess_url).bearer_auth(token).send().await;
 
-    let parsed_response = match response_result {
-        Ok(r) => r,
-        Err(e) => {
-            return Err(e.to_string());
-        }
-    };
+    let parsed_response = response_result?;
 
     if parsed_response.status() != 200 {
-        return Err(format!(
-            "Something went wrong trying to accept challenge.\nAPI response: {:#?}.",
-            parsed_response.text().await
-        ));
+        return Err(LichessError::BadResponse {
+            action: "accept challenge".to_string(),
+            response: format!("{:#?}", parsed_response.text().await),
+        });
     }
 
     return Ok(());
 }
 
-async fn write_chat_message(token: &str, game_id: &str, message: &str) -> Result<(), String> {
+async fn write_chat_message(token: &str, game_id: &str, message: &str) -> Result<(), LichessError> {
     let lichess_url = format!("https://lichess.org/api/bot/game/{game_id}/chat");
     let mut params = HashMap::new();
     params.insert("room", "player"); // player/spectator
@@ -346,18 +480,13 @@ async fn write_chat_message(token: &str, game_id: &str, message: &str) -> Result
         .send()
         .await;
 
-    let parsed_response = match response_result {
-        Ok(r) => r,
-        Err(e) => {
-            return Err(e.to_string());
-        }
-    };
+    let parsed_response = response_result?;
 
     if parsed_response.status() != 200 {
-        return Err(format!(
-            "Something went wrong trying to write chat message.\nAPI response: {:#?}.",
-            parsed_response.text().await
-        ));
+        return Err(LichessError::BadResponse {
+            action: "write chat message".to_string(),
+            response: format!("{:#?}", parsed_response.text().await),
+        });
     }
 
     return Ok(());
@@ -0,0 +1,73 @@
+use crate::fen_error::FenError;
+use crate::illegal_move_error::IllegalMoveError;
+use std::error::Error;
+use std::fmt;
+
+// Anything that can go wrong talking to Lichess, or playing the game it sends us.
+#[derive(Debug)]
+pub enum LichessError {
+    // `LICHESS_BOT_API_TOKEN` isn't set. Holds the detail from the environment.
+    MissingToken(String),
+
+    // The request never got a response.
+    Request(reqwest::Error),
+
+    // Lichess answered, but not with a 200. What we were trying to do and the response body.
+    BadResponse { action: String, response: String },
+
+    // Variant key we don't know how to play.
+    UnsupportedVariant(String),
+
+    InvalidFen(FenError),
+    IllegalMove(IllegalMoveError),
+}
+
+impl fmt::Display for LichessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            LichessError::MissingToken(detail) => write!(
+                f,
+                "Error reading ENV var: `LICHESS_BOT_API_TOKEN`. Make sure it is set! Detail: {detail}"
+            ),
+            LichessError::Request(e) => write!(f, "Request to Lichess failed: {e}"),
+            LichessError::BadResponse { action, response } => write!(
+                f,
+                "Something went wrong trying to {action}.\nAPI response: {response}."
+            ),
+            LichessError::UnsupportedVariant(key) => {
+                write!(f, "Unsupported variant: `{key}`.")
+            }
+            LichessError::InvalidFen(e) => write!(f, "Unable to import the position. {e}"),
+            LichessError::IllegalMove(e) => write!(f, "{e}"),
+        };
+    }
+}
+
+impl Error for LichessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            LichessError::Request(e) => Some(e),
+            LichessError::InvalidFen(e) => Some(e),
+            LichessError::IllegalMove(e) => Some(e),
+            _ => None,
+        };
+    }
+}
+
+impl From<reqwest::Error> for LichessError {
+    fn from(e: reqwest::Error) -> Self {
+        return LichessError::Request(e);
+    }
+}
+
+impl From<FenError> for LichessError {
+    fn from(e: FenError) -> Self {
+        return LichessError::InvalidFen(e);
+    }
+}
+
+impl From<IllegalMoveError> for LichessError {
+    fn from(e: IllegalMoveError) -> Self {
+        return LichessError::IllegalMove(e);
+    }
+}
@@ -83,6 +83,9 @@ pub struct GameFull {
     // Lichess API gives us CAMEL CASE. So we fix it.
     #[serde(rename = "initialFen")]
     pub initial_fen: String,
+
+    #[serde(default)]
+    pub variant: VariantInfo,
 }
 
 // Implement the Default trait for Piece
@@ -95,6 +98,23 @@ impl Default for GameFull {
             black: UserBasic::default(),
             state: GameState::default(),
             initial_fen: String::new(),
+            variant: VariantInfo::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VariantInfo {
+    pub key: String,
+    pub name: String,
+}
+
+// Lichess leaves the variant out of some events. Those are standard games.
+impl Default for VariantInfo {
+    fn default() -> Self {
+        VariantInfo {
+            key: "standard".to_string(),
+            name: "Standard".to_string(),
         }
     }
 }
@@ -117,6 +137,29 @@ pub struct InnerChallenge {
     // Lichess API gives us CAMEL CASE. So we fix it.
     #[serde(rename = "destUser")]
     pub dest_user: UserBasic,
+
+    #[serde(default)]
+    pub variant: VariantInfo,
+
+    #[serde(default)]
+    pub rated: bool,
+
+    #[serde(rename = "timeControl", default)]
+    pub time_control: TimeControlInfo,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TimeControlInfo {
+    // `clock`, `correspondence` or `unlimited`.
+    pub r#type: String,
+
+    // Clock games. Both in seconds.
+    pub limit: Option<u32>,
+    pub increment: Option<u32>,
+
+    // Correspondence games.
+    #[serde(rename = "daysPerTurn")]
+    pub days_per_turn: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// How much the Lichess bot prints. Each level includes everything above it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+// Every game runs on its own task, so the level is kept where they can all read it.
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Info as u8);
+
+impl LogLevel {
+    // Makes this the level for the whole process.
+    pub fn set_global(self) {
+        LOG_LEVEL.store(self as u8, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(self) -> bool {
+        return self as u8 <= LOG_LEVEL.load(Ordering::Relaxed);
+    }
+}
+
+// Prints `message` if the process log level lets `level` through.
+pub fn log(level: LogLevel, message: &str) {
+    if level.is_enabled() {
+        println!("{message}");
+    }
+}
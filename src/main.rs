@@ -1,13 +1,54 @@
+use chess_engine::bench::{nodes_per_second, run_bench, DEFAULT_BENCH_DEPTH};
+use chess_engine::bot_config::{BotConfig, DEFAULT_BOT_CONFIG_PATH};
 use chess_engine::lichess;
+use std::env;
+use std::process;
 
 #[tokio::main]
 async fn main() {
-    // Lichess bot.
-    let _ = lichess::main().await;
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // `bench [depth]`: fixed depth search of the built-in positions. The node total is the engine's signature.
+    if args.first().map(|s| s.as_str()) == Some("bench") {
+        let depth = match args.get(1).map(|s| s.parse::<u32>()) {
+            None => DEFAULT_BENCH_DEPTH,
+            Some(Ok(depth)) if depth > 0 => depth,
+            _ => {
+                eprintln!("Usage: chess_engine bench [depth]");
+                process::exit(1);
+            }
+        };
+        bench(depth);
+        return;
+    }
+
+    // Lichess bot. `--config <path>`, then `LICHESS_BOT_CONFIG`, then the default path.
+    let config_path = match args.as_slice() {
+        [] => env::var("LICHESS_BOT_CONFIG").unwrap_or(DEFAULT_BOT_CONFIG_PATH.to_string()),
+        [flag, path] if flag == "--config" => path.clone(),
+        _ => {
+            eprintln!("Usage: chess_engine [--config <path>]\n       chess_engine bench [depth]");
+            process::exit(1);
+        }
+    };
+    let config = match BotConfig::load_from_file(&config_path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
+    for warning in config.warnings() {
+        println!("Warning: {warning}");
+    }
+    config.log_level.set_global();
+
+    if let Err(e) = lichess::main(config).await {
+        println!("{e}");
+    }
 
     // Testing iterative deepening.
-    // let c = runtime_calculated_constants::Constants::new();
-    // let mut new_game = chess_game::ChessGame::new(&c);
+    // let mut new_game = chess_game::ChessGame::new();
     // let _ = new_game.import_fen("r2qk2r/5nPP/3Bpp2/1pPR3N/1pP1Q3/1P1b1P1p/P5PP/R3K2R w KQkq b6 0 1");
     // new_game.set_legal_moves(None);
 
@@ -21,3 +62,23 @@ async fn main() {
 
     // new_game.print_debug_game_state_str();
 }
+
+fn bench(depth: u32) {
+    let results = run_bench(depth);
+    for (i, result) in results.iter().enumerate() {
+        println!(
+            "Position {:>2}: {:>10} nodes {:>7} ms  {}",
+            i + 1,
+            result.nodes,
+            result.time_ms,
+            result.fen
+        );
+    }
+
+    let nodes: u64 = results.iter().map(|r| r.nodes).sum();
+    let time_ms: u128 = results.iter().map(|r| r.time_ms).sum();
+    println!("\nDepth {depth}");
+    println!("Total time (ms) : {time_ms}");
+    println!("Nodes searched  : {nodes}");
+    println!("Nodes/second    : {}", nodes_per_second(nodes, time_ms));
+}
@@ -1,20 +1,21 @@
 use crate::castle_sides::CastleSides;
-use crate::helpers::{square_to_coord, str_coord_to_square};
+use crate::move_parse_error::MoveParseError;
 use crate::piece_type::PieceType;
+use crate::square::Square;
 
 // Think about if this is the best way to do this...
 #[derive(Copy, Clone, Debug)]
 pub struct Move {
     // Basic data, required
-    pub from_square: usize,
+    pub from_square: Square,
     pub from_piece_type: Option<PieceType>,
 
-    pub to_square: usize,
+    pub to_square: Square,
     pub to_piece_type: Option<PieceType>, // If not 'None', then this is a capture.
 
     // En-Passant target tracking.
-    pub last_en_passant_target_coord: Option<usize>,
-    pub next_en_passant_target_coord: Option<usize>,
+    pub last_en_passant_target_coord: Option<Square>,
+    pub next_en_passant_target_coord: Option<Square>,
     pub is_en_passant_capture: bool,
 
     // Pawn promotion.
@@ -23,16 +24,22 @@ pub struct Move {
     // Castling
     pub castle_side: Option<CastleSides>,
 
+    // Crazyhouse drop. `from_piece_type` comes out of our pocket onto `to_square`, `from_square` is the same square.
+    pub is_drop: bool,
+
     // Populated later, used for move sorting.
     pub is_check: Option<bool>,
     pub removes_white_castling_rights_short: Option<bool>,
     pub removes_white_castling_rights_long: Option<bool>,
     pub removes_black_castling_rights_short: Option<bool>,
     pub removes_black_castling_rights_long: Option<bool>,
+
+    // Crazyhouse. Promoted pieces go back to the pocket as pawns, so unmaking needs to know.
+    pub captures_promoted_piece: Option<bool>,
 }
 
 impl Move {
-    pub fn new(from_square: usize, to_square: usize) -> Self {
+    pub fn new(from_square: Square, to_square: Square) -> Self {
         return Move {
             from_square,
             from_piece_type: None,
@@ -43,37 +50,60 @@ impl Move {
             is_en_passant_capture: false,
             pawn_promoting_to: None,
             castle_side: None,
+            is_drop: false,
             removes_white_castling_rights_short: None,
             removes_white_castling_rights_long: None,
             removes_black_castling_rights_short: None,
             removes_black_castling_rights_long: None,
+            captures_promoted_piece: None,
             is_check: None,
         };
     }
 
+    // Crazyhouse drop of `piece_type` onto `square`.
+    pub fn new_drop(piece_type: PieceType, square: Square) -> Self {
+        let mut m = Move::new(square, square);
+        m.from_piece_type = Some(piece_type);
+        m.is_drop = true;
+        return m;
+    }
+
     pub fn move_to_str(&self) -> String {
+        // Drops are written like `N@f3`.
+        if self.is_drop {
+            let piece_char = match self.from_piece_type {
+                Some(t) => t.to_char_side_agnostic().to_ascii_uppercase(),
+                None => '?',
+            };
+            return format!("{}@{}", piece_char, self.to_square);
+        }
+
         let extra_char: String = match self.pawn_promoting_to {
             Some(t) => t.to_char_side_agnostic().to_string(),
             None => String::from(""),
         };
-        return format!(
-            "{}{}{}",
-            square_to_coord(self.from_square),
-            square_to_coord(self.to_square),
-            extra_char
-        );
+        return format!("{}{}{}", self.from_square, self.to_square, extra_char);
     }
 
-    pub fn str_to_move(text: &str) -> Result<Move, String> {
+    pub fn str_to_move(text: &str) -> Result<Move, MoveParseError> {
+        // Checked first, the slicing below goes by bytes.
+        if !text.is_ascii() {
+            return Err(MoveParseError::NotAscii(text.to_string()));
+        }
+
+        if text.len() == 4 && text.chars().nth(1) == Some('@') {
+            let piece_char: char = text.chars().next().unwrap();
+            let piece_type = PieceType::char_to_piece_type(piece_char)?;
+            let square: Square = text[2..].parse()?;
+            return Ok(Move::new_drop(piece_type, square));
+        }
+
         if text.len() != 4 && text.len() != 5 {
-            return Err(format!(
-                "Invalid input detected. Expected 4 or 5 chars. Got: `{}`.",
-                text.len()
-            ));
+            return Err(MoveParseError::WrongLength(text.len()));
         }
 
-        let from_coord = str_coord_to_square(&text[..2]);
-        let to_coord = str_coord_to_square(&text[2..4]);
+        let from_coord = text[..2].parse::<Square>();
+        let to_coord = text[2..4].parse::<Square>();
 
         let from = match from_coord {
             Ok(c) => c,
@@ -87,7 +117,7 @@ impl Move {
         let mut pawn_promoting_to: Option<PieceType> = None;
         if text.len() == 5 {
             let promotion_char: char = text.chars().nth(4).unwrap();
-            let parsed_promotion_piece_type: Result<PieceType, String> =
+            let parsed_promotion_piece_type: Result<PieceType, MoveParseError> =
                 PieceType::char_to_piece_type(promotion_char);
             match parsed_promotion_piece_type {
                 Ok(t) => pawn_promoting_to = Some(t),
@@ -102,11 +132,13 @@ impl Move {
     }
 }
 
-// When comparing moves, we only care about the `from` and `to` and promotion. The other fields are for other parts of the program.
+// When comparing moves, we only care about the `from` and `to` and promotion (and the piece, for drops). The other fields are for other parts of the program.
 impl PartialEq for Move {
     fn eq(&self, other: &Self) -> bool {
         return self.from_square == other.from_square
             && self.to_square == other.to_square
-            && self.pawn_promoting_to == other.pawn_promoting_to;
+            && self.pawn_promoting_to == other.pawn_promoting_to
+            && self.is_drop == other.is_drop
+            && (!self.is_drop || self.from_piece_type == other.from_piece_type);
     }
 }
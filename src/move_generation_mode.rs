@@ -0,0 +1,52 @@
+use crate::r#move::Move;
+
+// Which legal moves to generate. Captures and quiet moves together make up all of them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MoveGenerationMode {
+    All,
+
+    // Captures (en passant included) and promotions. What a quiescence search wants.
+    CapturesAndPromotions,
+
+    // Everything else: quiet moves, castling, and drops.
+    Quiet,
+
+    // Every legal move if the side to move is in check, nothing if it isn't.
+    Evasions,
+}
+
+impl MoveGenerationMode {
+    pub fn includes_captures(&self) -> bool {
+        return !matches!(self, MoveGenerationMode::Quiet);
+    }
+
+    pub fn includes_quiet(&self) -> bool {
+        return !matches!(self, MoveGenerationMode::CapturesAndPromotions);
+    }
+
+    // All squares if we want captures, none if not. For and-ing into target bitboards.
+    pub fn capture_mask(&self) -> u64 {
+        return match self.includes_captures() {
+            true => u64::MAX,
+            false => 0,
+        };
+    }
+
+    // Same as `capture_mask`, for quiet moves.
+    pub fn quiet_mask(&self) -> u64 {
+        return match self.includes_quiet() {
+            true => u64::MAX,
+            false => 0,
+        };
+    }
+
+    // Whether this mode would generate `this_move`. Doesn't know about check, so evasions take anything.
+    pub fn includes_move(&self, this_move: &Move) -> bool {
+        let is_noisy = this_move.to_piece_type.is_some() || this_move.pawn_promoting_to.is_some();
+        return match self {
+            MoveGenerationMode::CapturesAndPromotions => is_noisy,
+            MoveGenerationMode::Quiet => !is_noisy,
+            _ => true,
+        };
+    }
+}
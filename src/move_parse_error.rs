@@ -0,0 +1,44 @@
+use std::error::Error;
+use std::fmt;
+
+// Why a square, piece letter or coordinate move (`e2e4`, `e7e8q`, `N@f3`) couldn't be read.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MoveParseError {
+    // Number of characters we got.
+    WrongLength(usize),
+    InvalidFile(char),
+    InvalidRank(char),
+    InvalidPiece(char),
+
+    // Moves are all ASCII. Holds the input.
+    NotAscii(String),
+}
+
+impl fmt::Display for MoveParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            MoveParseError::WrongLength(length) => {
+                write!(f, "Invalid input detected. Unexpected length: `{length}`.")
+            }
+            MoveParseError::InvalidFile(c) => {
+                write!(
+                    f,
+                    "Invalid file letter: `{c}`, should be between `a` and `h`."
+                )
+            }
+            MoveParseError::InvalidRank(c) => {
+                write!(
+                    f,
+                    "Invalid rank: `{c}`, should be between 1 and 8 inclusive."
+                )
+            }
+            MoveParseError::InvalidPiece(c) => write!(
+                f,
+                "Unexpected character. Cannot convert character `{c}` to piece type."
+            ),
+            MoveParseError::NotAscii(s) => write!(f, "Unexpected non-ASCII input: `{s}`."),
+        };
+    }
+}
+
+impl Error for MoveParseError {}
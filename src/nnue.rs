@@ -0,0 +1,455 @@
+use crate::color::Color;
+use crate::helpers::*;
+use crate::piece::Piece;
+use crate::piece_type::PieceType;
+use std::fs;
+
+/*
+    Network file format. Everything is little-endian.
+        4 bytes     -> magic, `CENN`
+        u32         -> version, currently 1
+        u32         -> architecture, 0 = HalfKP, 1 = HalfKA
+        u32         -> hidden size (number of accumulator neurons per perspective)
+        i16 * H     -> feature biases
+        i16 * F * H -> feature weights, one row of H weights per feature
+        i16 * 2H    -> output weights, side to move first, then the other side
+        i32         -> output bias
+*/
+const NNUE_MAGIC: &[u8; 4] = b"CENN";
+const NNUE_VERSION: u32 = 1;
+
+// Quantization. Accumulators are clipped to [0, QA], output weights are scaled by QB.
+pub const NNUE_QA: i64 = 255;
+pub const NNUE_QB: i64 = 64;
+
+// Converts the network output into centipawns.
+pub const NNUE_OUTPUT_SCALE: i64 = 400;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NnueArchitecture {
+    // King square + every non-king piece. 64 * 10 * 64 features.
+    HalfKP,
+
+    // King square + every piece, kings included. 64 * 12 * 64 features.
+    HalfKA,
+}
+
+impl NnueArchitecture {
+    pub fn feature_count(&self) -> usize {
+        return 64 * self.pieces_per_king_square() * 64;
+    }
+
+    fn pieces_per_king_square(&self) -> usize {
+        return match self {
+            Self::HalfKP => 10,
+            Self::HalfKA => 12,
+        };
+    }
+
+    fn to_u32(self) -> u32 {
+        return match self {
+            Self::HalfKP => 0,
+            Self::HalfKA => 1,
+        };
+    }
+
+    fn from_u32(n: u32) -> Result<Self, String> {
+        return match n {
+            0 => Ok(Self::HalfKP),
+            1 => Ok(Self::HalfKA),
+            _ => Err(format!("Unknown network architecture: {n}.")),
+        };
+    }
+}
+
+// One accumulator per perspective, indexed by `Color::idx`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NnueAccumulator {
+    pub perspectives: [Vec<i16>; 2],
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NnueNetwork {
+    pub architecture: NnueArchitecture,
+    pub hidden_size: usize,
+    pub feature_biases: Vec<i16>,
+    pub feature_weights: Vec<i16>,
+    pub output_weights: Vec<i16>,
+    pub output_bias: i32,
+}
+
+impl NnueNetwork {
+    // All zeros. Mostly useful for building networks by hand.
+    pub fn new(architecture: NnueArchitecture, hidden_size: usize) -> Self {
+        return NnueNetwork {
+            architecture,
+            hidden_size,
+            feature_biases: vec![0; hidden_size],
+            feature_weights: vec![0; architecture.feature_count() * hidden_size],
+            output_weights: vec![0; 2 * hidden_size],
+            output_bias: 0,
+        };
+    }
+
+    pub fn load_from_file(path: &str) -> Result<Self, String> {
+        return match fs::read(path) {
+            Ok(bytes) => Self::from_bytes(&bytes),
+            Err(e) => Err(format!("Unable to read network file `{path}`. Error: {e}")),
+        };
+    }
+
+    pub fn save_to_file(&self, path: &str) -> Result<(), String> {
+        return match fs::write(path, self.to_bytes()) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!("Unable to write network file `{path}`. Error: {e}")),
+        };
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader { bytes, position: 0 };
+
+        if reader.take(4)? != NNUE_MAGIC {
+            return Err("Not a network file, the magic bytes do not match.".to_string());
+        }
+
+        let version = reader.read_u32()?;
+        if version != NNUE_VERSION {
+            return Err(format!(
+                "Unsupported network version {version}. Expected {NNUE_VERSION}."
+            ));
+        }
+
+        let architecture = NnueArchitecture::from_u32(reader.read_u32()?)?;
+        let hidden_size = reader.read_u32()? as usize;
+        if hidden_size == 0 {
+            return Err("Network hidden size must be greater than zero.".to_string());
+        }
+
+        let feature_biases = reader.read_i16_vec(hidden_size)?;
+        let feature_weights = reader.read_i16_vec(architecture.feature_count() * hidden_size)?;
+        let output_weights = reader.read_i16_vec(2 * hidden_size)?;
+        let output_bias = reader.read_i32()?;
+
+        if reader.position != bytes.len() {
+            return Err(format!(
+                "Network file has {} unexpected trailing bytes.",
+                bytes.len() - reader.position
+            ));
+        }
+
+        return Ok(NnueNetwork {
+            architecture,
+            hidden_size,
+            feature_biases,
+            feature_weights,
+            output_weights,
+            output_bias,
+        });
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![];
+        bytes.extend_from_slice(NNUE_MAGIC);
+        bytes.extend_from_slice(&NNUE_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.architecture.to_u32().to_le_bytes());
+        bytes.extend_from_slice(&(self.hidden_size as u32).to_le_bytes());
+        for values in [
+            &self.feature_biases,
+            &self.feature_weights,
+            &self.output_weights,
+        ] {
+            for v in values.iter() {
+                bytes.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        bytes.extend_from_slice(&self.output_bias.to_le_bytes());
+
+        return bytes;
+    }
+
+    /**
+     * Which input neuron a piece turns on, as seen from one side of the board.
+     * Black sees the board flipped, so both sides share the same weights.
+     * Returns `None` for kings in HalfKP, they are only part of the king square.
+     */
+    pub fn feature_index(
+        &self,
+        perspective: Color,
+        king_square: usize,
+        piece_bitboard_index: usize,
+        square: usize,
+    ) -> Option<usize> {
+        let piece_type = PieceType::bitboard_index_to_piece_type(piece_bitboard_index);
+        let is_friendly = piece_bitboard_index / 6 == perspective.idx();
+        let pieces_per_side = self.architecture.pieces_per_king_square() / 2;
+
+        if self.architecture == NnueArchitecture::HalfKP && piece_type == PieceType::King {
+            return None;
+        }
+
+        let mut piece_index = piece_type.bitboard_index();
+        if !is_friendly {
+            piece_index += pieces_per_side;
+        }
+
+        return Some(
+            orient_square(king_square, perspective)
+                * self.architecture.pieces_per_king_square()
+                * 64
+                + piece_index * 64
+                + orient_square(square, perspective),
+        );
+    }
+
+    // Build one perspective from scratch.
+    pub fn refresh_perspective(&self, piece_bitboards: &[u64; 12], perspective: Color) -> Vec<i16> {
+        let mut values = self.feature_biases.clone();
+        let king_square = match get_lsb_index(
+            piece_bitboards[Piece::new(perspective, PieceType::King).bitboard_index()],
+        ) {
+            Some(s) => s,
+            None => return values,
+        };
+
+        for (piece_bitboard_index, bitboard) in piece_bitboards.iter().enumerate() {
+            let mut pieces = *bitboard;
+            while pieces != 0 {
+                let square = get_lsb_index(pieces).expect("Guard clause.");
+                if let Some(feature) =
+                    self.feature_index(perspective, king_square, piece_bitboard_index, square)
+                {
+                    add_assign(&mut values, self.feature_weight_row(feature));
+                }
+                pieces = pop_bit(pieces, square);
+            }
+        }
+
+        return values;
+    }
+
+    pub fn refresh_accumulator(&self, piece_bitboards: &[u64; 12]) -> NnueAccumulator {
+        return NnueAccumulator {
+            perspectives: [
+                self.refresh_perspective(piece_bitboards, Color::White),
+                self.refresh_perspective(piece_bitboards, Color::Black),
+            ],
+        };
+    }
+
+    /**
+     * Bring an accumulator up to date after a move, by looking at which pieces appeared and disappeared.
+     * If a king moved, every feature for that perspective changes; so that side is rebuilt instead.
+     */
+    pub fn update_accumulator(
+        &self,
+        accumulator: &mut NnueAccumulator,
+        previous_piece_bitboards: &[u64; 12],
+        piece_bitboards: &[u64; 12],
+    ) {
+        for perspective in [Color::White, Color::Black] {
+            let king_bitboard_index = Piece::new(perspective, PieceType::King).bitboard_index();
+            if previous_piece_bitboards[king_bitboard_index] != piece_bitboards[king_bitboard_index]
+            {
+                accumulator.perspectives[perspective.idx()] =
+                    self.refresh_perspective(piece_bitboards, perspective);
+                continue;
+            }
+
+            let king_square = match get_lsb_index(piece_bitboards[king_bitboard_index]) {
+                Some(s) => s,
+                None => continue,
+            };
+            let values = &mut accumulator.perspectives[perspective.idx()];
+
+            for piece_bitboard_index in 0..12 {
+                let before = previous_piece_bitboards[piece_bitboard_index];
+                let after = piece_bitboards[piece_bitboard_index];
+
+                let mut removed = before & !after;
+                while removed != 0 {
+                    let square = get_lsb_index(removed).expect("Guard clause.");
+                    if let Some(feature) =
+                        self.feature_index(perspective, king_square, piece_bitboard_index, square)
+                    {
+                        sub_assign(values, self.feature_weight_row(feature));
+                    }
+                    removed = pop_bit(removed, square);
+                }
+
+                let mut added = after & !before;
+                while added != 0 {
+                    let square = get_lsb_index(added).expect("Guard clause.");
+                    if let Some(feature) =
+                        self.feature_index(perspective, king_square, piece_bitboard_index, square)
+                    {
+                        add_assign(values, self.feature_weight_row(feature));
+                    }
+                    added = pop_bit(added, square);
+                }
+            }
+        }
+    }
+
+    // Centipawns, from the perspective of the side to move.
+    pub fn evaluate(&self, accumulator: &NnueAccumulator, side_to_move: Color) -> i64 {
+        let (us, them) = match side_to_move {
+            Color::White => (&accumulator.perspectives[0], &accumulator.perspectives[1]),
+            Color::Black => (&accumulator.perspectives[1], &accumulator.perspectives[0]),
+        };
+
+        let output = clipped_relu_dot(us, &self.output_weights[..self.hidden_size])
+            + clipped_relu_dot(them, &self.output_weights[self.hidden_size..])
+            + self.output_bias as i64;
+
+        return output * NNUE_OUTPUT_SCALE / (NNUE_QA * NNUE_QB);
+    }
+
+    fn feature_weight_row(&self, feature: usize) -> &[i16] {
+        let start = feature * self.hidden_size;
+        return &self.feature_weights[start..start + self.hidden_size];
+    }
+}
+
+fn orient_square(square: usize, perspective: Color) -> usize {
+    return match perspective {
+        Color::White => square,
+        Color::Black => square ^ 56,
+    };
+}
+
+/*
+    Inference kernels. The reference versions are plain scalar loops.
+    With the `nnue-autovec` feature, the same maths runs on fixed-size chunks,
+    which the compiler turns into SIMD instructions without us writing any intrinsics.
+*/
+const NNUE_CHUNK_SIZE: usize = 16;
+
+pub fn add_assign_reference(values: &mut [i16], weights: &[i16]) {
+    for (v, w) in values.iter_mut().zip(weights.iter()) {
+        *v = v.wrapping_add(*w);
+    }
+}
+
+pub fn sub_assign_reference(values: &mut [i16], weights: &[i16]) {
+    for (v, w) in values.iter_mut().zip(weights.iter()) {
+        *v = v.wrapping_sub(*w);
+    }
+}
+
+pub fn clipped_relu_dot_reference(values: &[i16], weights: &[i16]) -> i64 {
+    let mut sum: i64 = 0;
+    for (v, w) in values.iter().zip(weights.iter()) {
+        sum += (*v as i64).clamp(0, NNUE_QA) * (*w as i64);
+    }
+    return sum;
+}
+
+pub fn add_assign_chunked(values: &mut [i16], weights: &[i16]) {
+    let mut value_chunks = values.chunks_exact_mut(NNUE_CHUNK_SIZE);
+    let mut weight_chunks = weights.chunks_exact(NNUE_CHUNK_SIZE);
+    for (v, w) in (&mut value_chunks).zip(&mut weight_chunks) {
+        for i in 0..NNUE_CHUNK_SIZE {
+            v[i] = v[i].wrapping_add(w[i]);
+        }
+    }
+    add_assign_reference(value_chunks.into_remainder(), weight_chunks.remainder());
+}
+
+pub fn sub_assign_chunked(values: &mut [i16], weights: &[i16]) {
+    let mut value_chunks = values.chunks_exact_mut(NNUE_CHUNK_SIZE);
+    let mut weight_chunks = weights.chunks_exact(NNUE_CHUNK_SIZE);
+    for (v, w) in (&mut value_chunks).zip(&mut weight_chunks) {
+        for i in 0..NNUE_CHUNK_SIZE {
+            v[i] = v[i].wrapping_sub(w[i]);
+        }
+    }
+    sub_assign_reference(value_chunks.into_remainder(), weight_chunks.remainder());
+}
+
+pub fn clipped_relu_dot_chunked(values: &[i16], weights: &[i16]) -> i64 {
+    let value_chunks = values.chunks_exact(NNUE_CHUNK_SIZE);
+    let weight_chunks = weights.chunks_exact(NNUE_CHUNK_SIZE);
+    let remainder = clipped_relu_dot_reference(value_chunks.remainder(), weight_chunks.remainder());
+
+    // Products fit in an i32; summing a chunk in i32 lanes is what lets this vectorise.
+    let mut sum: i64 = 0;
+    for (v, w) in value_chunks.zip(weight_chunks) {
+        let mut lanes: [i32; NNUE_CHUNK_SIZE] = [0; NNUE_CHUNK_SIZE];
+        for i in 0..NNUE_CHUNK_SIZE {
+            lanes[i] = (v[i] as i32).clamp(0, NNUE_QA as i32) * (w[i] as i32);
+        }
+        sum += lanes.iter().map(|l| *l as i64).sum::<i64>();
+    }
+
+    return sum + remainder;
+}
+
+#[cfg(not(feature = "nnue-autovec"))]
+fn add_assign(values: &mut [i16], weights: &[i16]) {
+    add_assign_reference(values, weights);
+}
+
+#[cfg(feature = "nnue-autovec")]
+fn add_assign(values: &mut [i16], weights: &[i16]) {
+    add_assign_chunked(values, weights);
+}
+
+#[cfg(not(feature = "nnue-autovec"))]
+fn sub_assign(values: &mut [i16], weights: &[i16]) {
+    sub_assign_reference(values, weights);
+}
+
+#[cfg(feature = "nnue-autovec")]
+fn sub_assign(values: &mut [i16], weights: &[i16]) {
+    sub_assign_chunked(values, weights);
+}
+
+#[cfg(not(feature = "nnue-autovec"))]
+fn clipped_relu_dot(values: &[i16], weights: &[i16]) -> i64 {
+    return clipped_relu_dot_reference(values, weights);
+}
+
+#[cfg(feature = "nnue-autovec")]
+fn clipped_relu_dot(values: &[i16], weights: &[i16]) -> i64 {
+    return clipped_relu_dot_chunked(values, weights);
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.position + count > self.bytes.len() {
+            return Err(format!(
+                "Network file is truncated. Wanted {} bytes at offset {}, but the file is {} bytes.",
+                count,
+                self.position,
+                self.bytes.len()
+            ));
+        }
+
+        let slice = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        return Ok(slice);
+    }
+
+    fn read_u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        return Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    }
+
+    fn read_i32(&mut self) -> Result<i32, String> {
+        let b = self.take(4)?;
+        return Ok(i32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    }
+
+    fn read_i16_vec(&mut self, count: usize) -> Result<Vec<i16>, String> {
+        let b = self.take(count * 2)?;
+        return Ok(b
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+            .collect());
+    }
+}
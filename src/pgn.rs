@@ -0,0 +1,638 @@
+use crate::chess_game::ChessGame;
+use crate::constants::INITIAL_GAME_STATE_FEN;
+use crate::game_result::GameResult;
+use crate::pgn_error::PgnError;
+use crate::r#move::Move;
+use crate::variant::Variant;
+use std::fmt;
+use std::str::FromStr;
+
+// Tags every exported game carries, in the order they're written, with the value used when we don't know better.
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+// Movetext gets wrapped once a line would go past this.
+const MAX_LINE_LENGTH: usize = 80;
+
+// Suffix annotations and the NAG each one stands for.
+const SUFFIX_ANNOTATIONS: [(&str, u8); 6] = [
+    ("!!", 3),
+    ("??", 4),
+    ("!?", 5),
+    ("?!", 6),
+    ("!", 1),
+    ("?", 2),
+];
+
+// One move of the movetext, with everything written around it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PgnMove {
+    // As written, check and mate markers included. Annotations like `!?` are moved to `nags`.
+    pub san: String,
+
+    // Numeric annotation glyphs. `$1` (or `!`) is a good move, `$2` (or `?`) a mistake, and so on.
+    pub nags: Vec<u8>,
+
+    // Centipawns from white's perspective, read from and written to a `[%eval ...]` comment.
+    pub evaluation: Option<i64>,
+
+    // Comments after the move, without the braces or the `[%eval ...]` command.
+    pub comments: Vec<String>,
+
+    // Alternatives to this move. Each one starts from the position before it.
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+impl PgnMove {
+    pub fn new(san: &str) -> Self {
+        return PgnMove {
+            san: san.to_string(),
+            ..PgnMove::default()
+        };
+    }
+}
+
+/**
+ * A game as stored in PGN. Read one with `parse`, and write it back out with `to_string`.
+ * `replay` plays the main line on a `ChessGame`; to record a game as it's played, start from `starting_from`
+ * and `push_move` each move before making it.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct PgnGame {
+    // In the order they were read or set. Use `tag` and `set_tag` rather than searching this.
+    pub tags: Vec<(String, String)>,
+
+    // Comments before the first move.
+    pub comments: Vec<String>,
+
+    // The main line.
+    pub moves: Vec<PgnMove>,
+
+    pub result: GameResult,
+}
+
+impl Default for PgnGame {
+    fn default() -> Self {
+        return PgnGame::new();
+    }
+}
+
+impl PgnGame {
+    // A standard game with no moves, and the seven tag roster filled in with unknowns.
+    pub fn new() -> Self {
+        return PgnGame {
+            tags: SEVEN_TAG_ROSTER
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            comments: vec![],
+            moves: vec![],
+            result: GameResult::Ongoing,
+        };
+    }
+
+    // No moves yet, starting wherever `game` is now. Adds `Variant`, and `FEN` and `SetUp` if that isn't the usual start.
+    pub fn starting_from(game: &ChessGame) -> Self {
+        let mut pgn = PgnGame::new();
+        if game.variant != Variant::Standard {
+            pgn.set_tag("Variant", game.variant.pgn_name());
+        }
+
+        let mut usual_start = ChessGame::new();
+        usual_start.variant = game.variant;
+        let _ = usual_start.import_fen(INITIAL_GAME_STATE_FEN);
+
+        // `export_fen` leaves off the move counters, so the game is written as if it starts on move one.
+        let fen = game.export_fen();
+        if fen != usual_start.export_fen() {
+            pgn.set_tag("SetUp", "1");
+            pgn.set_tag("FEN", &format!("{fen} 0 1"));
+        }
+        return pgn;
+    }
+
+    pub fn parse(pgn: &str) -> Result<Self, PgnError> {
+        return pgn.parse();
+    }
+
+    // Just the tag pairs at the start of `pgn`, without looking at the movetext.
+    pub fn parse_tags(pgn: &str) -> Result<Vec<(String, String)>, PgnError> {
+        let mut parser = Parser {
+            chars: pgn.chars().collect(),
+            position: 0,
+        };
+        let mut tags_only = PgnGame {
+            tags: vec![],
+            comments: vec![],
+            moves: vec![],
+            result: GameResult::Ongoing,
+        };
+        parser.parse_tags(&mut tags_only)?;
+        return Ok(tags_only.tags);
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        return self
+            .tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str());
+    }
+
+    // Replaces the tag if it's there already, otherwise adds it at the end.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag_name, _)| tag_name == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    // Also keeps the `Result` tag in step.
+    pub fn set_result(&mut self, result: GameResult) {
+        self.result = result;
+        self.set_tag("Result", result_to_str(result));
+    }
+
+    /**
+     * Records `this_move` at the end of the main line, with the engine's evaluation if there is one.
+     * Call it before `make_move`, the SAN is worked out from the position the move is played in.
+     */
+    pub fn push_move(&mut self, game: &mut ChessGame, this_move: &Move, evaluation: Option<i64>) {
+        let mut pgn_move = PgnMove::new(&game.move_to_san(this_move));
+        pgn_move.evaluation = evaluation;
+        self.moves.push(pgn_move);
+    }
+
+    // The position before the first move, from the `Variant` and `FEN` tags. Legal moves are filled in.
+    pub fn starting_position(&self) -> Result<ChessGame, PgnError> {
+        let mut game = ChessGame::new();
+        if let Some(name) = self.tag("Variant") {
+            game.variant = Variant::from_pgn_name(name)?;
+        }
+        game.import_fen(self.tag("FEN").unwrap_or(INITIAL_GAME_STATE_FEN))?;
+        game.set_legal_moves(None);
+        return Ok(game);
+    }
+
+    // Plays the main line. Hands back the game after the last move, and the moves that got it there.
+    pub fn replay(&self) -> Result<(ChessGame, Vec<Move>), PgnError> {
+        let mut game = self.starting_position()?;
+        let mut moves = vec![];
+        for (ply, pgn_move) in self.moves.iter().enumerate() {
+            let this_move = game
+                .parse_san(&pgn_move.san)
+                .map_err(|error| PgnError::IllegalMove { ply, error })?;
+            game.make_move(&this_move, true);
+            moves.push(this_move);
+        }
+        return Ok((game, moves));
+    }
+
+    // Plies since white's first move, for the position in the `FEN` tag. Decides the move numbers we write.
+    fn starting_ply(&self) -> usize {
+        let fen = match self.tag("FEN") {
+            Some(fen) => fen,
+            None => return 0,
+        };
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        let black_to_move = fields.get(1) == Some(&"b");
+        let move_number = match fields.len() >= 6 {
+            true => fields[fields.len() - 1]
+                .parse::<usize>()
+                .unwrap_or(1)
+                .max(1),
+            false => 1,
+        };
+        return (move_number - 1) * 2 + black_to_move as usize;
+    }
+}
+
+impl FromStr for PgnGame {
+    type Err = PgnError;
+
+    // Reads the first game in `s`. Anything after it is ignored.
+    fn from_str(s: &str) -> Result<Self, PgnError> {
+        let mut parser = Parser {
+            chars: s.chars().collect(),
+            position: 0,
+        };
+        let mut pgn = PgnGame {
+            tags: vec![],
+            comments: vec![],
+            moves: vec![],
+            result: GameResult::Ongoing,
+        };
+
+        parser.parse_tags(&mut pgn)?;
+        let (moves, comments) = parser.parse_line(&mut pgn.result, 0)?;
+        pgn.moves = moves;
+        pgn.comments = comments;
+
+        if pgn.tags.is_empty() && pgn.moves.is_empty() && pgn.comments.is_empty() {
+            return Err(PgnError::Empty);
+        }
+
+        // Games cut off before the result token still have it in the tags.
+        if pgn.result == GameResult::Ongoing {
+            if let Some(result) = pgn.tag("Result").and_then(str_to_result) {
+                pgn.result = result;
+            }
+        }
+        return Ok(pgn);
+    }
+}
+
+// Export format: tags, a blank line, then the movetext wrapped to `MAX_LINE_LENGTH` and ending with the result.
+impl fmt::Display for PgnGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in self.tags.iter() {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{name} \"{value}\"]")?;
+        }
+        writeln!(f)?;
+
+        let mut tokens: Vec<String> = self.comments.iter().map(|c| comment_token(c)).collect();
+        write_line(&self.moves, self.starting_ply(), &mut tokens);
+        tokens.push(result_to_str(self.result).to_string());
+
+        let mut line_length = 0;
+        for token in tokens {
+            if line_length > 0 && line_length + 1 + token.len() > MAX_LINE_LENGTH {
+                writeln!(f)?;
+                line_length = 0;
+            }
+            if line_length > 0 {
+                write!(f, " ")?;
+                line_length += 1;
+            }
+            write!(f, "{token}")?;
+            line_length += token.len();
+        }
+        return writeln!(f);
+    }
+}
+
+// Movetext tokens for `moves`, the first of which is played `first_ply` plies after white's first move.
+fn write_line(moves: &[PgnMove], first_ply: usize, tokens: &mut Vec<String>) {
+    // Black moves only get a number at the start, or when something came between them and white's move.
+    let mut needs_number = true;
+    for (i, pgn_move) in moves.iter().enumerate() {
+        let ply = first_ply + i;
+        let move_number = ply / 2 + 1;
+        if ply.is_multiple_of(2) {
+            tokens.push(format!("{move_number}."));
+        } else if needs_number {
+            tokens.push(format!("{move_number}..."));
+        }
+        tokens.push(pgn_move.san.clone());
+        needs_number = false;
+
+        for nag in pgn_move.nags.iter() {
+            tokens.push(format!("${nag}"));
+        }
+        if let Some(evaluation) = pgn_move.evaluation {
+            tokens.push(format!("{{ [%eval {}] }}", evaluation_to_str(evaluation)));
+            needs_number = true;
+        }
+        for comment in pgn_move.comments.iter() {
+            tokens.push(comment_token(comment));
+            needs_number = true;
+        }
+
+        for variation in pgn_move.variations.iter() {
+            let mut variation_tokens = vec![];
+            write_line(variation, ply, &mut variation_tokens);
+            if let Some(first) = variation_tokens.first_mut() {
+                first.insert(0, '(');
+            }
+            if let Some(last) = variation_tokens.last_mut() {
+                last.push(')');
+            }
+            tokens.append(&mut variation_tokens);
+            needs_number = true;
+        }
+    }
+}
+
+fn comment_token(comment: &str) -> String {
+    // A `}` would end the comment early.
+    return format!("{{ {} }}", comment.replace('}', ")"));
+}
+
+fn result_to_str(result: GameResult) -> &'static str {
+    return match result {
+        GameResult::WhiteWins => "1-0",
+        GameResult::BlackWins => "0-1",
+        GameResult::Draw => "1/2-1/2",
+        GameResult::Ongoing => "*",
+    };
+}
+
+fn str_to_result(s: &str) -> Option<GameResult> {
+    return match s {
+        "1-0" => Some(GameResult::WhiteWins),
+        "0-1" => Some(GameResult::BlackWins),
+        "1/2-1/2" => Some(GameResult::Draw),
+        "*" => Some(GameResult::Ongoing),
+        _ => None,
+    };
+}
+
+// Pawns with two decimals, like Lichess. The search doesn't track how far away a mate is, so mates are written as `#1`.
+fn evaluation_to_str(evaluation: i64) -> String {
+    return match evaluation {
+        i64::MAX => "#1".to_string(),
+        i64::MIN => "#-1".to_string(),
+        _ => format!("{:.2}", evaluation as f64 / 100.0),
+    };
+}
+
+// Mates of any length become the search's mate scores.
+fn str_to_evaluation(s: &str) -> Option<i64> {
+    if let Some(mate_in) = s.strip_prefix('#') {
+        return match mate_in.parse::<i64>() {
+            Ok(moves) if moves < 0 => Some(i64::MIN),
+            Ok(_) => Some(i64::MAX),
+            Err(_) => None,
+        };
+    }
+    return s
+        .parse::<f64>()
+        .ok()
+        .map(|pawns| (pawns * 100.0).round() as i64);
+}
+
+// Pulls a `[%eval ...]` command out of a comment. Hands back what's left of the comment, and the evaluation.
+fn take_evaluation(comment: &str) -> (String, Option<i64>) {
+    let start = match comment.find("[%eval") {
+        Some(start) => start,
+        None => return (comment.trim().to_string(), None),
+    };
+    let end = match comment[start..].find(']') {
+        Some(length) => start + length,
+        None => return (comment.trim().to_string(), None),
+    };
+
+    let evaluation = comment[start + "[%eval".len()..end]
+        .split_whitespace()
+        .next()
+        .and_then(str_to_evaluation);
+    if evaluation.is_none() {
+        return (comment.trim().to_string(), None);
+    }
+
+    let rest = format!("{} {}", comment[..start].trim(), comment[end + 1..].trim());
+    return (rest.trim().to_string(), evaluation);
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        return self.chars.get(self.position).copied();
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    fn at_line_start(&self) -> bool {
+        return self.position == 0 || self.chars[self.position - 1] == '\n';
+    }
+
+    // Everything up to (not including) the end of the line.
+    fn read_rest_of_line(&mut self) -> String {
+        let start = self.position;
+        while self.peek().is_some_and(|c| c != '\n') {
+            self.position += 1;
+        }
+        return self.chars[start..self.position].iter().collect();
+    }
+
+    fn parse_tags(&mut self, pgn: &mut PgnGame) -> Result<(), PgnError> {
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('[') => {
+                    let (name, value) = self.parse_tag()?;
+                    pgn.tags.push((name, value));
+                }
+                // `%` at the start of a line escapes it.
+                Some('%') if self.at_line_start() => {
+                    self.read_rest_of_line();
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn parse_tag(&mut self) -> Result<(String, String), PgnError> {
+        let start = self.position;
+        let invalid_tag = |parser: &mut Parser| {
+            parser.read_rest_of_line();
+            let tag: String = parser.chars[start..parser.position].iter().collect();
+            return PgnError::InvalidTag(tag.trim().to_string());
+        };
+
+        // Skip the `[`.
+        self.position += 1;
+        self.skip_whitespace();
+
+        let mut name = String::new();
+        while let Some(c) = self.peek().filter(|c| c.is_alphanumeric() || *c == '_') {
+            name.push(c);
+            self.position += 1;
+        }
+        self.skip_whitespace();
+        if name.is_empty() || self.peek() != Some('"') {
+            return Err(invalid_tag(self));
+        }
+        self.position += 1;
+
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => break,
+                Some('\\') if matches!(self.chars.get(self.position + 1), Some('"' | '\\')) => {
+                    value.push(self.chars[self.position + 1]);
+                    self.position += 2;
+                }
+                Some('\n') | None => return Err(invalid_tag(self)),
+                Some(c) => {
+                    value.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+        self.position += 1;
+
+        self.skip_whitespace();
+        if self.peek() != Some(']') {
+            return Err(invalid_tag(self));
+        }
+        self.position += 1;
+        return Ok((name, value));
+    }
+
+    /**
+     * Reads moves until the line ends: a `)` for a variation, or the result, a new game's tags or the end of the
+     * input for the main line. Hands back the moves, and any comments before the first one.
+     */
+    fn parse_line(
+        &mut self,
+        result: &mut GameResult,
+        depth: usize,
+    ) -> Result<(Vec<PgnMove>, Vec<String>), PgnError> {
+        let mut moves: Vec<PgnMove> = vec![];
+        let mut leading_comments: Vec<String> = vec![];
+
+        loop {
+            self.skip_whitespace();
+            let c = match self.peek() {
+                Some(c) => c,
+                None if depth == 0 => break,
+                None => return Err(PgnError::UnbalancedVariation),
+            };
+
+            match c {
+                '{' | ';' => {
+                    let comment = match c {
+                        '{' => self.read_brace_comment()?,
+                        _ => {
+                            self.position += 1;
+                            self.read_rest_of_line()
+                        }
+                    };
+                    let (comment, evaluation) = take_evaluation(&comment);
+                    match moves.last_mut() {
+                        Some(last) => {
+                            if evaluation.is_some() {
+                                last.evaluation = evaluation;
+                            }
+                            if !comment.is_empty() {
+                                last.comments.push(comment);
+                            }
+                        }
+                        None if !comment.is_empty() => leading_comments.push(comment),
+                        None => {}
+                    }
+                }
+                '%' if self.at_line_start() => {
+                    self.read_rest_of_line();
+                }
+                '(' => {
+                    self.position += 1;
+                    let (mut variation, comments) = self.parse_line(result, depth + 1)?;
+                    if let Some(first) = variation.first_mut() {
+                        first.comments.splice(0..0, comments);
+                    }
+                    match moves.last_mut() {
+                        Some(last) => last.variations.push(variation),
+                        None => return Err(PgnError::VariationWithoutMove),
+                    }
+                }
+                ')' => {
+                    if depth == 0 {
+                        return Err(PgnError::UnbalancedVariation);
+                    }
+                    self.position += 1;
+                    break;
+                }
+                '$' => {
+                    self.position += 1;
+                    let token = self.read_token();
+                    if let (Some(last), Ok(nag)) = (moves.last_mut(), token.parse::<u8>()) {
+                        last.nags.push(nag);
+                    }
+                }
+                // The next game's tags.
+                '[' if depth == 0 => break,
+                _ => {
+                    let token = self.read_token();
+                    if let Some(game_result) = str_to_result(&token) {
+                        if depth == 0 {
+                            *result = game_result;
+                            break;
+                        }
+                        continue;
+                    }
+
+                    let (san, nags) = split_san_token(&token);
+                    if !san.is_empty() {
+                        let mut pgn_move = PgnMove::new(&san);
+                        pgn_move.nags = nags;
+                        moves.push(pgn_move);
+                    }
+                }
+            }
+        }
+
+        return Ok((moves, leading_comments));
+    }
+
+    // From the `{` to the matching `}`. Comments don't nest.
+    fn read_brace_comment(&mut self) -> Result<String, PgnError> {
+        self.position += 1;
+        let start = self.position;
+        while self.peek().is_some_and(|c| c != '}') {
+            self.position += 1;
+        }
+        if self.peek().is_none() {
+            return Err(PgnError::UnterminatedComment);
+        }
+        let comment = self.chars[start..self.position].iter().collect();
+        self.position += 1;
+        return Ok(comment);
+    }
+
+    // Up to the next whitespace, or anything that starts a new token.
+    fn read_token(&mut self) -> String {
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !"{}();$[".contains(c))
+        {
+            self.position += 1;
+        }
+
+        // Always make progress, even on a stray `}` or `]`.
+        if self.position == start {
+            self.position += 1;
+        }
+        return self.chars[start..self.position].iter().collect();
+    }
+}
+
+/**
+ * Splits a movetext token into the SAN and any suffix annotations. Drops a move number stuck to the front (`12.Nf3`,
+ * `12...Nf6`), so a bare move number comes back empty. `0-0` isn't a move number, those need a dot.
+ */
+fn split_san_token(token: &str) -> (String, Vec<u8>) {
+    let mut san = token;
+    let digits = san.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && san[digits..].starts_with('.') {
+        san = san[digits..].trim_start_matches('.');
+    }
+
+    let mut nags = vec![];
+    for (suffix, nag) in SUFFIX_ANNOTATIONS {
+        if let Some(stripped) = san.strip_suffix(suffix) {
+            san = stripped;
+            nags.push(nag);
+            break;
+        }
+    }
+    return (san.to_string(), nags);
+}
@@ -0,0 +1,66 @@
+use crate::fen_error::FenError;
+use crate::illegal_move_error::IllegalMoveError;
+use std::error::Error;
+use std::fmt;
+
+// Why a PGN couldn't be read, or its moves couldn't be replayed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PgnError {
+    // No tags and no moves.
+    Empty,
+
+    // A tag pair that isn't `[Name "value"]`. Holds what we read of it.
+    InvalidTag(String),
+
+    // `{` without a closing `}`.
+    UnterminatedComment,
+
+    // A `(` without a closing `)`, or the other way round.
+    UnbalancedVariation,
+
+    // A `(` before any move it could be an alternative to.
+    VariationWithoutMove,
+
+    // `Variant` tag we don't know how to play.
+    UnsupportedVariant(String),
+
+    InvalidFen(FenError),
+
+    // Index of the move in the main line, and why it doesn't fit the position.
+    IllegalMove { ply: usize, error: IllegalMoveError },
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            PgnError::Empty => write!(f, "No game found in PGN."),
+            PgnError::InvalidTag(tag) => write!(f, "Invalid tag pair: `{tag}`."),
+            PgnError::UnterminatedComment => write!(f, "Found `{{` without a closing `}}`."),
+            PgnError::UnbalancedVariation => {
+                write!(f, "Variation parentheses don't match up.")
+            }
+            PgnError::VariationWithoutMove => {
+                write!(f, "Found a variation before any move.")
+            }
+            PgnError::UnsupportedVariant(name) => write!(f, "Unsupported variant: `{name}`."),
+            PgnError::InvalidFen(e) => write!(f, "Unable to import the starting position. {e}"),
+            PgnError::IllegalMove { ply, error } => write!(f, "Ply {ply}: {error}"),
+        };
+    }
+}
+
+impl Error for PgnError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            PgnError::InvalidFen(e) => Some(e),
+            PgnError::IllegalMove { error, .. } => Some(error),
+            _ => None,
+        };
+    }
+}
+
+impl From<FenError> for PgnError {
+    fn from(e: FenError) -> Self {
+        return PgnError::InvalidFen(e);
+    }
+}
@@ -0,0 +1,40 @@
+use crate::pgn_error::PgnError;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+// Why `PgnReader` couldn't hand back a game.
+#[derive(Debug)]
+pub enum PgnReadError {
+    // Reading the input failed. The reader stops after this.
+    Io(io::Error),
+
+    // The game starting on `line` (counted from 1) couldn't be read. The reader carries on with the next one.
+    Malformed { line: usize, error: PgnError },
+}
+
+impl fmt::Display for PgnReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            PgnReadError::Io(e) => write!(f, "Unable to read PGN. Error: {e}"),
+            PgnReadError::Malformed { line, error } => {
+                write!(f, "Skipped the game starting on line {line}. {error}")
+            }
+        };
+    }
+}
+
+impl Error for PgnReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            PgnReadError::Io(e) => Some(e),
+            PgnReadError::Malformed { error, .. } => Some(error),
+        };
+    }
+}
+
+impl From<io::Error> for PgnReadError {
+    fn from(e: io::Error) -> Self {
+        return PgnReadError::Io(e);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::pgn::PgnGame;
+use crate::pgn_read_error::PgnReadError;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+/**
+ * Streams games out of a PGN database one at a time, so only the game being read is ever in memory.
+ * Games that don't parse come back as `PgnReadError::Malformed` with the line they start on, and reading carries on
+ * with the next one. `.filter_map(Result::ok)` skips them. Moves are only checked against the position when a game is
+ * `replay`ed. For scanning tags alone, `headers` doesn't keep the movetext at all. Bytes that aren't UTF-8, from the
+ * odd Latin-1 player name, come through as `\u{FFFD}` rather than ending the stream.
+ */
+pub struct PgnReader<R: BufRead> {
+    reader: R,
+
+    // Lines read so far.
+    line_number: usize,
+
+    // First tag line of the next game, read while looking for the end of the one before.
+    next_game_line: Option<String>,
+
+    // Set after an I/O error, so we don't keep hitting it.
+    finished: bool,
+}
+
+// Tag pairs only, see `PgnReader::headers`.
+pub struct PgnHeaders<R: BufRead> {
+    reader: PgnReader<R>,
+}
+
+impl PgnReader<BufReader<File>> {
+    pub fn open(path: &str) -> Result<Self, PgnReadError> {
+        let file = File::open(path)?;
+        return Ok(PgnReader::new(BufReader::new(file)));
+    }
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(reader: R) -> Self {
+        return PgnReader {
+            reader,
+            line_number: 0,
+            next_game_line: None,
+            finished: false,
+        };
+    }
+
+    // The rest of the games, but only their tag pairs. Much faster than parsing every game.
+    pub fn headers(self) -> PgnHeaders<R> {
+        return PgnHeaders { reader: self };
+    }
+
+    /**
+     * Text of the next game, and the line it starts on. A game ends where the next one's tags start, the first tag
+     * line after some movetext. Comments aren't tracked, so a game with an unclosed `{` doesn't swallow the rest of
+     * the file. With `keep_movetext` off, only the tag lines are kept.
+     */
+    fn read_game_text(
+        &mut self,
+        keep_movetext: bool,
+    ) -> Option<Result<(usize, String), PgnReadError>> {
+        if self.finished {
+            return None;
+        }
+
+        let mut text = String::new();
+        let mut first_line: Option<usize> = None;
+        let mut seen_movetext = false;
+
+        loop {
+            let line = match self.next_game_line.take() {
+                Some(line) => line,
+                None => {
+                    // `read_line` fails on bad UTF-8, so read bytes and fix them up ourselves.
+                    let mut bytes = vec![];
+                    match self.reader.read_until(b'\n', &mut bytes) {
+                        Ok(0) => break,
+                        Ok(_) => {}
+                        Err(e) => {
+                            self.finished = true;
+                            return Some(Err(PgnReadError::Io(e)));
+                        }
+                    }
+                    self.line_number += 1;
+                    String::from_utf8_lossy(&bytes).into_owned()
+                }
+            };
+
+            let trimmed = line.trim();
+            let is_tag_line = looks_like_tag(trimmed);
+            if is_tag_line && seen_movetext {
+                self.next_game_line = Some(line);
+                break;
+            }
+            if first_line.is_none() && trimmed.is_empty() {
+                continue;
+            }
+            first_line.get_or_insert(self.line_number);
+
+            if !is_tag_line {
+                // `%` at the start of a line escapes it.
+                if trimmed.starts_with('%') {
+                    continue;
+                }
+                if !trimmed.is_empty() {
+                    seen_movetext = true;
+                }
+                if !keep_movetext {
+                    continue;
+                }
+            }
+            text.push_str(&line);
+        }
+
+        return first_line.map(|line| Ok((line, text)));
+    }
+}
+
+// `[Name ...`. Commands like `[%eval 0.3]` that wrap onto a line of their own start with `%`, so they don't count.
+fn looks_like_tag(line: &str) -> bool {
+    return line
+        .strip_prefix('[')
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_alphabetic()));
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<PgnGame, PgnReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, text) = match self.read_game_text(true)? {
+            Ok(game_text) => game_text,
+            Err(e) => return Some(Err(e)),
+        };
+        return Some(
+            PgnGame::parse(&text).map_err(|error| PgnReadError::Malformed { line, error }),
+        );
+    }
+}
+
+impl<R: BufRead> Iterator for PgnHeaders<R> {
+    type Item = Result<Vec<(String, String)>, PgnReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, text) = match self.reader.read_game_text(false)? {
+            Ok(game_text) => game_text,
+            Err(e) => return Some(Err(e)),
+        };
+        return Some(
+            PgnGame::parse_tags(&text).map_err(|error| PgnReadError::Malformed { line, error }),
+        );
+    }
+}
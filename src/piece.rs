@@ -0,0 +1,41 @@
+use crate::color::Color;
+use crate::move_parse_error::MoveParseError;
+use crate::piece_type::PieceType;
+
+// A piece type of one color. Knows which of `ChessGame::piece_bitboards` it lives in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Piece {
+    pub color: Color,
+    pub piece_type: PieceType,
+}
+
+impl Piece {
+    pub fn new(color: Color, piece_type: PieceType) -> Self {
+        return Piece { color, piece_type };
+    }
+
+    pub fn bitboard_index(&self) -> usize {
+        return self.piece_type.bitboard_index() + self.color.piece_bitboard_offset();
+    }
+
+    pub fn from_bitboard_index(index: usize) -> Self {
+        let color = match index < 6 {
+            true => Color::White,
+            false => Color::Black,
+        };
+        return Piece::new(color, PieceType::bitboard_index_to_piece_type(index));
+    }
+
+    // FEN letter, upper case for White.
+    pub fn to_char(&self) -> char {
+        return self.piece_type.to_char(self.color);
+    }
+
+    pub fn from_char(c: char) -> Result<Self, MoveParseError> {
+        let color = match c.is_ascii_uppercase() {
+            true => Color::White,
+            false => Color::Black,
+        };
+        return Ok(Piece::new(color, PieceType::char_to_piece_type(c)?));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::color::Color;
-use crate::constants;
+use crate::move_parse_error::MoveParseError;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum PieceType {
     King,
     Queen,
@@ -12,39 +12,15 @@ pub enum PieceType {
 }
 
 impl PieceType {
-    pub fn piece_base_value(&self) -> i64 {
+    // How much this piece counts towards the game phase. Pawns and kings do not count.
+    pub fn piece_phase_value(&self) -> i64 {
         return match self {
             Self::King => 0,
-            Self::Queen => 900,
-            Self::Rook => 500,
-            Self::Bishop => 320,
-            Self::Knight => 300,
-            Self::Pawn => 100,
-        };
-    }
-
-    pub fn piece_happy_square_value(&self, square: usize, is_white_piece: bool) -> i64 {
-        return match self {
-            Self::King => constants::KING_HAPPY_SQUARES_NON_ENDGAME[square],
-            Self::Queen => constants::QUEEN_HAPPY_SQUARES[square],
-            Self::Rook => constants::ROOK_HAPPY_SQUARES[square],
-            Self::Bishop => constants::BISHOP_HAPPY_SQUARES[square],
-            Self::Knight => constants::KNIGHT_HAPPY_SQUARES[square],
-            Self::Pawn => {
-                if is_white_piece {
-                    return constants::PAWN_HAPPY_SQUARES[square];
-                } else {
-                    // Break the square into it's x and y components; and negate the y component.
-                    let rank: usize = 7 - (square / 8);
-                    let file_number: usize = square % 8;
-
-                    // Rebuild the coordinate.
-                    let new_square: usize = rank * 8 + file_number;
-
-                    // Get the value.
-                    return constants::PAWN_HAPPY_SQUARES[new_square];
-                }
-            }
+            Self::Queen => 4,
+            Self::Rook => 2,
+            Self::Bishop => 1,
+            Self::Knight => 1,
+            Self::Pawn => 0,
         };
     }
 
@@ -68,7 +44,7 @@ impl PieceType {
         };
     }
 
-    pub fn char_to_piece_type(c: char) -> Result<PieceType, String> {
+    pub fn char_to_piece_type(c: char) -> Result<PieceType, MoveParseError> {
         return match c.to_ascii_lowercase() {
             'k' => Ok(PieceType::King),
             'q' => Ok(PieceType::Queen),
@@ -76,10 +52,7 @@ impl PieceType {
             'b' => Ok(PieceType::Bishop),
             'n' => Ok(PieceType::Knight),
             'p' => Ok(PieceType::Pawn),
-            _ => Err(format!(
-                "Unexpected character. Cannot convert character `{}` to piece type.",
-                c
-            )),
+            _ => Err(MoveParseError::InvalidPiece(c)),
         };
     }
 
@@ -94,6 +67,7 @@ impl PieceType {
         };
     }
 
+    // Either color's index works, black pieces wrap around.
     pub fn bitboard_index_to_piece_type(i: usize) -> Self {
         return match i % 6 {
             0 => Self::Pawn,
@@ -101,10 +75,7 @@ impl PieceType {
             2 => Self::Knight,
             3 => Self::Rook,
             4 => Self::Queen,
-            5 => Self::King,
-            _ => {
-                panic!("Something has gone wrong converting bitboard index to piece type.");
-            }
+            _ => Self::King,
         };
     }
 }
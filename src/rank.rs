@@ -0,0 +1,32 @@
+use crate::move_parse_error::MoveParseError;
+
+// A row of the board, counted from White's side. Rank 1 is 0 and rank 8 is 7.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rank(u8);
+
+impl Rank {
+    pub const FIRST: Rank = Rank(0);
+    pub const EIGHTH: Rank = Rank(7);
+
+    pub fn new(index: usize) -> Option<Self> {
+        if index >= 8 {
+            return None;
+        }
+        return Some(Rank(index as u8));
+    }
+
+    pub fn from_char(c: char) -> Result<Self, MoveParseError> {
+        if !('1'..='8').contains(&c) {
+            return Err(MoveParseError::InvalidRank(c));
+        }
+        return Ok(Rank(c as u8 - b'1'));
+    }
+
+    pub fn index(&self) -> usize {
+        return self.0 as usize;
+    }
+
+    pub fn to_char(&self) -> char {
+        return (b'1' + self.0) as char;
+    }
+}
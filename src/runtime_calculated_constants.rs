@@ -1,8 +1,10 @@
 use crate::color::Color;
 use crate::constants;
+use crate::helpers::{count_bits, get_lsb_index, pop_bit, set_bit};
 use crate::piece_type::PieceType;
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
+use std::sync::OnceLock;
 
 // Consider moving these to true constants file? But they are only used here...
 pub const NOT_FILE_A: u64 = 18374403900871474942;
@@ -27,9 +29,29 @@ pub struct Constants {
     pub zobrist_castling_rights: [u64; 4],
     pub zobrist_en_passant: [u64; 8],
     pub zobrist_to_move: u64,
+
+    // Crazyhouse pockets. One hash for each color, droppable piece type, and count. Count 0 hashes to 0.
+    pub zobrist_pockets: [[[u64; MAX_POCKET_COUNT + 1]; 5]; 2],
+
+    // Three-check. One hash for each color and number of checks given. 0 checks hashes to 0.
+    pub zobrist_checks: [[u64; MAX_CHECK_COUNT + 1]; 2],
 }
 
+// Most pieces of one type a side can have in hand. Every pawn on the board, both colors.
+pub const MAX_POCKET_COUNT: usize = 16;
+
+// Three-check. Giving this many checks wins.
+pub const MAX_CHECK_COUNT: usize = 3;
+
+// Built once, the first time any game asks for them.
+static CONSTANTS: OnceLock<Constants> = OnceLock::new();
+
 impl Constants {
+    // The tables every `ChessGame` shares.
+    pub fn get() -> &'static Constants {
+        return CONSTANTS.get_or_init(Constants::new);
+    }
+
     pub fn new() -> Self {
         let mut pawn_attacks: [[u64; 64]; 2] = [[0; 64]; 2];
         let mut knight_attacks: [u64; 64] = [0; 64];
@@ -84,6 +106,27 @@ impl Constants {
             zobrist_en_passant[en_passant_file] = rng.gen();
         }
 
+        let zobrist_to_move: u64 = rng.gen();
+
+        // Added after the rest, so standard chess hashes stay the same.
+        let mut zobrist_pockets: [[[u64; MAX_POCKET_COUNT + 1]; 5]; 2] =
+            [[[0; MAX_POCKET_COUNT + 1]; 5]; 2];
+        for color_pockets in zobrist_pockets.iter_mut() {
+            for piece_pocket in color_pockets.iter_mut() {
+                // An empty pocket hashes as nothing.
+                for key in piece_pocket.iter_mut().skip(1) {
+                    *key = rng.gen();
+                }
+            }
+        }
+
+        let mut zobrist_checks: [[u64; MAX_CHECK_COUNT + 1]; 2] = [[0; MAX_CHECK_COUNT + 1]; 2];
+        for color_checks in zobrist_checks.iter_mut() {
+            for key in color_checks.iter_mut().skip(1) {
+                *key = rng.gen();
+            }
+        }
+
         return Constants {
             pawn_attacks,
             knight_attacks,
@@ -93,7 +136,9 @@ impl Constants {
             zobrist_table,
             zobrist_castling_rights,
             zobrist_en_passant,
-            zobrist_to_move: rng.gen(),
+            zobrist_to_move,
+            zobrist_pockets,
+            zobrist_checks,
         };
     }
 }
@@ -557,51 +602,6 @@ pub fn mask_king_attacks(square: usize) -> u64 {
     return attacks;
 }
 
-// Should these be macros? Or something similar?
-pub fn get_bit(bitboard: u64, square: usize) -> u64 {
-    return bitboard & (1 << square);
-}
-
-pub fn set_bit(bitboard: u64, square: usize) -> u64 {
-    return bitboard | (1 << square);
-}
-
-pub fn pop_bit(bitboard: u64, square: usize) -> u64 {
-    if get_bit(bitboard, square) != 0 {
-        return bitboard ^ (1 << square);
-    } else {
-        return bitboard;
-    }
-}
-
-pub fn count_bits(mut bitboard: u64) -> usize {
-    let mut bit_count = 0;
-
-    while bitboard != 0 {
-        bit_count += 1;
-
-        // Reset the least significant bit, once per iteration until there are no active bits left.
-        bitboard &= bitboard - 1;
-    }
-
-    return bit_count;
-}
-
-pub fn get_lsb_index(bitboard: u64) -> Option<usize> {
-    // Below operations will not work on bitboard of `0`.
-    if bitboard == 0 {
-        return None;
-    }
-
-    // Get the position of the least-significant bit, using some bit magic!
-    let lsb = bitboard & !bitboard + 1;
-
-    // Subtract `1` to populate the trailing bits.
-    let populated = lsb - 1;
-
-    return Some(count_bits(populated));
-}
-
 /*
     // This is the code we used to generate magic numbers. We don't need to run it again, but it should remain. Somewhere.
     struct MagicNumberHelper {
@@ -0,0 +1,39 @@
+// Depth searched when no limit is set at all, so an empty `SearchLimits` still returns.
+pub const UNLIMITED_SEARCH_DEPTH: u32 = 6;
+
+// When an iterative deepening search should stop. Limits left as `None` are ignored.
+// Node and time limits are also checked inside the search, which then drops the unfinished depth and returns the last
+// finished one. The first depth always finishes, so there's always a move, even if that overshoots the budget.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub time_ms: Option<u128>,
+}
+
+impl SearchLimits {
+    pub fn depth(depth: u32) -> Self {
+        return SearchLimits {
+            depth: Some(depth),
+            ..Default::default()
+        };
+    }
+
+    pub fn nodes(nodes: u64) -> Self {
+        return SearchLimits {
+            nodes: Some(nodes),
+            ..Default::default()
+        };
+    }
+
+    pub fn time_ms(time_ms: u128) -> Self {
+        return SearchLimits {
+            time_ms: Some(time_ms),
+            ..Default::default()
+        };
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        return self.depth.is_none() && self.nodes.is_none() && self.time_ms.is_none();
+    }
+}
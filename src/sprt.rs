@@ -0,0 +1,132 @@
+// Games played, from the point of view of the engine being tested.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct MatchScore {
+    pub wins: u64,
+    pub draws: u64,
+    pub losses: u64,
+}
+
+/**
+ * Sequential probability ratio test. Tests whether the Elo difference is `elo1` (H1) rather than `elo0` (H0), with
+ * false positive rate `alpha` and false negative rate `beta`. Uses the usual normal approximation of the trinomial log
+ * likelihood ratio, so it needs some spread in the results before it says anything.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SprtVerdict {
+    // Not enough games to tell yet.
+    Continue,
+
+    // Results fit `elo0` better. The change isn't worth `elo1`.
+    AcceptH0,
+
+    // Results fit `elo1` better.
+    AcceptH1,
+}
+
+impl MatchScore {
+    pub fn games(&self) -> u64 {
+        return self.wins + self.draws + self.losses;
+    }
+
+    // Points per game, 1 for a win and 0.5 for a draw.
+    pub fn score(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.5;
+        }
+        return (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64;
+    }
+
+    // Variance of the points from a single game.
+    fn variance(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        let score = self.score();
+        let games = self.games() as f64;
+        return (self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * score.powi(2))
+            / games;
+    }
+
+    // Elo difference the score works out to. Infinite for a clean sweep either way.
+    pub fn elo(&self) -> f64 {
+        return score_to_elo(self.score());
+    }
+
+    // Half the width of the 95% confidence interval around `elo`. Infinite until there's been more than one kind of result.
+    pub fn elo_error(&self) -> f64 {
+        if self.variance() == 0.0 {
+            return f64::INFINITY;
+        }
+        let margin = 1.96 * (self.variance() / self.games() as f64).sqrt();
+        let low = score_to_elo((self.score() - margin).max(0.0));
+        let high = score_to_elo((self.score() + margin).min(1.0));
+        return (high - low) / 2.0;
+    }
+}
+
+impl Default for Sprt {
+    // Is the change worth a few Elo? The common setting for testing small search and evaluation changes.
+    fn default() -> Self {
+        return Sprt {
+            elo0: 0.0,
+            elo1: 5.0,
+            alpha: 0.05,
+            beta: 0.05,
+        };
+    }
+}
+
+impl Sprt {
+    // Below `lower_bound` we accept H0, above `upper_bound` H1.
+    pub fn lower_bound(&self) -> f64 {
+        return (self.beta / (1.0 - self.alpha)).ln();
+    }
+
+    pub fn upper_bound(&self) -> f64 {
+        return ((1.0 - self.beta) / self.alpha).ln();
+    }
+
+    // Log likelihood ratio of H1 against H0. Zero until there's some spread in the results.
+    pub fn llr(&self, match_score: &MatchScore) -> f64 {
+        let variance = match_score.variance();
+        if variance == 0.0 {
+            return 0.0;
+        }
+        let score0 = elo_to_score(self.elo0);
+        let score1 = elo_to_score(self.elo1);
+        return match_score.games() as f64
+            * (score1 - score0)
+            * (2.0 * match_score.score() - score0 - score1)
+            / (2.0 * variance);
+    }
+
+    pub fn verdict(&self, match_score: &MatchScore) -> SprtVerdict {
+        let llr = self.llr(match_score);
+        if llr >= self.upper_bound() {
+            return SprtVerdict::AcceptH1;
+        }
+        if llr <= self.lower_bound() {
+            return SprtVerdict::AcceptH0;
+        }
+        return SprtVerdict::Continue;
+    }
+}
+
+// Expected points per game for an Elo difference.
+pub fn elo_to_score(elo: f64) -> f64 {
+    return 1.0 / (1.0 + 10f64.powf(-elo / 400.0));
+}
+
+pub fn score_to_elo(score: f64) -> f64 {
+    return -400.0 * (1.0 / score - 1.0).log10();
+}
@@ -0,0 +1,75 @@
+use crate::bitboard::Bitboard;
+use crate::file::File;
+use crate::move_parse_error::MoveParseError;
+use crate::rank::Rank;
+use std::fmt;
+use std::str::FromStr;
+
+// A square on the board. Same numbering as the bitboards: a8 is 0, h8 is 7, a1 is 56 and h1 is 63.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Square(u8);
+
+impl Square {
+    // Panics on anything off the board. Use `try_new` for indices that haven't been checked.
+    pub fn new(index: usize) -> Self {
+        if index >= 64 {
+            panic!("Square index out of range: `{index}`.");
+        }
+        return Square(index as u8);
+    }
+
+    pub fn try_new(index: usize) -> Option<Self> {
+        if index >= 64 {
+            return None;
+        }
+        return Some(Square(index as u8));
+    }
+
+    pub fn from_file_rank(file: File, rank: Rank) -> Self {
+        return Square(((7 - rank.index()) * 8 + file.index()) as u8);
+    }
+
+    pub fn index(&self) -> usize {
+        return self.0 as usize;
+    }
+
+    pub fn file(&self) -> File {
+        return File::new(self.index() % 8).expect("Square is on the board.");
+    }
+
+    pub fn rank(&self) -> Rank {
+        return Rank::new(7 - self.index() / 8).expect("Square is on the board.");
+    }
+
+    pub fn bitboard(&self) -> Bitboard {
+        return Bitboard(1 << self.0);
+    }
+}
+
+impl From<Square> for usize {
+    fn from(square: Square) -> usize {
+        return square.index();
+    }
+}
+
+// Written as a coordinate, like `e4`.
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "{}{}", self.file().to_char(), self.rank().to_char());
+    }
+}
+
+impl FromStr for Square {
+    type Err = MoveParseError;
+
+    fn from_str(s: &str) -> Result<Self, MoveParseError> {
+        let chars: Vec<char> = s.chars().collect();
+        if chars.len() != 2 {
+            return Err(MoveParseError::WrongLength(chars.len()));
+        }
+
+        let file = File::from_char(chars[0])?;
+        let rank = Rank::from_char(chars[1])?;
+        return Ok(Square::from_file_rank(file, rank));
+    }
+}
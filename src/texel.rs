@@ -0,0 +1,373 @@
+use crate::chess_game::{ChessGame, POCKET_PIECE_TYPES};
+use crate::color::Color;
+use crate::datagen::TrainingPosition;
+use crate::endgame;
+use crate::evaluation_parameters::EvaluationParameters;
+use crate::helpers::*;
+use crate::square::Square;
+use std::fs;
+
+// A position, and how the game it came from ended (1.0 white won, 0.5 draw, 0.0 black won).
+#[derive(Clone, Debug)]
+pub struct LabelledPosition {
+    pub fen: String,
+    pub result: f64,
+}
+
+/**
+ * Accepts the common formats for labelled positions:
+ *  `<fen> [1.0]`, `<fen> [0.5]`, `<fen> 1-0`, `<fen> c9 "1/2-1/2";`
+ * As well as the `<fen> | <score> | <result>` lines written by the data generator.
+ */
+pub fn parse_labelled_position(line: &str) -> Result<LabelledPosition, String> {
+    if line.contains('|') {
+        let position = TrainingPosition::from_text_line(line)?;
+        if !(0.0..=1.0).contains(&position.result) {
+            return Err(format!(
+                "Game result must be between 0 and 1. Got: {}.",
+                position.result
+            ));
+        }
+
+        return Ok(LabelledPosition {
+            fen: position.fen,
+            result: position.result,
+        });
+    }
+
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let mut fen_tokens: Vec<&str> = vec![];
+    let mut result: Option<f64> = None;
+
+    for token in tokens.iter() {
+        let cleaned = token.trim_matches(|c| c == '"' || c == ';');
+
+        let parsed_result = if cleaned.starts_with('[') && cleaned.ends_with(']') {
+            match cleaned[1..cleaned.len() - 1].parse::<f64>() {
+                Ok(r) => Some(r),
+                Err(_) => return Err(format!("Unable to parse result `{cleaned}`.")),
+            }
+        } else {
+            match cleaned {
+                "1-0" => Some(1.0),
+                "0-1" => Some(0.0),
+                "1/2-1/2" => Some(0.5),
+                _ => None,
+            }
+        };
+
+        match parsed_result {
+            Some(r) => {
+                result = Some(r);
+                break;
+            }
+            None => fen_tokens.push(token),
+        }
+    }
+
+    // EPD style lines put the result in the `c9` opcode.
+    if fen_tokens.last() == Some(&"c9") {
+        fen_tokens.pop();
+    }
+
+    let result = match result {
+        Some(r) => r,
+        None => return Err(format!("No game result found in line: `{line}`.")),
+    };
+    if !(0.0..=1.0).contains(&result) {
+        return Err(format!(
+            "Game result must be between 0 and 1. Got: {result}."
+        ));
+    }
+    if fen_tokens.len() < 2 {
+        return Err(format!("No FEN found in line: `{line}`."));
+    }
+
+    return Ok(LabelledPosition {
+        fen: fen_tokens.join(" "),
+        result,
+    });
+}
+
+// Blank lines and lines starting with `#` are skipped.
+pub fn load_labelled_positions(path: &str) -> Result<Vec<LabelledPosition>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) => {
+            return Err(format!(
+                "Unable to read positions file `{path}`. Error: {e}"
+            ))
+        }
+    };
+
+    let mut positions: Vec<LabelledPosition> = vec![];
+    for (line_number, line) in contents.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        match parse_labelled_position(trimmed) {
+            Ok(p) => positions.push(p),
+            Err(e) => return Err(format!("Line {}: {e}", line_number + 1)),
+        }
+    }
+
+    return Ok(positions);
+}
+
+// Expected score for white, given an evaluation in centipawns.
+pub fn sigmoid(evaluation: f64, k: f64) -> f64 {
+    return 1.0 / (1.0 + f64::powf(10.0, -k * evaluation / 400.0));
+}
+
+/**
+ * The evaluation is a weighted sum of the parameters, plus terms we do not tune.
+ * So each position boils down to how many times each parameter is used (white minus black),
+ * and a fixed leftover. This lets us try new weights without rebuilding the board.
+ */
+struct TexelEntry {
+    coefficients: Vec<(usize, i64)>,
+    fixed: i64,
+    result: f64,
+}
+
+pub struct TexelTuner {
+    entries: Vec<TexelEntry>,
+
+    // Positions left out because the evaluation there is not a weighted sum of the parameters.
+    pub skipped: usize,
+
+    // Which entries use each parameter, and how many times.
+    parameter_users: Vec<Vec<(usize, i64)>>,
+    pub k: f64,
+}
+
+impl TexelTuner {
+    pub fn new(
+        positions: &[LabelledPosition],
+        parameters: &EvaluationParameters,
+    ) -> Result<Self, String> {
+        let mut game = ChessGame::new();
+        game.evaluation_parameters = parameters.clone();
+        let values = parameters.to_vec();
+
+        let mut entries: Vec<TexelEntry> = vec![];
+        let mut skipped: usize = 0;
+        let mut parameter_users: Vec<Vec<(usize, i64)>> =
+            vec![vec![]; EvaluationParameters::parameter_count()];
+
+        for position in positions.iter() {
+            game.import_fen(&position.fen).map_err(|e| e.to_string())?;
+
+            // Known endings replace the evaluation outright, and mop-up switches on at a material threshold.
+            // Piece values do nothing in either, so fitting them there would only add noise.
+            if endgame::evaluate_endgame(&game).is_some() || endgame::mop_up(&game).is_some() {
+                skipped += 1;
+                continue;
+            }
+
+            let mut coefficients: Vec<(usize, i64)> = vec![];
+            let mut occupancies = game.occupied().0;
+            while occupancies != 0 {
+                let square = get_lsb_index(occupancies).expect("Guard clause.");
+                let piece = game
+                    .get_piece_at_square(Square::new(square))
+                    .expect("Square is occupied.");
+                let (piece_type, color) = (piece.piece_type, piece.color);
+                let sign = match color {
+                    Color::White => 1,
+                    Color::Black => -1,
+                };
+
+                if let Some(i) = EvaluationParameters::piece_value_parameter_index(piece_type) {
+                    add_coefficient(&mut coefficients, i, sign);
+                }
+                add_coefficient(
+                    &mut coefficients,
+                    EvaluationParameters::happy_square_parameter_index(piece_type, square, color),
+                    sign,
+                );
+
+                occupancies = pop_bit(occupancies, square);
+            }
+
+            // Crazyhouse pieces in hand are scored at their piece value.
+            for side in [Color::White, Color::Black] {
+                let sign = match side {
+                    Color::White => 1,
+                    Color::Black => -1,
+                };
+                for piece_type in POCKET_PIECE_TYPES.iter() {
+                    let count = game.pocket(side, *piece_type) as i64;
+                    if let Some(i) = EvaluationParameters::piece_value_parameter_index(*piece_type)
+                    {
+                        add_coefficient(&mut coefficients, i, sign * count);
+                    }
+                }
+            }
+
+            // White and black pieces can cancel each other out.
+            coefficients.retain(|(_, c)| *c != 0);
+
+            // Whatever the parameters do not explain is left as a constant.
+            let linear: i64 = coefficients.iter().map(|(i, c)| values[*i] * c).sum();
+            let fixed = game.evaluate_board() - linear;
+
+            let entry_index = entries.len();
+            for (i, c) in coefficients.iter() {
+                parameter_users[*i].push((entry_index, *c));
+            }
+
+            entries.push(TexelEntry {
+                coefficients,
+                fixed,
+                result: position.result,
+            });
+        }
+
+        return Ok(TexelTuner {
+            entries,
+            skipped,
+            parameter_users,
+            k: 1.0,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    fn evaluate_all(&self, values: &[i64]) -> Vec<i64> {
+        return self
+            .entries
+            .iter()
+            .map(|e| {
+                e.fixed
+                    + e.coefficients
+                        .iter()
+                        .map(|(i, c)| values[*i] * c)
+                        .sum::<i64>()
+            })
+            .collect();
+    }
+
+    pub fn mean_squared_error(&self, values: &[i64], k: f64) -> f64 {
+        if self.entries.is_empty() {
+            return 0.0;
+        }
+
+        let evaluations = self.evaluate_all(values);
+        let mut total_error: f64 = 0.0;
+        for (entry, evaluation) in self.entries.iter().zip(evaluations.iter()) {
+            let error = entry.result - sigmoid(*evaluation as f64, k);
+            total_error += error * error;
+        }
+
+        return total_error / self.entries.len() as f64;
+    }
+
+    // Find the scaling constant that best maps our evaluations to game results. Golden section search.
+    pub fn find_best_k(&mut self, values: &[i64]) -> f64 {
+        let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+        let mut low: f64 = 0.01;
+        let mut high: f64 = 10.0;
+
+        while high - low > 0.0001 {
+            let left = high - ratio * (high - low);
+            let right = low + ratio * (high - low);
+            if self.mean_squared_error(values, left) < self.mean_squared_error(values, right) {
+                high = right;
+            } else {
+                low = left;
+            }
+        }
+
+        self.k = (low + high) / 2.0;
+        return self.k;
+    }
+
+    /**
+     * Texel's local search. Nudge each parameter up or down by one, and keep the change if the error drops.
+     * Stops when a full pass makes no progress, or we run out of iterations.
+     */
+    pub fn tune(
+        &self,
+        parameters: &EvaluationParameters,
+        max_iterations: u32,
+        verbose: bool,
+    ) -> EvaluationParameters {
+        let mut values = parameters.to_vec();
+        if self.entries.is_empty() {
+            return parameters.clone();
+        }
+
+        let mut evaluations = self.evaluate_all(&values);
+        let mut errors: Vec<f64> = self
+            .entries
+            .iter()
+            .zip(evaluations.iter())
+            .map(|(e, v)| {
+                let error = e.result - sigmoid(*v as f64, self.k);
+                error * error
+            })
+            .collect();
+        let mut best_error = self.mean_squared_error(&values, self.k);
+
+        for iteration in 0..max_iterations {
+            let mut improved = false;
+
+            for (parameter, users) in self.parameter_users.iter().enumerate() {
+                // Parameters that never show up in the data cannot be tuned.
+                if users.is_empty() {
+                    continue;
+                }
+
+                for delta in [1, -1] {
+                    let mut error_change: f64 = 0.0;
+                    for (entry_index, coefficient) in users.iter() {
+                        let new_evaluation = evaluations[*entry_index] + delta * coefficient;
+                        let error = self.entries[*entry_index].result
+                            - sigmoid(new_evaluation as f64, self.k);
+                        error_change += error * error - errors[*entry_index];
+                    }
+
+                    if error_change / (self.entries.len() as f64) < -1e-12 {
+                        values[parameter] += delta;
+                        for (entry_index, coefficient) in users.iter() {
+                            evaluations[*entry_index] += delta * coefficient;
+                            let error = self.entries[*entry_index].result
+                                - sigmoid(evaluations[*entry_index] as f64, self.k);
+                            errors[*entry_index] = error * error;
+                        }
+                        best_error += error_change / (self.entries.len() as f64);
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+
+            if verbose {
+                println!("Iteration {}: error {:.8}", iteration + 1, best_error);
+            }
+
+            if !improved {
+                break;
+            }
+        }
+
+        return EvaluationParameters::from_vec(&values)
+            .expect("Tuning does not change the number of parameters.");
+    }
+}
+
+fn add_coefficient(coefficients: &mut Vec<(usize, i64)>, index: usize, amount: i64) {
+    match coefficients.iter_mut().find(|(i, _)| *i == index) {
+        Some((_, c)) => *c += amount,
+        None => coefficients.push((index, amount)),
+    }
+}
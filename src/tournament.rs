@@ -0,0 +1,212 @@
+use crate::color::Color;
+use crate::engine_match::{MatchGame, MatchOptions, Referee, Termination, TIME_FORFEIT_MARGIN_MS};
+use crate::fen_error::FenError;
+use crate::game_result::GameResult;
+use crate::sprt::MatchScore;
+use crate::uci_engine::UciEngine;
+use crate::uci_error::UciError;
+use std::fmt;
+
+// One game of the schedule. Players and the opening are indexes into the tournament's lists.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pairing {
+    pub white: usize,
+    pub black: usize,
+    pub opening: usize,
+}
+
+// Results between every pair of players. `scores[a][b]` is how `a` did against `b`, whatever the colors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Crosstable {
+    pub names: Vec<String>,
+    pub scores: Vec<Vec<MatchScore>>,
+}
+
+/**
+ * Every pair of players plays `games_per_pairing` games. Like in an engine match, each opening is played twice with
+ * the colors swapped, and the openings are used in order. All pairings play their first game before any plays its
+ * second, so a tournament cut short is still about even.
+ */
+pub fn round_robin(players: usize, openings: usize, games_per_pairing: usize) -> Vec<Pairing> {
+    let mut schedule = vec![];
+    for game_number in 0..games_per_pairing {
+        for first in 0..players {
+            for second in first + 1..players {
+                let (white, black) = match game_number % 2 {
+                    0 => (first, second),
+                    _ => (second, first),
+                };
+                schedule.push(Pairing {
+                    white,
+                    black,
+                    opening: (game_number / 2) % openings,
+                });
+            }
+        }
+    }
+    return schedule;
+}
+
+/**
+ * Plays one game between two UCI engines from `opening_fen`, keeping their clocks. Every move they send is checked
+ * against the legal moves. An engine that sends an illegal move, runs out of time, or stops answering loses, with the
+ * reason in a PGN comment.
+ */
+pub fn play_game(
+    white: &mut UciEngine,
+    black: &mut UciEngine,
+    opening_fen: &str,
+    options: &MatchOptions,
+) -> Result<MatchGame, FenError> {
+    let mut referee = Referee::new(options.variant, opening_fen, options.adjudication)?;
+    referee.pgn.set_tag("Event", "Tournament");
+    referee.pgn.set_tag("White", &white.name);
+    referee.pgn.set_tag("Black", &black.name);
+
+    // Engines get the opening once and the moves since, so they can spot repetitions. Some insist on move counters.
+    let start_fen = format!("{} 0 1", referee.game.export_fen());
+    let mut moves: Vec<String> = vec![];
+
+    // Indexed by color, like the clocks.
+    let mut engines = [white, black];
+    let time_control = options.time_control;
+    let mut clocks = [time_control.base_ms; 2];
+
+    for side in [Color::White, Color::Black] {
+        if let Err(e) = engines[side.idx()].new_game(options.variant) {
+            let name = engines[side.idx()].name.clone();
+            return Ok(referee.forfeit(side, Termination::EngineFailure, &format!("{name}: {e}")));
+        }
+    }
+
+    let (result, termination) = loop {
+        if let Some(game_over) = referee.game_over() {
+            break game_over;
+        }
+        let side = referee.side_to_move();
+        let mover = side.idx();
+        let engine = &mut engines[mover];
+
+        let answer = match engine.go(
+            &start_fen,
+            &moves,
+            clocks,
+            time_control.increment_ms,
+            clocks[mover] + TIME_FORFEIT_MARGIN_MS,
+        ) {
+            Ok(answer) => answer,
+            Err(UciError::Timeout(_)) => {
+                let detail = format!("{} ran out of time.", engine.name);
+                return Ok(referee.forfeit(side, Termination::TimeForfeit, &detail));
+            }
+            Err(e) => {
+                let detail = format!("{}: {e}", engine.name);
+                return Ok(referee.forfeit(side, Termination::EngineFailure, &detail));
+            }
+        };
+        clocks[mover] = clocks[mover].saturating_sub(answer.time_ms) + time_control.increment_ms;
+
+        let this_move = match referee.game.uci_to_legal_move(&answer.best_move) {
+            Ok(m) => m,
+            Err(e) => {
+                let detail = format!("{}: {e}", engine.name);
+                return Ok(referee.forfeit(side, Termination::IllegalMove, &detail));
+            }
+        };
+
+        // Engines score for the side to move, the referee from white's side.
+        let score = answer.score.map(|score| match (side, score) {
+            (Color::White, _) => score,
+            (Color::Black, i64::MAX) => i64::MIN,
+            (Color::Black, i64::MIN) => i64::MAX,
+            (Color::Black, _) => -score,
+        });
+        referee.play_move(&this_move, score);
+        moves.push(answer.best_move);
+    };
+
+    return Ok(referee.finish(result, termination));
+}
+
+impl Crosstable {
+    pub fn new(names: &[String]) -> Self {
+        return Crosstable {
+            names: names.to_vec(),
+            scores: vec![vec![MatchScore::default(); names.len()]; names.len()],
+        };
+    }
+
+    pub fn add_game(&mut self, white: usize, black: usize, result: GameResult) {
+        match result {
+            GameResult::WhiteWins => {
+                self.scores[white][black].wins += 1;
+                self.scores[black][white].losses += 1;
+            }
+            GameResult::BlackWins => {
+                self.scores[white][black].losses += 1;
+                self.scores[black][white].wins += 1;
+            }
+            GameResult::Draw => {
+                self.scores[white][black].draws += 1;
+                self.scores[black][white].draws += 1;
+            }
+            GameResult::Ongoing => {}
+        }
+    }
+
+    // All of `player`'s games, against everyone.
+    pub fn total(&self, player: usize) -> MatchScore {
+        let mut total = MatchScore::default();
+        for score in self.scores[player].iter() {
+            total.wins += score.wins;
+            total.draws += score.draws;
+            total.losses += score.losses;
+        }
+        return total;
+    }
+
+    // Player indexes, most points first.
+    pub fn standings(&self) -> Vec<usize> {
+        let mut standings: Vec<usize> = (0..self.names.len()).collect();
+        standings.sort_by(|a, b| points(&self.total(*b)).total_cmp(&points(&self.total(*a))));
+        return standings;
+    }
+}
+
+fn points(score: &MatchScore) -> f64 {
+    return score.wins as f64 + score.draws as f64 / 2.0;
+}
+
+impl fmt::Display for Crosstable {
+    // Players in order of points, then their points against each of the others, in the same order.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let standings = self.standings();
+        let name_width = self.names.iter().map(|n| n.len()).max().unwrap_or(0).max(6);
+
+        write!(f, "    {:name_width$}  Points  Games", "Engine")?;
+        for column in 1..=standings.len() {
+            write!(f, " {column:>5}")?;
+        }
+        writeln!(f)?;
+
+        for (rank, player) in standings.iter().enumerate() {
+            let total = self.total(*player);
+            write!(
+                f,
+                "{:>2}. {:name_width$}  {:>6.1}  {:>5}",
+                rank + 1,
+                self.names[*player],
+                points(&total),
+                total.games()
+            )?;
+            for opponent in standings.iter() {
+                match opponent == player {
+                    true => write!(f, " {:>5}", "-")?,
+                    false => write!(f, " {:>5.1}", points(&self.scores[*player][*opponent]))?,
+                }
+            }
+            writeln!(f)?;
+        }
+        return Ok(());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::uci_error::UciError;
+use crate::variant::Variant;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How long an engine gets to answer anything but `go`.
+pub const UCI_RESPONSE_TIMEOUT_MS: u128 = 10_000;
+
+/**
+ * An engine running as a child process, spoken to over UCI. Its output is read on a thread of its own, so an engine
+ * that hangs can be timed out rather than hanging us too. The engine is told to quit when this is dropped.
+ */
+pub struct UciEngine {
+    // From `id name`, or the path if the engine didn't send one.
+    pub name: String,
+
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+// What an engine answered to `go`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UciSearchResult {
+    // As sent, like `e2e4`. Nothing checks that it's legal, that's up to whoever is running the game.
+    pub best_move: String,
+
+    // The last score the engine reported, in centipawns for the side to move. Mates are `i64::MAX` or `i64::MIN`,
+    // like our own search.
+    pub score: Option<i64>,
+
+    pub time_ms: u128,
+}
+
+impl UciEngine {
+    // Starts the engine at `path`, sets `options` (name and value pairs), and waits until it's ready.
+    pub fn start(path: &str, options: &[(String, String)]) -> Result<Self, UciError> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|error| UciError::Spawn {
+                path: path.to_string(),
+                error,
+            })?;
+        let stdin = child.stdin.take().expect("stdin is piped.");
+        let stdout = child.stdout.take().expect("stdout is piped.");
+
+        // The thread ends when the engine closes its output, which drops the sender and tells us it's gone.
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let sent = line.map(|line| sender.send(line).is_ok());
+                if !matches!(sent, Ok(true)) {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = UciEngine {
+            name: path.to_string(),
+            child,
+            stdin,
+            lines,
+        };
+
+        engine.send("uci")?;
+        let deadline = deadline_after(UCI_RESPONSE_TIMEOUT_MS);
+        loop {
+            let line = engine.read_line(deadline, "uciok")?;
+            if let Some(name) = line.trim().strip_prefix("id name ") {
+                engine.name = name.trim().to_string();
+            }
+            if line.trim() == "uciok" {
+                break;
+            }
+        }
+
+        for (name, value) in options {
+            engine.send(&format!("setoption name {name} value {value}"))?;
+        }
+        engine.wait_until_ready()?;
+        return Ok(engine);
+    }
+
+    // Gets the engine ready for a new game. Engines that play Chess960 need to be told to.
+    pub fn new_game(&mut self, variant: Variant) -> Result<(), UciError> {
+        if variant == Variant::Chess960 {
+            self.send("setoption name UCI_Chess960 value true")?;
+        }
+        self.send("ucinewgame")?;
+        return self.wait_until_ready();
+    }
+
+    /**
+     * Asks for a move in the position `fen` followed by `moves` (in UCI), with the clocks (white, then black) and
+     * increment to report. Gives up after `timeout_ms`, telling the engine to stop.
+     */
+    pub fn go(
+        &mut self,
+        fen: &str,
+        moves: &[String],
+        clocks: [u128; 2],
+        increment_ms: u128,
+        timeout_ms: u128,
+    ) -> Result<UciSearchResult, UciError> {
+        let mut position = format!("position fen {fen}");
+        if !moves.is_empty() {
+            position.push_str(" moves ");
+            position.push_str(&moves.join(" "));
+        }
+        self.send(&position)?;
+
+        let start_time = Instant::now();
+        self.send(&format!(
+            "go wtime {} btime {} winc {increment_ms} binc {increment_ms}",
+            clocks[0], clocks[1]
+        ))?;
+
+        let deadline = deadline_after(timeout_ms);
+        let mut score = None;
+        loop {
+            let line = match self.read_line(deadline, "bestmove") {
+                Ok(line) => line,
+                Err(UciError::Timeout(waiting_for)) => {
+                    // Let it finish so its late `bestmove` doesn't get read as the answer to our next `go`.
+                    self.send("stop")?;
+                    let deadline = deadline_after(UCI_RESPONSE_TIMEOUT_MS);
+                    while !self
+                        .read_line(deadline, "bestmove")?
+                        .starts_with("bestmove")
+                    {}
+                    return Err(UciError::Timeout(waiting_for));
+                }
+                Err(e) => return Err(e),
+            };
+
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("info") => score = parse_score(&line).or(score),
+                Some("bestmove") => {
+                    return Ok(UciSearchResult {
+                        best_move: words.next().unwrap_or_default().to_string(),
+                        score,
+                        time_ms: start_time.elapsed().as_millis(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn wait_until_ready(&mut self) -> Result<(), UciError> {
+        self.send("isready")?;
+        let deadline = deadline_after(UCI_RESPONSE_TIMEOUT_MS);
+        while self.read_line(deadline, "readyok")?.trim() != "readyok" {}
+        return Ok(());
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), UciError> {
+        writeln!(self.stdin, "{command}")?;
+        self.stdin.flush()?;
+        return Ok(());
+    }
+
+    // The next line from the engine, or an error once `deadline` passes. `waiting_for` is for the error.
+    fn read_line(&self, deadline: Instant, waiting_for: &str) -> Result<String, UciError> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        return match self.lines.recv_timeout(timeout) {
+            Ok(line) => Ok(line),
+            Err(RecvTimeoutError::Timeout) => Err(UciError::Timeout(waiting_for.to_string())),
+            Err(RecvTimeoutError::Disconnected) => Err(UciError::Disconnected),
+        };
+    }
+}
+
+impl Drop for UciEngine {
+    // Asks nicely first, then kills engines that are still going a moment later.
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let deadline = deadline_after(500);
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/**
+ * The score from an `info` line, like `info depth 12 score cp -35 nodes 1000 pv e7e5`. Centipawns for the side to
+ * move; `score mate 3` is `i64::MAX` and `score mate -3` (getting mated) `i64::MIN`.
+ */
+pub fn parse_score(info: &str) -> Option<i64> {
+    let words: Vec<&str> = info.split_whitespace().collect();
+    let score_index = words.iter().position(|w| *w == "score")?;
+    let value = words.get(score_index + 2)?.parse::<i64>().ok()?;
+    return match *words.get(score_index + 1)? {
+        "cp" => Some(value),
+        "mate" if value > 0 => Some(i64::MAX),
+        "mate" => Some(i64::MIN),
+        _ => None,
+    };
+}
+
+fn deadline_after(ms: u128) -> Instant {
+    return Instant::now() + Duration::from_millis(ms as u64);
+}
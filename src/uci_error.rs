@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+// Anything that can go wrong running an engine over UCI.
+#[derive(Debug)]
+pub enum UciError {
+    // The engine at `path` couldn't be started.
+    Spawn { path: String, error: io::Error },
+
+    // Writing to the engine failed, normally because it has exited.
+    Io(io::Error),
+
+    // The engine closed its output, so it has exited or crashed.
+    Disconnected,
+
+    // No answer in time. Holds what we were waiting for.
+    Timeout(String),
+}
+
+impl fmt::Display for UciError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            UciError::Spawn { path, error } => {
+                write!(f, "Unable to start engine `{path}`. Error: {error}")
+            }
+            UciError::Io(e) => write!(f, "Unable to talk to the engine. Error: {e}"),
+            UciError::Disconnected => write!(f, "The engine exited."),
+            UciError::Timeout(waiting_for) => {
+                write!(
+                    f,
+                    "The engine didn't answer in time. Waiting for `{waiting_for}`."
+                )
+            }
+        };
+    }
+}
+
+impl Error for UciError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        return match self {
+            UciError::Spawn { error, .. } => Some(error),
+            UciError::Io(e) => Some(e),
+            _ => None,
+        };
+    }
+}
+
+impl From<io::Error> for UciError {
+    fn from(e: io::Error) -> Self {
+        return UciError::Io(e);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::lichess_error::LichessError;
+use crate::pgn_error::PgnError;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Variant {
+    Standard,
+
+    // Fischer Random. Same rules, but kings and rooks can start on any file.
+    // Castling is written as the king taking its own rook.
+    Chess960,
+
+    // Captured pieces go to the capturer's pocket, and can be dropped back on the board as a move.
+    Crazyhouse,
+
+    // Captures are compulsory and the king is just another piece. Losing everything (or getting stalemated) wins.
+    Antichess,
+
+    // Captures explode, removing every piece but pawns around the target square. Blowing up the king wins.
+    Atomic,
+
+    // Giving check three times wins.
+    ThreeCheck,
+
+    // Getting your king to one of the four centre squares wins.
+    KingOfTheHill,
+
+    // No checks allowed, and the first king to reach the eighth rank wins. Black gets one more move to draw.
+    RacingKings,
+}
+
+impl Variant {
+    // Variant keys as sent by the Lichess API.
+    pub fn from_lichess_key(key: &str) -> Result<Self, LichessError> {
+        return match key {
+            "standard" | "fromPosition" => Ok(Variant::Standard),
+            "chess960" => Ok(Variant::Chess960),
+            "crazyhouse" => Ok(Variant::Crazyhouse),
+            "antichess" => Ok(Variant::Antichess),
+            "atomic" => Ok(Variant::Atomic),
+            "threeCheck" => Ok(Variant::ThreeCheck),
+            "kingOfTheHill" => Ok(Variant::KingOfTheHill),
+            "racingKings" => Ok(Variant::RacingKings),
+            _ => Err(LichessError::UnsupportedVariant(key.to_string())),
+        };
+    }
+
+    // Name used in the PGN `Variant` tag, as Lichess writes it.
+    pub fn pgn_name(&self) -> &'static str {
+        return match self {
+            Variant::Standard => "Standard",
+            Variant::Chess960 => "Chess960",
+            Variant::Crazyhouse => "Crazyhouse",
+            Variant::Antichess => "Antichess",
+            Variant::Atomic => "Atomic",
+            Variant::ThreeCheck => "Three-check",
+            Variant::KingOfTheHill => "King of the Hill",
+            Variant::RacingKings => "Racing Kings",
+        };
+    }
+
+    // Reads the PGN `Variant` tag. Ignores case, spaces and dashes, so `three check` and `kingOfTheHill` work too.
+    pub fn from_pgn_name(name: &str) -> Result<Self, PgnError> {
+        let key: String = name
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        return match key.as_str() {
+            "standard" | "fromposition" => Ok(Variant::Standard),
+            "chess960" | "fischerandom" | "fischerrandom" => Ok(Variant::Chess960),
+            "crazyhouse" => Ok(Variant::Crazyhouse),
+            "antichess" | "giveaway" => Ok(Variant::Antichess),
+            "atomic" => Ok(Variant::Atomic),
+            "threecheck" => Ok(Variant::ThreeCheck),
+            "kingofthehill" => Ok(Variant::KingOfTheHill),
+            "racingkings" => Ok(Variant::RacingKings),
+            _ => Err(PgnError::UnsupportedVariant(name.to_string())),
+        };
+    }
+
+    // Whether the usual endgame knowledge (drawn material, mating patterns, KPK) applies.
+    pub fn has_standard_endgames(&self) -> bool {
+        return matches!(
+            self,
+            Variant::Standard | Variant::Chess960 | Variant::Crazyhouse
+        );
+    }
+
+    // Whether a side can have `count` kings. Antichess kings are just another piece, and atomic games can end with a
+    // king blown off the board.
+    pub fn allows_king_count(&self, count: usize) -> bool {
+        return match self {
+            Variant::Antichess => true,
+            Variant::Atomic => count <= 1,
+            _ => count == 1,
+        };
+    }
+
+    // Whether the king can't be left in check, and nothing else changes when a move is legal.
+    // Antichess has no royal king, atomic explosions can take kings off the board, and racing kings bans giving check.
+    pub fn has_standard_king_safety(&self) -> bool {
+        return !matches!(
+            self,
+            Variant::Antichess | Variant::Atomic | Variant::RacingKings
+        );
+    }
+}
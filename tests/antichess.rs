@@ -0,0 +1,99 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::piece_type::PieceType;
+use chess_engine::variant::Variant;
+
+fn antichess_game() -> ChessGame {
+    let mut game = ChessGame::new();
+    game.variant = Variant::Antichess;
+    return game;
+}
+
+#[test]
+fn test_antichess_perft() {
+    let mut game = antichess_game();
+
+    let positions: Vec<(&str, [u64; 3])> = vec![
+        (INITIAL_GAME_STATE_FEN, [20, 400, 8_067]),
+        (
+            "rnb1kb1r/3p1ppp/ppp1p3/8/P1P2PnP/1P6/3PP1P1/RNB1QB1R w - - 1 9",
+            [22, 346, 4_782],
+        ),
+        ("4q3/8/Bp2p1p1/8/8/2P5/P1K5/R7 w - - 1 13", [24, 339, 4_873]),
+        // Promoting to a king leaves black with two of them.
+        (
+            "r3k2r/bppp1Npp/5nb1/nP6/B1P1P3/B4N2/Pp1P2PP/R2Q1RK1 b - - 0 2",
+            [11, 22, 140],
+        ),
+    ];
+
+    for (fen, expected) in positions.iter() {
+        assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+        for (depth, nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                game.perft(depth as u32 + 1),
+                *nodes,
+                "Perft {} of {fen}.",
+                depth + 1
+            );
+        }
+    }
+}
+
+#[test]
+fn test_captures_are_compulsory() {
+    let mut game = antichess_game();
+    let _ = game.import_fen("rnbqkbnr/p1pppppp/8/1p6/8/4P3/PPPP1PPP/RNBQKBNR w - - 0 2");
+    game.set_legal_moves(None);
+
+    assert_eq!(game.legal_moves.len(), 1);
+    assert!(game.uci_to_legal_move("f1b5").is_ok());
+    assert!(game.uci_to_legal_move("e3e4").is_err());
+}
+
+#[test]
+fn test_king_is_not_royal() {
+    let mut game = antichess_game();
+
+    // The king can walk into attacks, be taken, and a pawn can promote to one.
+    let _ = game.import_fen("8/1P6/8/8/8/8/r7/4K3 w - - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("e1e2").is_ok());
+    assert!(game.uci_to_legal_move("e1d2").is_ok());
+    let promotion = game
+        .uci_to_legal_move("b7b8k")
+        .expect("Pawns can promote to a king.");
+    assert_eq!(promotion.pawn_promoting_to, Some(PieceType::King));
+    assert!(!game.is_checkmate());
+
+    // No castling either.
+    let _ = game.import_fen("4k3/8/8/8/8/8/8/R3K2R w - - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("e1g1").is_err());
+    assert!(game.uci_to_legal_move("e1c1").is_err());
+}
+
+#[test]
+fn test_losing_everything_wins() {
+    let mut game = antichess_game();
+
+    // White's last piece has to take, and then white has nothing left.
+    let _ = game.import_fen("8/8/8/8/8/8/1p6/2R5 b - - 0 1");
+    game.set_legal_moves(None);
+    let capture = game.uci_to_legal_move("b2c1q").unwrap();
+    game.make_move(&capture, true);
+    assert!(game.is_antichess_win());
+    assert!(!game.is_checkmate());
+    assert!(!game.is_stalemate());
+
+    // Being stuck counts too.
+    let _ = game.import_fen("8/8/8/8/8/p7/P7/8 w - - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.is_antichess_win());
+
+    // The search sees that black has to hand white the win.
+    let _ = game.import_fen("8/8/8/8/8/8/1p6/2R5 b - - 0 1");
+    game.set_legal_moves(None);
+    let (score, _) = game.minimax(2, std::i64::MIN, std::i64::MAX);
+    assert_eq!(score, std::i64::MAX);
+}
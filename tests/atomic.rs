@@ -0,0 +1,110 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::color::Color;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::variant::Variant;
+
+fn atomic_game() -> ChessGame {
+    let mut game = ChessGame::new();
+    game.variant = Variant::Atomic;
+    return game;
+}
+
+#[test]
+fn test_atomic_perft() {
+    let mut game = atomic_game();
+
+    let positions: Vec<(&str, [u64; 3])> = vec![
+        (INITIAL_GAME_STATE_FEN, [20, 400, 8_902]),
+        (
+            "rn2kb1r/1b1p1ppp/1pp1p3/p7/P1P1PP2/8/1P1P1K1P/RNBQ1B1R w kq - 1 9",
+            [32, 762, 24_195],
+        ),
+        (
+            "r3k1nr/p1ppqpb1/bn2p1p1/3PN3/1P2PQ2/7p/P1P1BPPP/R3K2R w KQkq - 1 3",
+            [50, 1_961, 88_360],
+        ),
+        (
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            [8, 311, 10_932],
+        ),
+    ];
+
+    for (fen, expected) in positions.iter() {
+        assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+        for (depth, nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                game.perft(depth as u32 + 1),
+                *nodes,
+                "Perft {} of {fen}.",
+                depth + 1
+            );
+        }
+    }
+}
+
+#[test]
+fn test_capture_explodes() {
+    let mut game = atomic_game();
+    let fen = "4k3/8/2npb3/3r4/8/8/3Q4/4K3 w - - 0 1";
+    let _ = game.import_fen(fen);
+    game.set_legal_moves(None);
+    let hash = game.zobrist_hash;
+
+    // Everything around d5 goes, apart from the pawn. The queen goes with it.
+    let capture = game.uci_to_legal_move("d2d5").unwrap();
+    game.make_move(&capture, true);
+    assert_eq!(game.export_fen(), "4k3/8/3p4/8/8/8/8/4K3 b - -");
+
+    let mut fresh = atomic_game();
+    let _ = fresh.import_fen(&game.export_fen());
+    assert_eq!(game.zobrist_hash, fresh.zobrist_hash);
+
+    game.unmake_move(&capture);
+    assert_eq!(game.export_fen(), "4k3/8/2npb3/3r4/8/8/3Q4/4K3 w - -");
+    assert_eq!(game.zobrist_hash, hash);
+}
+
+#[test]
+fn test_own_king_cant_be_blown_up() {
+    let mut game = atomic_game();
+
+    // Taking on d2 would blow up our own king.
+    let _ = game.import_fen("4k3/8/8/8/8/8/3n4/3QK3 w - - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("d1d2").is_err());
+    assert!(game.uci_to_legal_move("e1d2").is_err());
+
+    // Kings can't take anything at all.
+    let _ = game.import_fen("4k3/8/8/8/8/8/4n3/4K3 w - - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("e1e2").is_err());
+}
+
+#[test]
+fn test_touching_kings_are_never_in_check() {
+    let mut game = atomic_game();
+    let _ = game.import_fen("8/8/8/8/8/3k4/4K2r/8 w - - 0 1");
+    game.set_legal_moves(None);
+
+    // The rook is looking at our king, but taking it would blow up theirs.
+    assert!(!game.is_in_check(&Color::White));
+
+    // Stepping away from the other king walks into the rook, staying next to it doesn't.
+    assert!(game.uci_to_legal_move("e2f2").is_err());
+    assert!(game.uci_to_legal_move("e2d2").is_ok());
+}
+
+#[test]
+fn test_blowing_up_the_king_wins() {
+    let mut game = atomic_game();
+
+    // Taking next to the black king ends the game, even with our own king in check.
+    let _ = game.import_fen("3rk3/4p3/8/8/8/8/8/3RK2q w - - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.is_in_check(&Color::White));
+    let capture = game.uci_to_legal_move("d1d8").unwrap();
+    game.make_move(&capture, true);
+    assert!(game.legal_moves.is_empty());
+    assert!(game.is_checkmate());
+    assert!(!game.is_stalemate());
+}
@@ -0,0 +1,35 @@
+use chess_engine::bench::{nodes_per_second, run_bench, BenchResult, BENCH_POSITIONS};
+use chess_engine::chess_game::ChessGame;
+
+#[test]
+fn test_bench_positions_are_valid() {
+    let mut game = ChessGame::new();
+    for fen in BENCH_POSITIONS {
+        assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+        game.set_legal_moves(None);
+        assert!(!game.legal_moves.is_empty(), "No moves to search in {fen}.");
+    }
+}
+
+fn node_counts(results: &[BenchResult]) -> Vec<u64> {
+    return results.iter().map(|r| r.nodes).collect();
+}
+
+#[test]
+fn test_bench_is_reproducible() {
+    let first = run_bench(2);
+    let second = run_bench(2);
+    assert_eq!(first.len(), BENCH_POSITIONS.len());
+    assert_eq!(node_counts(&first), node_counts(&second));
+    assert!(first.iter().all(|r| r.nodes > 0));
+
+    // Shallower searches look at less.
+    let shallower: u64 = node_counts(&run_bench(1)).iter().sum();
+    assert!(shallower < node_counts(&first).iter().sum());
+}
+
+#[test]
+fn test_nodes_per_second() {
+    assert_eq!(nodes_per_second(150_000, 3_000), 50_000);
+    assert_eq!(nodes_per_second(150_000, 0), 0);
+}
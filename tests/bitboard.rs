@@ -0,0 +1,62 @@
+use chess_engine::bitboard::Bitboard;
+use chess_engine::color::Color;
+use chess_engine::piece::Piece;
+use chess_engine::piece_type::PieceType;
+use chess_engine::square::Square;
+
+fn square(s: &str) -> Square {
+    return s.parse().unwrap();
+}
+
+#[test]
+fn test_bitboard_operators() {
+    let a = Bitboard::EMPTY.with(square("e4")).with(square("d5"));
+    let b = square("d5").bitboard() | square("h1").bitboard();
+
+    assert_eq!((a & b).count(), 1);
+    assert!((a & b).contains(square("d5")));
+    assert_eq!((a | b).count(), 3);
+    assert_eq!((a ^ b).count(), 2);
+    assert!(!(!a).contains(square("e4")));
+    assert_eq!((!a).count(), 62);
+    assert!(a.without(square("e4")).without(square("d5")).is_empty());
+
+    let mut c = a;
+    c &= b;
+    c |= square("a8").bitboard();
+    c ^= square("d5").bitboard();
+    assert_eq!(c, square("a8").bitboard());
+}
+
+#[test]
+fn test_bitboard_squares() {
+    let bitboard = Bitboard::EMPTY
+        .with(square("h1"))
+        .with(square("a8"))
+        .with(square("e4"));
+    let squares: Vec<Square> = bitboard.into_iter().collect();
+    assert_eq!(squares, vec![square("a8"), square("e4"), square("h1")]);
+    assert_eq!(bitboard.lsb(), Some(square("a8")));
+    assert_eq!(Bitboard::EMPTY.lsb(), None);
+    assert_eq!(Bitboard::FULL.squares().count(), 64);
+}
+
+#[test]
+fn test_piece_bitboard_index() {
+    for index in 0..12 {
+        assert_eq!(Piece::from_bitboard_index(index).bitboard_index(), index);
+    }
+    assert_eq!(
+        Piece::new(Color::White, PieceType::Pawn).bitboard_index(),
+        0
+    );
+    assert_eq!(
+        Piece::new(Color::Black, PieceType::King).bitboard_index(),
+        11
+    );
+    assert_eq!(
+        Piece::from_char('N'),
+        Ok(Piece::new(Color::White, PieceType::Knight))
+    );
+    assert_eq!(Piece::new(Color::Black, PieceType::Queen).to_char(), 'q');
+}
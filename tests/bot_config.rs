@@ -0,0 +1,196 @@
+use chess_engine::bot_config::{BotConfig, RatedPolicy};
+use chess_engine::bot_config_error::BotConfigError;
+use chess_engine::evaluation_parameters::EvaluationParameters;
+use chess_engine::lichess_structs::InnerChallenge;
+use chess_engine::log_level::LogLevel;
+
+// A challenge event as Lichess sends it, with the bits the config looks at filled in.
+fn challenge(challenger: &str, variant: &str, rated: bool, time_control: &str) -> InnerChallenge {
+    let json = format!(
+        r#"{{
+            "id": "abcd1234",
+            "url": "https://lichess.org/abcd1234",
+            "status": "created",
+            "challenger": {{"id": "{id}", "name": "{challenger}", "title": null, "rating": 1500, "provisional": false}},
+            "destUser": {{"id": "mybot", "name": "MyBot", "title": "BOT", "rating": 1500, "provisional": false}},
+            "variant": {{"key": "{variant}", "name": "{variant}"}},
+            "rated": {rated},
+            "timeControl": {time_control}
+        }}"#,
+        id = challenger.to_lowercase()
+    );
+    return serde_json::from_str(&json).unwrap();
+}
+
+fn blitz() -> &'static str {
+    return r#"{"type": "clock", "limit": 300, "increment": 3}"#;
+}
+
+#[test]
+fn test_defaults() {
+    let config = BotConfig::from_json(r#"{"username": "MyBot"}"#).unwrap();
+    assert_eq!(config.username, "MyBot");
+    assert!(config.challengers.allow.is_empty());
+    assert!(config.variants.is_empty());
+    assert_eq!(config.rated, RatedPolicy::Any);
+    assert_eq!(config.max_concurrent_games, 1);
+    assert_eq!(config.engine.hash_mb, 16);
+    assert_eq!(config.log_level, LogLevel::Info);
+    assert!(config.warnings().is_empty());
+
+    assert!(config.is_bot("mybot"));
+    assert!(!config.is_bot("someone_else"));
+}
+
+#[test]
+fn test_example_config() {
+    let config = BotConfig::load_from_file("lichess_bot.example.json").unwrap();
+    assert_eq!(config.variants, ["standard", "chess960"]);
+    assert_eq!(config.max_concurrent_games, 2);
+    assert_eq!(config.engine.hash_mb, 64);
+}
+
+#[test]
+fn test_invalid_configs() {
+    let error = |json: &str| -> BotConfigError {
+        return BotConfig::from_json(json).unwrap_err();
+    };
+
+    assert!(matches!(
+        BotConfig::load_from_file("no_such_config.json"),
+        Err(BotConfigError::Io { .. })
+    ));
+    assert!(matches!(error("{"), BotConfigError::Parse(_)));
+    assert!(matches!(
+        error(r#"{"username": "MyBot", "hash_mb": 64}"#),
+        BotConfigError::Parse(_)
+    ));
+    assert!(matches!(
+        error(r#"{"username": "MyBot", "rated": "sometimes"}"#),
+        BotConfigError::Parse(_)
+    ));
+    assert!(matches!(error("{}"), BotConfigError::MissingUsername));
+
+    let both =
+        error(r#"{"username": "MyBot", "challengers": {"allow": ["Alice"], "deny": ["alice"]}}"#);
+    assert!(
+        matches!(both, BotConfigError::ChallengerAllowedAndDenied(ref name) if name == "Alice")
+    );
+
+    let variant = error(r#"{"username": "MyBot", "variants": ["standard", "horde"]}"#);
+    assert!(matches!(variant, BotConfigError::UnsupportedVariant(ref key) if key == "horde"));
+
+    let range = error(
+        r#"{"username": "MyBot", "time_controls": {"min_increment_seconds": 10, "max_increment_seconds": 5}}"#,
+    );
+    assert_eq!(
+        range.to_string(),
+        "`time_controls.min_increment_seconds` is more than `time_controls.max_increment_seconds`."
+    );
+
+    let games = error(r#"{"username": "MyBot", "max_concurrent_games": 0}"#);
+    assert_eq!(
+        games.to_string(),
+        "`max_concurrent_games` has to be at least 1."
+    );
+    assert!(matches!(
+        error(r#"{"username": "MyBot", "engine": {"hash_mb": 0}}"#),
+        BotConfigError::OutOfRange { .. }
+    ));
+    assert!(matches!(
+        error(r#"{"username": "MyBot", "engine": {"book_path": "no_such_book.bin"}}"#),
+        BotConfigError::MissingBook(_)
+    ));
+    assert!(matches!(
+        error(
+            r#"{"username": "MyBot", "engine": {"evaluation_parameters_path": "lichess_bot.example.json"}}"#
+        ),
+        BotConfigError::InvalidEvaluationParameters(_)
+    ));
+}
+
+#[test]
+fn test_evaluation_parameters_path() {
+    let path = std::env::temp_dir().join("bot_config_evaluation_parameters.json");
+    let path = path.to_str().unwrap();
+    EvaluationParameters::default().save_to_file(path).unwrap();
+
+    let json =
+        format!(r#"{{"username": "MyBot", "engine": {{"evaluation_parameters_path": "{path}"}}}}"#);
+    let config = BotConfig::from_json(&json).unwrap();
+    assert_eq!(
+        config.engine.evaluation_parameters_path.as_deref(),
+        Some(path)
+    );
+    assert!(config.warnings().is_empty());
+}
+
+#[test]
+fn test_warnings() {
+    let config = BotConfig::from_json(
+        r#"{"username": "MyBot", "engine": {"threads": 4, "book_path": "lichess_bot.example.json"}}"#,
+    )
+    .unwrap();
+    assert_eq!(config.warnings().len(), 2);
+}
+
+#[test]
+fn test_check_challenge() {
+    let config = BotConfig::from_json(
+        r#"{
+            "username": "MyBot",
+            "challengers": {"deny": ["Mallory"]},
+            "variants": ["standard", "chess960"],
+            "rated": "casual",
+            "time_controls": {"min_initial_seconds": 60, "max_increment_seconds": 10}
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.check_challenge(&challenge("Alice", "standard", false, blitz())),
+        Ok(())
+    );
+    assert_eq!(
+        config.check_challenge(&challenge("Bob", "chess960", false, blitz())),
+        Ok(())
+    );
+
+    // Names aren't case sensitive.
+    assert!(config
+        .check_challenge(&challenge("MALLORY", "standard", false, blitz()))
+        .is_err());
+
+    assert!(config
+        .check_challenge(&challenge("Alice", "atomic", false, blitz()))
+        .is_err());
+    assert!(config
+        .check_challenge(&challenge("Alice", "horde", false, blitz()))
+        .is_err());
+    assert!(config
+        .check_challenge(&challenge("Alice", "standard", true, blitz()))
+        .is_err());
+
+    let bullet = r#"{"type": "clock", "limit": 30, "increment": 0}"#;
+    let big_increment = r#"{"type": "clock", "limit": 300, "increment": 30}"#;
+    let correspondence = r#"{"type": "correspondence", "daysPerTurn": 3}"#;
+    let unlimited = r#"{"type": "unlimited"}"#;
+    for time_control in [bullet, big_increment, correspondence, unlimited] {
+        let result = config.check_challenge(&challenge("Alice", "standard", false, time_control));
+        assert!(result.is_err(), "{time_control}");
+    }
+
+    // With an allow list, nobody else gets in.
+    let allow_list = BotConfig::from_json(
+        r#"{"username": "MyBot", "challengers": {"allow": ["Alice"]}, "time_controls": {"allow_unlimited": true}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        allow_list.check_challenge(&challenge("alice", "atomic", true, unlimited)),
+        Ok(())
+    );
+    assert_eq!(
+        allow_list.check_challenge(&challenge("Bob", "standard", true, blitz())),
+        Err("Bob isn't on the allow list.".to_string())
+    );
+}
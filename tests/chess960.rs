@@ -0,0 +1,156 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::square::Square;
+use chess_engine::variant::Variant;
+
+fn perft(game: &mut ChessGame, fen: &str, depth: u32) -> u64 {
+    assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+    return game.perft(depth);
+}
+
+#[test]
+fn test_standard_perft() {
+    let mut game = ChessGame::new();
+
+    assert_eq!(perft(&mut game, INITIAL_GAME_STATE_FEN, 3), 8_902);
+    assert_eq!(
+        perft(
+            &mut game,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            2
+        ),
+        2_039
+    );
+    assert_eq!(
+        perft(
+            &mut game,
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            3
+        ),
+        9_467
+    );
+}
+
+#[test]
+fn test_chess960_perft() {
+    let mut game = ChessGame::new();
+    game.variant = Variant::Chess960;
+
+    let positions: Vec<(&str, [u64; 3])> = vec![
+        (
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            [21, 528, 12_189],
+        ),
+        (
+            "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+            [21, 807, 18_002],
+        ),
+        (
+            "1rqbkrbn/1ppppp1p/1n6/p1N3p1/8/2P4P/PP1PPPP1/1RQBKRBN w FBfb - 0 9",
+            [29, 502, 14_569],
+        ),
+        (
+            "rbbqn1kr/pp2p1pp/6n1/2pp1p2/2P4P/P7/BP1PPPP1/R1BQNNKR w HAha - 0 9",
+            [27, 916, 25_798],
+        ),
+    ];
+
+    for (fen, expected) in positions.iter() {
+        for (depth, nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                perft(&mut game, fen, depth as u32 + 1),
+                *nodes,
+                "Perft {} of {fen}.",
+                depth + 1
+            );
+        }
+    }
+}
+
+#[test]
+fn test_castling_fen_round_trip() {
+    let mut game = ChessGame::new();
+    game.variant = Variant::Chess960;
+
+    // Shredder-FEN comes back out as X-FEN, outermost rooks get the plain letters.
+    let _ = game.import_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9");
+    assert_eq!(
+        game.export_fen(),
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq -"
+    );
+
+    // An inner rook needs its file spelled out.
+    let _ = game.import_fen("rkr5/8/8/8/8/8/8/RKR4R w CAca - 0 1");
+    assert_eq!(game.export_fen(), "rkr5/8/8/8/8/8/8/RKR4R w CQkq -");
+    let rook_squares = ["c1", "a1", "c8", "a8"].map(|s| s.parse::<Square>().unwrap());
+    assert_eq!(game.castling_rook_squares(), rook_squares);
+
+    // Reading our own output back gives the same rights, and the same hash.
+    let hash = game.zobrist_hash;
+    let _ = game.import_fen(&game.export_fen());
+    assert_eq!(game.castling_rook_squares(), rook_squares);
+    assert_eq!(game.zobrist_hash, hash);
+}
+
+#[test]
+fn test_castling_notation() {
+    let mut game = ChessGame::new();
+    let _ = game.import_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    game.set_legal_moves(None);
+
+    // Standard chess names the king's destination.
+    assert!(game.uci_to_legal_move("e1g1").is_ok());
+    assert!(game.uci_to_legal_move("e1h1").is_err());
+
+    // Chess960 has the king take its own rook.
+    game.variant = Variant::Chess960;
+    let short = game
+        .uci_to_legal_move("e1h1")
+        .expect("Short castling should be legal.");
+    let long = game
+        .uci_to_legal_move("e1a1")
+        .expect("Long castling should be legal.");
+    assert!(short.castle_side.is_some() && long.castle_side.is_some());
+    assert!(game.uci_to_legal_move("e1g1").is_err());
+
+    // King already on g1, castling just moves the rook.
+    let _ = game.import_fen("6kr/8/8/8/8/8/8/6KR w Kk - 0 1");
+    game.set_legal_moves(None);
+    let castle = game
+        .uci_to_legal_move("g1h1")
+        .expect("Castling should be legal.");
+    let hash = game.zobrist_hash;
+    game.make_move(&castle, true);
+    assert_eq!(game.export_fen(), "6kr/8/8/8/8/8/8/5RK1 b k -");
+    game.unmake_move(&castle);
+    assert_eq!(game.export_fen(), "6kr/8/8/8/8/8/8/6KR w Kk -");
+    assert_eq!(game.zobrist_hash, hash);
+}
+
+#[test]
+fn test_castling_legality() {
+    let mut game = ChessGame::new();
+
+    // Can't castle out of check.
+    let _ = game.import_fen("4r1k1/8/8/8/8/8/8/R3K2R w KQ - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.legal_moves.iter().all(|m| m.castle_side.is_none()));
+
+    // Or through it. Long castling is still fine, b1 may be attacked.
+    let _ = game.import_fen("1r3rk1/8/8/8/8/8/8/R3K2R w KQ - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("e1g1").is_err());
+    assert!(game.uci_to_legal_move("e1c1").is_ok());
+
+    // In Chess960 a piece between the king and its destination blocks, even past the rook.
+    game.variant = Variant::Chess960;
+    let _ = game.import_fen("8/8/8/8/8/8/8/1RK2B1k w B - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("c1b1").is_ok());
+    let _ = game.import_fen("8/8/8/8/8/8/8/NRK4k w B - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("c1b1").is_ok());
+    let _ = game.import_fen("8/8/8/8/8/8/8/1RKB3k w B - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("c1b1").is_err());
+}
@@ -0,0 +1,278 @@
+use chess_engine::bitboard::Bitboard;
+use chess_engine::chess_game::ChessGame;
+use chess_engine::color::Color;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::fen_error::FenError;
+use chess_engine::illegal_move_error::IllegalMoveError;
+use chess_engine::piece_type::PieceType;
+use chess_engine::r#move::Move;
+use chess_engine::runtime_calculated_constants::Constants;
+use chess_engine::search_limits::SearchLimits;
+use chess_engine::square::Square;
+use chess_engine::variant::Variant;
+use std::thread;
+
+fn new_game_at(fen: &str) -> ChessGame {
+    let mut game = ChessGame::new();
+    assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+    return game;
+}
+
+#[test]
+fn test_games_share_constants() {
+    let first = ChessGame::new();
+    let second = ChessGame::default();
+    assert!(std::ptr::eq(
+        first.bitboard_constants,
+        second.bitboard_constants
+    ));
+    assert!(std::ptr::eq(first.bitboard_constants, Constants::get()));
+}
+
+#[test]
+fn test_game_can_move_across_threads() {
+    let game = new_game_at(INITIAL_GAME_STATE_FEN);
+
+    let handle = thread::spawn(move || {
+        let mut game = game;
+        return game.perft(3);
+    });
+    assert_eq!(handle.join().unwrap(), 8_902);
+
+    // Several games at once, each on its own thread.
+    let handles: Vec<thread::JoinHandle<u64>> = (0..4)
+        .map(|_| {
+            let mut game = new_game_at(INITIAL_GAME_STATE_FEN);
+            return thread::spawn(move || game.perft(2));
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 400);
+    }
+}
+
+#[test]
+fn test_fen_errors() {
+    let mut game = ChessGame::new();
+    let cases: Vec<(&str, FenError)> = vec![
+        ("", FenError::WrongColumnCount(0)),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+            FenError::MissingSideToMove,
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+            FenError::InvalidSideToMove("x".to_string()),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNZ w KQkq - 0 1",
+            FenError::InvalidPiece('Z'),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN w KQkq - 0 1",
+            FenError::WrongColumnCount(7),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNRR w KQkq - 0 1",
+            FenError::WrongColumnCount(9),
+        ),
+        ("8/8/8/8/8/8/8 w - - 0 1", FenError::WrongRowCount(7)),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[K] w KQkq - 0 1",
+            FenError::KingInPocket,
+        ),
+        (
+            "P3k3/8/8/8/8/8/8/4K3 w - - 0 1",
+            FenError::PawnOnBackRank("a8".parse().unwrap()),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K2p b - - 0 1",
+            FenError::PawnOnBackRank("h1".parse().unwrap()),
+        ),
+        (
+            "4k3/8/8/8/8/8/8/3KK3 w - - 0 1",
+            FenError::WrongKingCount {
+                side: Color::White,
+                count: 2,
+            },
+        ),
+        (
+            "8/8/8/8/8/8/8/4K3 w - - 0 1",
+            FenError::WrongKingCount {
+                side: Color::Black,
+                count: 0,
+            },
+        ),
+    ];
+
+    for (fen, expected) in cases {
+        assert_eq!(
+            game.import_fen(fen),
+            Err(expected),
+            "Wrong error for {fen}."
+        );
+    }
+
+    // Antichess kings aren't royal, and atomic games end with a king gone.
+    game.variant = Variant::Antichess;
+    assert!(game.import_fen("4k3/8/8/8/8/8/8/3KK3 w - - 0 1").is_ok());
+    game.variant = Variant::Atomic;
+    assert!(game.import_fen("8/8/8/8/8/8/8/4K3 b - - 0 1").is_ok());
+    assert!(game.import_fen("4k3/8/8/8/8/8/8/3KK3 w - - 0 1").is_err());
+}
+
+#[test]
+fn test_try_make_move() {
+    let mut game = new_game_at(INITIAL_GAME_STATE_FEN);
+
+    let e4 = Move::str_to_move("e2e4").unwrap();
+    let played = game.try_make_move(&e4).expect("e2e4 is legal.");
+    assert_eq!(played.from_piece_type, Some(PieceType::Pawn));
+    assert!(!game.white_to_move);
+
+    // Black to move, so White's moves are not legal. Nothing changes on failure.
+    let fen = game.export_fen();
+    let d4 = Move::str_to_move("d2d4").unwrap();
+    assert_eq!(
+        game.try_make_move(&d4),
+        Err(IllegalMoveError::NotLegal("d2d4".to_string()))
+    );
+    assert_eq!(game.export_fen(), fen);
+
+    game.unmake_move(&played);
+    assert_eq!(
+        game.export_fen(),
+        new_game_at(INITIAL_GAME_STATE_FEN).export_fen()
+    );
+}
+
+#[test]
+fn test_try_get_bot_move() {
+    // Fool's mate, White has nothing left to play.
+    let mut game = new_game_at("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+    assert_eq!(game.try_get_bot_move(), Err(IllegalMoveError::NoLegalMoves));
+    assert_eq!(
+        game.try_make_move(&Move::str_to_move("e2e4").unwrap()),
+        Err(IllegalMoveError::NoLegalMoves)
+    );
+}
+
+#[test]
+fn test_search_limits() {
+    // Depth 1 is 21 nodes from the start. The node budget runs out partway through depth 2, which is thrown away.
+    let mut game = new_game_at(INITIAL_GAME_STATE_FEN);
+    game.set_legal_moves(None);
+    let (_, best_move) = game.search_with_limits(&SearchLimits::nodes(100));
+    assert!(best_move.is_some());
+    assert!(
+        game.debug_minimax_calls <= 101,
+        "{}",
+        game.debug_minimax_calls
+    );
+    assert_eq!(
+        game.export_fen(),
+        new_game_at(INITIAL_GAME_STATE_FEN).export_fen()
+    );
+
+    // Stops partway through a depth too, rather than finishing it.
+    let mut game =
+        new_game_at("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    game.set_legal_moves(None);
+    let start = std::time::Instant::now();
+    let (_, best_move) = game.search_with_limits(&SearchLimits::time_ms(50));
+    assert!(best_move.is_some());
+    assert!(start.elapsed().as_millis() < 1_000);
+
+    // No limits at all still returns.
+    let mut game = new_game_at("7k/8/8/8/8/8/8/K7 w - - 0 1");
+    game.set_legal_moves(None);
+    let (_, best_move) = game.search_with_limits(&SearchLimits::default());
+    assert!(best_move.is_some());
+}
+
+#[test]
+fn test_attacks() {
+    let game = new_game_at(INITIAL_GAME_STATE_FEN);
+    let square = |s: &str| -> Square { return s.parse().unwrap() };
+    let bitboard = |squares: &[&str]| -> Bitboard {
+        return squares
+            .iter()
+            .fold(Bitboard::default(), |b, s| b.with(square(s)));
+    };
+
+    let occupied = game.occupied();
+    assert_eq!(
+        game.get_rook_attacks(square("a1"), occupied),
+        bitboard(&["a2", "b1"])
+    );
+    assert_eq!(
+        game.get_bishop_attacks(square("c1"), occupied),
+        bitboard(&["b2", "d2"])
+    );
+    assert_eq!(
+        game.get_queen_attacks(square("d4"), Bitboard::default())
+            .count(),
+        27
+    );
+    assert_eq!(
+        game.attackers_of(square("f3"), &Color::White, occupied),
+        bitboard(&["e2", "g2", "g1"])
+    );
+    assert!(game
+        .attackers_of(square("e4"), &Color::Black, occupied)
+        .is_empty());
+}
+
+#[test]
+fn test_insufficient_material() {
+    for fen in [
+        "8/8/4k3/8/8/3K4/8/8 w - - 0 1",
+        "8/8/4k3/8/8/3K4/8/6N1 w - - 0 1",
+        "8/8/4k3/8/2b5/3K4/8/8 w - - 0 1",
+    ] {
+        assert!(new_game_at(fen).is_insufficient_material(), "{fen}");
+    }
+
+    for fen in [
+        INITIAL_GAME_STATE_FEN,
+        "8/8/4k3/8/8/3K4/4P3/8 w - - 0 1",
+        "8/8/4k3/8/2b5/3K4/8/6N1 w - - 0 1",
+        "8/8/4k3/8/8/3K4/8/6NN w - - 0 1",
+    ] {
+        assert!(!new_game_at(fen).is_insufficient_material(), "{fen}");
+    }
+
+    // Pieces come back out of the pocket in crazyhouse.
+    let mut game = ChessGame::new();
+    game.variant = chess_engine::variant::Variant::Crazyhouse;
+    assert!(game.import_fen("8/8/4k3/8/8/3K4/8/8[Qq] w - - 0 1").is_ok());
+    assert!(!game.is_insufficient_material());
+}
+
+#[test]
+fn test_compute_zobrist() {
+    let mut game = new_game_at(INITIAL_GAME_STATE_FEN);
+    assert_eq!(game.compute_zobrist(), game.zobrist_hash);
+    let start_hash = game.zobrist_hash;
+
+    // Knights out and back is the starting position again.
+    game.set_legal_moves(None);
+    for uci in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+        let this_move = game.uci_to_legal_move(uci).expect("Legal move.");
+        game.make_move(&this_move, true);
+        assert_eq!(game.compute_zobrist(), game.zobrist_hash, "After {uci}.");
+    }
+    assert_eq!(game.zobrist_hash, start_hash);
+
+    // Castling rights, en passant and side to move all count.
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Kkq - 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1",
+    ] {
+        let game = new_game_at(fen);
+        assert_eq!(game.compute_zobrist(), game.zobrist_hash, "{fen}");
+        assert_ne!(game.zobrist_hash, start_hash, "{fen}");
+    }
+}
@@ -0,0 +1,135 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::color::Color;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::piece_type::PieceType;
+use chess_engine::r#move::Move;
+use chess_engine::variant::Variant;
+
+fn crazyhouse_game() -> ChessGame {
+    let mut game = ChessGame::new();
+    game.variant = Variant::Crazyhouse;
+    return game;
+}
+
+#[test]
+fn test_crazyhouse_perft() {
+    let mut game = crazyhouse_game();
+
+    let positions: Vec<(&str, [u64; 3])> = vec![
+        (INITIAL_GAME_STATE_FEN, [20, 400, 8_902]),
+        (
+            "4b~3/7p/3R4/1P1P4/6R1/8/8/K4k2[PP] b - - 2 18",
+            [11, 757, 11_418],
+        ),
+        (
+            "5R~2/3r4/1K1p4/R7/2PP1P2/6p1/5k2/8[] b - - 0 14",
+            [17, 477, 8_196],
+        ),
+        (
+            "8/R7/2PP4/K5R1/5p1k/2p5/4P3/8[p] b - - 3 11",
+            [43, 1_251, 11_877],
+        ),
+    ];
+
+    for (fen, expected) in positions.iter() {
+        assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+        for (depth, nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                game.perft(depth as u32 + 1),
+                *nodes,
+                "Perft {} of {fen}.",
+                depth + 1
+            );
+        }
+    }
+}
+
+#[test]
+fn test_pocket_fen() {
+    let mut game = crazyhouse_game();
+
+    let fen = "r1bqk2r/pppp1ppp/2n5/4p3/1b2P3/2N5/PPPP1PPP/R1BQKB~1R[NPn] w KQkq - 0 1";
+    assert!(game.import_fen(fen).is_ok());
+    assert_eq!(game.pocket(Color::White, PieceType::Knight), 1);
+    assert_eq!(game.pocket(Color::White, PieceType::Pawn), 1);
+    assert_eq!(game.pocket(Color::Black, PieceType::Knight), 1);
+    assert_eq!(
+        game.export_fen(),
+        "r1bqk2r/pppp1ppp/2n5/4p3/1b2P3/2N5/PPPP1PPP/R1BQKB~1R[NPn] w KQkq -"
+    );
+
+    // Same position with the pocket as a ninth row.
+    let hash = game.zobrist_hash;
+    assert!(game
+        .import_fen("r1bqk2r/pppp1ppp/2n5/4p3/1b2P3/2N5/PPPP1PPP/R1BQKB~1R/nPN w KQkq - 0 1")
+        .is_ok());
+    assert_eq!(game.zobrist_hash, hash);
+
+    // Kings can't be in hand.
+    assert!(game.import_fen("4k3/8/8/8/8/8/8/4K3[K] w - - 0 1").is_err());
+}
+
+#[test]
+fn test_drop_notation() {
+    let mut game = crazyhouse_game();
+    let _ = game.import_fen("4k3/8/8/8/8/8/8/4K3[NPp] w - - 0 1");
+    game.set_legal_moves(None);
+
+    let drop = Move::str_to_move("N@f3").expect("Drop should parse.");
+    assert!(drop.is_drop);
+    assert_eq!(drop.from_piece_type, Some(PieceType::Knight));
+    assert_eq!(drop.move_to_str(), "N@f3");
+
+    assert!(game.uci_to_legal_move("N@f3").is_ok());
+    assert!(game.uci_to_legal_move("P@e4").is_ok());
+
+    // No pawns on the back ranks, no dropping what we don't have, no dropping onto pieces.
+    assert!(game.uci_to_legal_move("P@e8").is_err());
+    assert!(game.uci_to_legal_move("P@a1").is_err());
+    assert!(game.uci_to_legal_move("Q@d4").is_err());
+    assert!(game.uci_to_legal_move("N@e1").is_err());
+}
+
+#[test]
+fn test_captured_promoted_piece_returns_as_pawn() {
+    let mut game = crazyhouse_game();
+    let fen = "4k3/8/8/8/8/8/3q~4/4K3[] w - - 0 1";
+    let _ = game.import_fen(fen);
+    game.set_legal_moves(None);
+
+    let capture = game
+        .uci_to_legal_move("e1d2")
+        .expect("King can take the queen.");
+    game.make_move(&capture, true);
+    assert_eq!(game.pocket(Color::White, PieceType::Pawn), 1);
+    assert_eq!(game.pocket(Color::White, PieceType::Queen), 0);
+    assert_eq!(game.export_fen(), "4k3/8/8/8/8/8/3K4/8[P] b - -");
+
+    // Hash matches a fresh import of the same position.
+    let mut fresh = crazyhouse_game();
+    let _ = fresh.import_fen(&game.export_fen());
+    assert_eq!(game.zobrist_hash, fresh.zobrist_hash);
+
+    game.unmake_move(&capture);
+    assert_eq!(game.export_fen(), "4k3/8/8/8/8/8/3q~4/4K3[] w - -");
+}
+
+#[test]
+fn test_promotion_is_tracked() {
+    let mut game = crazyhouse_game();
+    let _ = game.import_fen("4k3/1P6/8/8/8/8/8/4K3[] w - - 0 1");
+    game.set_legal_moves(None);
+
+    let promotion = game.uci_to_legal_move("b7b8q").unwrap();
+    game.make_move(&promotion, true);
+    assert_eq!(game.export_fen(), "1Q~2k3/8/8/8/8/8/8/4K3[] b - -");
+
+    // The promoted queen keeps its marker as it moves.
+    let _ = game.import_fen("4k3/8/8/8/8/8/8/Q~3K3[] w - - 0 1");
+    game.set_legal_moves(None);
+    let queen_move = game.uci_to_legal_move("a1a7").unwrap();
+    game.make_move(&queen_move, true);
+    assert_eq!(game.export_fen(), "4k3/Q~7/8/8/8/8/8/4K3[] b - -");
+    game.unmake_move(&queen_move);
+    assert_eq!(game.export_fen(), "4k3/8/8/8/8/8/8/Q~3K3[] w - -");
+}
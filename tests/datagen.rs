@@ -0,0 +1,104 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::datagen::*;
+use chess_engine::search_limits::SearchLimits;
+use chess_engine::texel::parse_labelled_position;
+
+fn test_positions() -> Vec<TrainingPosition> {
+    return vec![
+        TrainingPosition {
+            fen: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -".to_string(),
+            score: 15,
+            result: 0.5,
+        },
+        TrainingPosition {
+            fen: "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b Kq -".to_string(),
+            score: -230,
+            result: 1.0,
+        },
+        TrainingPosition {
+            fen: "rnbqkbnr/p1pp1ppp/8/1p2pP2/8/8/PPPPP1PP/RNBQKBNR w KQkq e6".to_string(),
+            score: 40,
+            result: 0.0,
+        },
+        TrainingPosition {
+            fen: "8/8/4k3/8/8/3K4/8/8 b - -".to_string(),
+            score: 0,
+            result: 0.5,
+        },
+    ];
+}
+
+#[test]
+fn test_packed_round_trip() {
+    for position in test_positions() {
+        let bytes = position.to_packed().expect("Position should pack.");
+        assert_eq!(bytes.len(), PACKED_POSITION_SIZE);
+        assert_eq!(TrainingPosition::from_packed(&bytes), Ok(position));
+    }
+}
+
+#[test]
+fn test_packed_score_is_clamped() {
+    let position = TrainingPosition {
+        fen: "8/8/4k3/8/8/3K4/8/Q7 w - -".to_string(),
+        score: 100_000,
+        result: 1.0,
+    };
+    let unpacked = TrainingPosition::from_packed(&position.to_packed().unwrap()).unwrap();
+    assert_eq!(unpacked.score, PACKED_SCORE_LIMIT);
+}
+
+#[test]
+fn test_packed_rejects_bad_records() {
+    let bytes = test_positions()[0].to_packed().unwrap();
+    assert!(TrainingPosition::from_packed(&bytes[..PACKED_POSITION_SIZE - 1]).is_err());
+
+    let mut bad_result = bytes;
+    bad_result[28] = 7;
+    assert!(TrainingPosition::from_packed(&bad_result).is_err());
+}
+
+#[test]
+fn test_text_round_trip() {
+    for position in test_positions() {
+        let line = position.to_text_line();
+        assert_eq!(
+            TrainingPosition::from_text_line(&line),
+            Ok(position.clone())
+        );
+
+        // The texel tuner should be able to read what we write.
+        let labelled = parse_labelled_position(&line).expect("Texel should parse datagen lines.");
+        assert_eq!(labelled.fen, position.fen);
+        assert_eq!(labelled.result, position.result);
+    }
+}
+
+#[test]
+fn test_self_play() {
+    let options = DatagenOptions {
+        limits: SearchLimits::depth(1),
+        random_plies: 4,
+        max_plies: 40,
+        seed: 7,
+    };
+
+    let mut self_play = SelfPlay::new(ChessGame::new(), options.clone());
+    let positions = self_play.play_game();
+    assert!(!positions.is_empty());
+
+    let mut game = ChessGame::new();
+    for position in positions.iter() {
+        assert!(game.import_fen(&position.fen).is_ok());
+        assert!([0.0, 0.5, 1.0].contains(&position.result));
+        assert_eq!(position.result, positions[0].result);
+
+        // Only quiet positions are kept, so no one is in check.
+        assert!(!game.is_king_attacked(&chess_engine::color::Color::White));
+        assert!(!game.is_king_attacked(&chess_engine::color::Color::Black));
+    }
+
+    // Same seed, same games.
+    let mut replay = SelfPlay::new(ChessGame::new(), options);
+    assert_eq!(replay.play_game(), positions);
+}
@@ -0,0 +1,124 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::endgame::KNOWN_WIN;
+
+fn evaluate(game: &mut ChessGame, fen: &str) -> i64 {
+    assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+    return game.evaluate_board();
+}
+
+#[test]
+fn test_drawn_material() {
+    let mut game = ChessGame::new();
+    let fens: Vec<&str> = vec![
+        "8/8/4k3/8/8/3K4/8/8 w - - 0 1",
+        "8/8/4k3/8/8/3K4/8/5N2 w - - 0 1",
+        "8/8/4k3/8/8/3K4/8/5B2 w - - 0 1",
+        "8/8/4k3/8/8/3K4/8/4NN2 w - - 0 1",
+        "8/8/4k3/2n5/8/3K4/8/4B3 b - - 0 1",
+        "8/2nn4/4k3/8/8/3K4/8/8 b - - 0 1",
+    ];
+
+    for fen in fens.iter() {
+        assert_eq!(evaluate(&mut game, fen), 0, "Expected a draw for {fen}.");
+    }
+}
+
+#[test]
+fn test_wrong_bishop_rook_pawn() {
+    let mut game = ChessGame::new();
+
+    // Dark squared bishop can't help push the a-pawn past the king on b8.
+    assert_eq!(evaluate(&mut game, "1k6/8/8/8/8/8/P7/K1B5 w - - 0 1"), 0);
+    assert_eq!(evaluate(&mut game, "k1b5/p7/8/8/8/8/8/1K6 b - - 0 1"), 0);
+
+    // Right bishop, or the king is too far away. Not a draw.
+    assert!(evaluate(&mut game, "1k6/8/8/8/8/8/P7/KB6 w - - 0 1") > 0);
+    assert!(evaluate(&mut game, "8/8/8/8/8/8/P7/K1B4k w - - 0 1") > 0);
+}
+
+#[test]
+fn test_kqk_and_krk_push_king_to_edge() {
+    let mut game = ChessGame::new();
+
+    let center = evaluate(&mut game, "8/8/8/3k4/8/2K5/8/7Q w - - 0 1");
+    let edge = evaluate(&mut game, "3k4/8/8/8/8/2K5/8/7Q w - - 0 1");
+    assert!(center > KNOWN_WIN);
+    assert!(edge > center);
+
+    let center = evaluate(&mut game, "8/8/8/3k4/8/2K5/8/7R w - - 0 1");
+    let edge = evaluate(&mut game, "3k4/8/8/8/8/2K5/8/7R w - - 0 1");
+    assert!(center > KNOWN_WIN);
+    assert!(edge > center);
+
+    // Same thing for black, mirrored.
+    assert_eq!(
+        evaluate(&mut game, "7r/8/2k5/8/8/8/8/3K4 b - - 0 1"),
+        -evaluate(&mut game, "3k4/8/8/8/8/2K5/8/7R w - - 0 1")
+    );
+}
+
+#[test]
+fn test_kbnk_prefers_bishop_corner() {
+    let mut game = ChessGame::new();
+
+    // Light squared bishop, so a8 and h1 are the mating corners.
+    let right_corner = evaluate(&mut game, "k7/8/2K5/8/8/8/8/1BN5 w - - 0 1");
+    let wrong_corner = evaluate(&mut game, "7k/8/5K2/8/8/8/8/1BN5 w - - 0 1");
+    assert!(wrong_corner > KNOWN_WIN);
+    assert!(right_corner > wrong_corner);
+}
+
+#[test]
+fn test_kpk_rules() {
+    let mut game = ChessGame::new();
+
+    // Black king can't catch the pawn.
+    assert!(evaluate(&mut game, "8/8/8/8/8/8/P6k/K7 w - - 0 1") > KNOWN_WIN);
+    assert!(evaluate(&mut game, "k7/7p/8/8/8/8/8/K7 b - - 0 1") < -KNOWN_WIN);
+
+    // Rook pawn, and the king is already in the corner.
+    assert_eq!(evaluate(&mut game, "k7/8/8/8/8/8/P7/7K w - - 0 1"), 0);
+
+    // King in front of the pawn.
+    assert_eq!(evaluate(&mut game, "8/8/4k3/8/4P3/4K3/8/8 w - - 0 1"), 0);
+}
+
+#[test]
+fn test_mop_up() {
+    let mut game = ChessGame::new();
+
+    // Queen and rook against a bare king isn't a specialised ending, mop-up does the work.
+    let mop_up_bonus = |game: &mut ChessGame, fen: &str| -> i64 {
+        let _ = game.import_fen(fen);
+        let trace = game.evaluate_trace();
+        let mop_up = trace.get_term("Mop-up").expect("Mop-up term should exist.");
+        assert_eq!(mop_up.white.middlegame, 0);
+        return mop_up.white.endgame;
+    };
+    let center = mop_up_bonus(&mut game, "8/8/8/3k4/8/2K5/8/6RQ w - - 0 1");
+    let corner = mop_up_bonus(&mut game, "k7/8/8/8/8/2K5/8/6RQ w - - 0 1");
+    let kings_close = mop_up_bonus(&mut game, "k7/2K5/8/8/8/8/8/6RQ w - - 0 1");
+    assert!(corner > center);
+    assert!(kings_close > corner);
+
+    let trace = game.evaluate_trace();
+    let term_sum: i64 = trace.terms.iter().map(|t| t.net(trace.phase)).sum();
+    assert_eq!(term_sum, trace.total);
+
+    // Equal material, no mop-up.
+    let _ = game.import_fen("8/8/4k3/8/8/3K4/3PR3/4r3 w - - 0 1");
+    assert!(game.evaluate_trace().get_term("Mop-up").is_none());
+}
+
+#[test]
+fn test_endgame_trace_adds_up() {
+    let mut game = ChessGame::new();
+    let _ = game.import_fen("3k4/8/8/8/8/2K5/8/7Q w - - 0 1");
+
+    let trace = game.evaluate_trace();
+    assert_eq!(trace.total, game.evaluate_board());
+    assert!(trace.get_term("Endgame knowledge").is_some());
+
+    let term_sum: i64 = trace.terms.iter().map(|t| t.net(trace.phase)).sum();
+    assert_eq!(term_sum, trace.total);
+}
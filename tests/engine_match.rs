@@ -0,0 +1,147 @@
+use chess_engine::color::Color;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::engine_match::{
+    play_game, Adjudication, MatchEngine, MatchOptions, Referee, Termination, TimeControl,
+};
+use chess_engine::game_result::GameResult;
+use chess_engine::variant::Variant;
+
+fn fast_options() -> MatchOptions {
+    return MatchOptions {
+        variant: Variant::Standard,
+        time_control: TimeControl {
+            base_ms: 200,
+            increment_ms: 10,
+        },
+        adjudication: Adjudication::default(),
+    };
+}
+
+#[test]
+fn test_time_control_parse() {
+    assert_eq!(
+        TimeControl::parse("10+0.1"),
+        Ok(TimeControl {
+            base_ms: 10_000,
+            increment_ms: 100
+        })
+    );
+    assert_eq!(
+        TimeControl::parse("5"),
+        Ok(TimeControl {
+            base_ms: 5_000,
+            increment_ms: 0
+        })
+    );
+    assert!(TimeControl::parse("ten").is_err());
+    assert!(TimeControl::parse("10+-1").is_err());
+}
+
+#[test]
+fn test_play_game() {
+    let white = MatchEngine::new("White engine");
+    let black = MatchEngine::new("Black engine");
+
+    // Mate in one.
+    let game = play_game(
+        &white,
+        &black,
+        "6k1/5ppp/8/8/8/8/8/R5K1 w - -",
+        &fast_options(),
+    )
+    .expect("Opening is valid.");
+    assert_eq!(game.result, GameResult::WhiteWins);
+    assert_eq!(game.termination, Termination::OnTheBoard);
+    assert_eq!(game.pgn.moves.len(), 1);
+    assert_eq!(game.pgn.moves[0].san, "Ra8#");
+    assert_eq!(game.pgn.tag("White"), Some("White engine"));
+    assert_eq!(game.pgn.tag("Result"), Some("1-0"));
+    assert_eq!(game.pgn.tag("Termination"), Some("on the board"));
+
+    // Nothing left to mate with.
+    let game = play_game(
+        &white,
+        &black,
+        "8/8/4k3/8/8/3K4/8/6N1 b - -",
+        &fast_options(),
+    )
+    .unwrap();
+    assert_eq!(game.result, GameResult::Draw);
+    assert_eq!(game.termination, Termination::InsufficientMaterial);
+    assert!(game.pgn.moves.is_empty());
+
+    // Out of moves.
+    let mut options = fast_options();
+    options.adjudication.max_plies = 4;
+    let game = play_game(&white, &black, INITIAL_GAME_STATE_FEN, &options).unwrap();
+    assert_eq!(game.result, GameResult::Draw);
+    assert_eq!(game.termination, Termination::MaxPlies);
+    assert_eq!(game.pgn.moves.len(), 4);
+    assert!(game.pgn.moves.iter().all(|m| m.evaluation.is_some()));
+    assert!(game.pgn.replay().is_ok());
+
+    assert!(play_game(&white, &black, "not a fen", &options).is_err());
+}
+
+#[test]
+fn test_resign_adjudication() {
+    // White is a queen up and both engines know it.
+    let mut options = fast_options();
+    options.adjudication.resign_plies = 2;
+    let game = play_game(
+        &MatchEngine::new("A"),
+        &MatchEngine::new("B"),
+        "4k3/8/8/8/8/8/3QPPPP/4K3 w - -",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(game.result, GameResult::WhiteWins);
+    assert_eq!(game.termination, Termination::ResignAdjudication);
+    assert_eq!(game.pgn.moves.len(), 2);
+}
+
+#[test]
+fn test_forfeit() {
+    let mut referee = Referee::new(
+        Variant::Standard,
+        INITIAL_GAME_STATE_FEN,
+        Adjudication::default(),
+    )
+    .unwrap();
+    let e4 = referee.game.uci_to_legal_move("e2e4").unwrap();
+    referee.play_move(&e4, Some(30));
+
+    let game = referee.forfeit(Color::Black, Termination::TimeForfeit, "B ran out of time.");
+    assert_eq!(game.result, GameResult::WhiteWins);
+    assert_eq!(game.pgn.tag("Termination"), Some("time forfeit"));
+    assert_eq!(game.pgn.moves[0].comments, ["B ran out of time."]);
+}
+
+#[test]
+fn test_fifty_moves_from_opening_fen() {
+    // 99 plies without progress already, so one quiet move ends it.
+    let mut referee = Referee::new(
+        Variant::Standard,
+        "4k3/8/8/8/8/8/8/R3K3 w - - 99 80",
+        Adjudication::default(),
+    )
+    .unwrap();
+    assert_eq!(referee.game_over(), None);
+    let rook_move = referee.game.uci_to_legal_move("a1a2").unwrap();
+    referee.play_move(&rook_move, None);
+    assert_eq!(
+        referee.game_over(),
+        Some((GameResult::Draw, Termination::FiftyMoves))
+    );
+
+    // No counters, a fresh count.
+    let mut referee = Referee::new(
+        Variant::Standard,
+        "4k3/8/8/8/8/8/8/R3K3 w - -",
+        Adjudication::default(),
+    )
+    .unwrap();
+    let rook_move = referee.game.uci_to_legal_move("a1a2").unwrap();
+    referee.play_move(&rook_move, None);
+    assert_eq!(referee.game_over(), None);
+}
@@ -0,0 +1,106 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::epd::EpdPosition;
+use chess_engine::epd_error::EpdError;
+use chess_engine::illegal_move_error::IllegalMoveError;
+use chess_engine::search_limits::SearchLimits;
+
+#[test]
+fn test_parse_epd() {
+    let position = EpdPosition::parse(
+        r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001"; c0 "Qg6 mates; fxg6 Nxg6#";"#,
+    )
+    .expect("EPD should parse.");
+
+    assert_eq!(
+        position.fen,
+        "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - -"
+    );
+    assert_eq!(position.best_moves, vec!["Qg6".to_string()]);
+    assert!(position.avoid_moves.is_empty());
+    assert_eq!(position.id.as_deref(), Some("WAC.001"));
+    assert_eq!(position.comment.as_deref(), Some("Qg6 mates; fxg6 Nxg6#"));
+    assert_eq!(position.operations.len(), 3);
+
+    // Move counters, several moves per opcode, opcodes we don't know and no final `;`.
+    let position = EpdPosition::parse(
+        "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3 bm Bb5 Bc4; am Qe2; acd 12; id sample",
+    )
+    .expect("EPD should parse.");
+    assert_eq!(
+        position.fen,
+        "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq -"
+    );
+    assert_eq!(
+        position.best_moves,
+        vec!["Bb5".to_string(), "Bc4".to_string()]
+    );
+    assert_eq!(position.avoid_moves, vec!["Qe2".to_string()]);
+    assert_eq!(position.id.as_deref(), Some("sample"));
+    assert_eq!(
+        position.operations[2],
+        ("acd".to_string(), vec!["12".to_string()])
+    );
+}
+
+#[test]
+fn test_epd_errors() {
+    assert_eq!(
+        EpdPosition::parse("8/8/8/8/8/8/8/8 w"),
+        Err(EpdError::MissingFields(2))
+    );
+    assert_eq!(EpdPosition::parse(""), Err(EpdError::MissingFields(0)));
+    assert_eq!(
+        EpdPosition::parse("4k3/8/8/8/8/8/8/4K3 w - - id \"open"),
+        Err(EpdError::UnterminatedString)
+    );
+
+    let mut game = ChessGame::new();
+    let position = EpdPosition::parse("4k3/8/8/8/8/8/8/4K3 w - - id \"nothing\";").unwrap();
+    assert_eq!(
+        position.solve(&mut game, &SearchLimits::depth(1)),
+        Err(EpdError::NothingToCheck)
+    );
+
+    let position = EpdPosition::parse("4k3/8/8/8/8/8/8/4K3 w - - bm Qh5;").unwrap();
+    assert_eq!(
+        position.solve(&mut game, &SearchLimits::depth(1)),
+        Err(EpdError::InvalidMove {
+            opcode: "bm".to_string(),
+            error: IllegalMoveError::NotLegal("Qh5".to_string())
+        })
+    );
+
+    let position = EpdPosition::parse("4k3/8/8/8/8/8/8/4K3 x - - bm Kd2;").unwrap();
+    assert!(matches!(
+        position.solve(&mut game, &SearchLimits::depth(1)),
+        Err(EpdError::InvalidFen(_))
+    ));
+}
+
+#[test]
+fn test_solve_epd() {
+    let mut game = ChessGame::new();
+
+    // Back rank mate.
+    let position =
+        EpdPosition::parse("6k1/5ppp/8/8/8/8/8/R5K1 w - - bm Ra8#; id \"mate\";").unwrap();
+    let outcome = position
+        .solve(&mut game, &SearchLimits::depth(2))
+        .expect("Position should be searched.");
+    assert!(outcome.solved);
+    assert_eq!(outcome.found.as_deref(), Some("Ra8#"));
+    assert!(outcome.nodes > 0);
+
+    // Same position, but told to avoid the mate.
+    let position = EpdPosition::parse("6k1/5ppp/8/8/8/8/8/R5K1 w - - am Ra8;").unwrap();
+    let outcome = position.solve(&mut game, &SearchLimits::depth(2)).unwrap();
+    assert!(!outcome.solved);
+
+    // Moves written as UCI work too.
+    let position = EpdPosition::parse("6k1/5ppp/8/8/8/8/8/R5K1 w - - bm a1a8;").unwrap();
+    position.set_up(&mut game).unwrap();
+    let mate = game.uci_to_legal_move("a1a8").unwrap();
+    let other = game.uci_to_legal_move("a1a2").unwrap();
+    assert_eq!(position.is_solved_by(&game, &mate), Ok(true));
+    assert_eq!(position.is_solved_by(&game, &other), Ok(false));
+}
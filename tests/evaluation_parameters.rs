@@ -0,0 +1,84 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::color::Color;
+use chess_engine::evaluation_parameters::EvaluationParameters;
+use chess_engine::piece_type::PieceType;
+
+#[test]
+fn test_default_parameters_match_hand_written_evaluation() {
+    let mut game = ChessGame::new();
+
+    // Values from before the weights were moved into a parameter set.
+    let expected: Vec<(&str, i64)> = vec![
+        (
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            20,
+        ),
+        ("8/2p5/3p4/KP5r/1R3p2/6k1/4P1P1/8 w - - 0 1", -5),
+        (
+            "rnbqkb1r/pp1p1ppp/4pn2/2p5/2PP4/5N2/PP2PPPP/RNBQKB1R b KQkq - 0 1",
+            5,
+        ),
+    ];
+
+    for (fen, evaluation) in expected.iter() {
+        let _ = game.import_fen(fen);
+        assert_eq!(
+            game.evaluate_board(),
+            *evaluation,
+            "Evaluation changed for {fen}."
+        );
+    }
+}
+
+#[test]
+fn test_parameters_round_trip() {
+    let mut parameters = EvaluationParameters::default();
+    parameters.knight_value = 315;
+    parameters.pawn_happy_squares[20] = 42;
+
+    let json = parameters.to_json().expect("Should serialize.");
+    let parsed = EvaluationParameters::from_json(&json).expect("Should parse.");
+    assert_eq!(parsed, parameters);
+
+    let flattened = parameters.to_vec();
+    assert_eq!(flattened.len(), EvaluationParameters::parameter_count());
+    assert_eq!(EvaluationParameters::from_vec(&flattened), Ok(parameters));
+}
+
+#[test]
+fn test_parameters_validation() {
+    let mut parameters = EvaluationParameters::default();
+    parameters.rook_happy_squares.pop();
+    let json = parameters.to_json().expect("Should serialize.");
+    assert!(EvaluationParameters::from_json(&json).is_err());
+
+    assert!(EvaluationParameters::from_json("{ \"pawn_value\": 100 }").is_err());
+    assert!(EvaluationParameters::from_vec(&[1, 2, 3]).is_err());
+}
+
+#[test]
+fn test_parameter_indexes_agree_with_lookups() {
+    let parameters = EvaluationParameters::default();
+    let flattened = parameters.to_vec();
+
+    for piece_type in [PieceType::Pawn, PieceType::Knight, PieceType::King] {
+        for square in [0, 13, 36, 63] {
+            for side in [Color::White, Color::Black] {
+                let index =
+                    EvaluationParameters::happy_square_parameter_index(piece_type, square, side);
+                assert_eq!(
+                    flattened[index],
+                    parameters.happy_square_value(piece_type, square, side)
+                );
+            }
+        }
+    }
+
+    assert!(EvaluationParameters::piece_value_parameter_index(PieceType::King).is_none());
+    let queen_index = EvaluationParameters::piece_value_parameter_index(PieceType::Queen)
+        .expect("Queens are tunable.");
+    assert_eq!(
+        flattened[queen_index],
+        parameters.piece_value(PieceType::Queen)
+    );
+}
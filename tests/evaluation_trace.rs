@@ -0,0 +1,53 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::constants;
+
+#[test]
+fn test_evaluate_trace_matches_evaluate_board() {
+    let mut game = ChessGame::new();
+    let fens: Vec<&str> = vec![
+        constants::INITIAL_GAME_STATE_FEN,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p2/6k1/4P1P1/8 w - - 0 1",
+        "rnbqkb1r/pp1p1ppp/4pn2/2p5/2PP4/5N2/PP2PPPP/RNBQKB1R b KQkq - 0 1",
+    ];
+
+    for fen in fens.iter() {
+        assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+        let trace = game.evaluate_trace();
+        assert_eq!(
+            trace.total,
+            game.evaluate_board(),
+            "Trace total differs for {fen}."
+        );
+
+        // The terms should add up to the total.
+        let term_sum: i64 = trace.terms.iter().map(|t| t.net(trace.phase)).sum();
+        assert_eq!(term_sum, trace.total, "Terms do not add up for {fen}.");
+    }
+}
+
+#[test]
+fn test_evaluate_trace_initial_position() {
+    let mut game = ChessGame::new();
+    let _ = game.import_fen(constants::INITIAL_GAME_STATE_FEN);
+
+    let trace = game.evaluate_trace();
+    assert_eq!(trace.phase, constants::GAME_PHASE_MAX);
+    assert_eq!(trace.total, 0);
+
+    let material = trace
+        .get_term("Material")
+        .expect("Material term should exist.");
+    assert_eq!(material.white, material.black);
+    assert_eq!(
+        material.white.middlegame,
+        8 * 100 + 2 * 300 + 2 * 320 + 2 * 500 + 900
+    );
+
+    // Should be printable and serializable.
+    assert!(trace.to_table_string().contains("Material"));
+    let json = trace.to_json().expect("Trace should serialize.");
+    let parsed: serde_json::Value = serde_json::from_str(&json).expect("Should be valid JSON.");
+    assert_eq!(parsed["total"], 0);
+    assert_eq!(parsed["terms"][0]["name"], "Material");
+}
@@ -0,0 +1,63 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::game_result::GameResult;
+use chess_engine::variant::Variant;
+
+fn result_of(game: &mut ChessGame, variant: Variant, fen: &str) -> GameResult {
+    game.variant = variant;
+    assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+    game.set_legal_moves(None);
+    return game.game_result();
+}
+
+#[test]
+fn test_standard_results() {
+    let mut game = ChessGame::new();
+
+    assert_eq!(
+        result_of(&mut game, Variant::Standard, INITIAL_GAME_STATE_FEN),
+        GameResult::Ongoing
+    );
+
+    // Fool's mate.
+    let fen = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3";
+    assert_eq!(
+        result_of(&mut game, Variant::Standard, fen),
+        GameResult::BlackWins
+    );
+
+    let fen = "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1";
+    assert_eq!(
+        result_of(&mut game, Variant::Standard, fen),
+        GameResult::Draw
+    );
+    assert!(!GameResult::Ongoing.is_over());
+    assert!(GameResult::Draw.is_over());
+}
+
+#[test]
+fn test_variant_results() {
+    let mut game = ChessGame::new();
+
+    // Stalemated in antichess is a win.
+    let fen = "8/8/8/8/8/p7/P7/8 w - - 0 1";
+    assert_eq!(
+        result_of(&mut game, Variant::Antichess, fen),
+        GameResult::WhiteWins
+    );
+
+    // No black king left in atomic.
+    let fen = "8/8/8/8/8/8/8/4K3 b - - 0 1";
+    assert_eq!(
+        result_of(&mut game, Variant::Atomic, fen),
+        GameResult::WhiteWins
+    );
+
+    // Black has given three checks.
+    let fen = "4k3/8/8/8/8/8/8/4K3 w - - 3+0 0 1";
+    assert_eq!(
+        result_of(&mut game, Variant::ThreeCheck, fen),
+        GameResult::BlackWins
+    );
+    assert!(game.legal_moves.is_empty());
+}
@@ -0,0 +1,67 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::game_result::GameResult;
+use chess_engine::variant::Variant;
+
+fn king_of_the_hill_game() -> ChessGame {
+    let mut game = ChessGame::new();
+    game.variant = Variant::KingOfTheHill;
+    return game;
+}
+
+#[test]
+fn test_king_of_the_hill_perft() {
+    let mut game = king_of_the_hill_game();
+
+    let positions: Vec<(&str, [u64; 3])> = vec![
+        (INITIAL_GAME_STATE_FEN, [20, 400, 8_902]),
+        (
+            "r5k1/p4r1n/1n2q1p1/3p4/8/1P5P/P1P2PK1/RN5R b - - 1 16",
+            [45, 867, 38_885],
+        ),
+        (
+            "8/1p4p1/1N2k3/4N2p/4P3/8/P1R3PP/7K b - - 0 17",
+            [7, 191, 1_237],
+        ),
+    ];
+
+    for (fen, expected) in positions.iter() {
+        assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+        for (depth, nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                game.perft(depth as u32 + 1),
+                *nodes,
+                "Perft {} of {fen}.",
+                depth + 1
+            );
+        }
+    }
+}
+
+#[test]
+fn test_reaching_the_hill_wins() {
+    let mut game = king_of_the_hill_game();
+    let _ = game.import_fen("7k/8/8/8/4K3/8/8/8 b - - 0 1");
+    game.set_legal_moves(None);
+
+    assert!(game.legal_moves.is_empty());
+    assert_eq!(game.game_result(), GameResult::WhiteWins);
+    assert!(!game.is_stalemate());
+
+    // Same position in standard chess is just a king ending.
+    game.variant = Variant::Standard;
+    game.set_legal_moves(None);
+    assert_eq!(game.game_result(), GameResult::Ongoing);
+}
+
+#[test]
+fn test_search_walks_to_the_hill() {
+    let mut game = king_of_the_hill_game();
+    let _ = game.import_fen("7k/8/8/8/8/3K4/8/r7 w - - 0 1");
+    game.set_legal_moves(None);
+
+    let (score, best_move) = game.minimax(1, std::i64::MIN, std::i64::MAX);
+    assert_eq!(score, std::i64::MAX);
+    let best_move = game.move_to_uci(&best_move.unwrap());
+    assert!(["d3d4", "d3e4"].contains(&best_move.as_str()));
+}
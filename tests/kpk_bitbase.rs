@@ -0,0 +1,80 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::color::Color;
+use chess_engine::endgame::KNOWN_WIN;
+use chess_engine::helpers::str_coord_to_square;
+use chess_engine::kpk_bitbase::KpkBitbase;
+
+fn probe(
+    strong_side: Color,
+    king: &str,
+    pawn: &str,
+    weak_king: &str,
+    strong_to_move: bool,
+) -> bool {
+    return KpkBitbase::get().probe(
+        strong_side,
+        str_coord_to_square(king).unwrap(),
+        str_coord_to_square(pawn).unwrap(),
+        str_coord_to_square(weak_king).unwrap(),
+        strong_to_move,
+    );
+}
+
+#[test]
+fn test_known_positions() {
+    // King on the sixth in front of the pawn wins, whoever moves.
+    assert!(probe(Color::White, "e6", "e5", "e8", true));
+    assert!(probe(Color::White, "e6", "e5", "e8", false));
+
+    // Opposition. Whoever has to move loses the fight for the key squares.
+    assert!(!probe(Color::White, "e5", "e4", "e7", true));
+    assert!(probe(Color::White, "e5", "e4", "e7", false));
+
+    // Rook pawns draw once the defending king reaches the corner.
+    assert!(!probe(Color::White, "h1", "a2", "a8", true));
+    assert!(!probe(Color::White, "b6", "a5", "a8", true));
+
+    // Outside the square of the pawn.
+    assert!(probe(Color::White, "a1", "a2", "h2", true));
+    assert!(!probe(Color::White, "a1", "a2", "g2", false));
+
+    // Stalemate.
+    assert!(!probe(Color::White, "b6", "a7", "a8", false));
+
+    // Defending king in front of the pawn, with the pawn far back.
+    assert!(!probe(Color::White, "e3", "e4", "e6", true));
+}
+
+#[test]
+fn test_black_and_mirrored_positions() {
+    // Same as the opposition positions above, flipped to black.
+    assert!(!probe(Color::Black, "e4", "e5", "e2", true));
+    assert!(probe(Color::Black, "e4", "e5", "e2", false));
+
+    // Mirrored onto the other side of the board.
+    assert!(!probe(Color::White, "d5", "d4", "d7", true));
+    assert!(probe(Color::White, "d5", "d4", "d7", false));
+    assert!(!probe(Color::White, "a1", "h2", "h8", true));
+}
+
+#[test]
+fn test_bitbase_is_shared() {
+    let first = KpkBitbase::get() as *const KpkBitbase;
+    let second = KpkBitbase::get() as *const KpkBitbase;
+    assert_eq!(first, second);
+    assert!(KpkBitbase::get().win_count() > 0);
+}
+
+#[test]
+fn test_evaluation_uses_bitbase() {
+    let mut game = ChessGame::new();
+
+    let _ = game.import_fen("8/4k3/8/4K3/4P3/8/8/8 b - - 0 1");
+    assert!(game.evaluate_board() > KNOWN_WIN);
+
+    let _ = game.import_fen("8/4k3/8/4K3/4P3/8/8/8 w - - 0 1");
+    assert_eq!(game.evaluate_board(), 0);
+
+    let _ = game.import_fen("8/8/8/4p3/4k3/8/4K3/8 w - - 0 1");
+    assert!(game.evaluate_board() < -KNOWN_WIN);
+}
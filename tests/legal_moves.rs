@@ -0,0 +1,146 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::move_generation_mode::MoveGenerationMode;
+use chess_engine::piece_type::PieceType;
+use chess_engine::r#move::Move;
+use chess_engine::variant::Variant;
+
+// Everything a generated move carries, so the two generators have to agree on all of it.
+fn describe_moves(game: &ChessGame, moves: &[Move]) -> Vec<String> {
+    let mut descriptions: Vec<String> = moves
+        .iter()
+        .map(|m| {
+            format!(
+                "{} check {:?} castling {:?} {:?} {:?} {:?}",
+                game.move_to_uci(m),
+                m.is_check,
+                m.removes_white_castling_rights_short,
+                m.removes_white_castling_rights_long,
+                m.removes_black_castling_rights_short,
+                m.removes_black_castling_rights_long
+            )
+        })
+        .collect();
+    descriptions.sort();
+    return descriptions;
+}
+
+// Compares both generators in this position and every position below it, `depth` plies deep.
+fn compare_generators(game: &mut ChessGame, depth: u32) {
+    let with_masks = game.get_legal_moves_with_masks(MoveGenerationMode::All);
+    let by_make_unmake = game.get_legal_moves_by_make_unmake(MoveGenerationMode::All);
+    assert_eq!(
+        describe_moves(game, &with_masks),
+        describe_moves(game, &by_make_unmake),
+        "Generators disagree on {}.",
+        game.export_fen()
+    );
+
+    if depth == 0 {
+        return;
+    }
+    for m in by_make_unmake.iter() {
+        game.make_move(m, false);
+        compare_generators(game, depth - 1);
+        game.unmake_move(m);
+    }
+}
+
+#[test]
+fn test_generators_agree() {
+    let mut game = ChessGame::new();
+
+    let positions: Vec<(Variant, &str)> = vec![
+        (Variant::Standard, INITIAL_GAME_STATE_FEN),
+        (
+            Variant::Standard,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        ),
+        (
+            Variant::Standard,
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ),
+        (
+            Variant::Standard,
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        ),
+        (
+            Variant::Standard,
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        ),
+        (
+            Variant::Chess960,
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        ),
+        (
+            Variant::Crazyhouse,
+            "r1bqk2r/pppp1ppp/2n5/4p3/1b2P3/2N5/PPPP1PPP/R1BQKB~1R[NPn] w KQkq - 0 1",
+        ),
+    ];
+
+    for (variant, fen) in positions.iter() {
+        game.variant = *variant;
+        assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+        compare_generators(&mut game, 2);
+    }
+}
+
+#[test]
+fn test_en_passant_discovered_check() {
+    let mut game = ChessGame::new();
+
+    // Taking en passant clears both pawns off the rank, and the rook sees our king.
+    let _ = game.import_fen("8/8/8/K2pP2r/8/8/8/7k w - d6 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("e5d6").is_err());
+    assert!(game.uci_to_legal_move("e5e6").is_ok());
+
+    // Same thing without the rook is fine.
+    let _ = game.import_fen("8/8/8/K2pP3/8/8/8/7k w - d6 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("e5d6").is_ok());
+}
+
+#[test]
+fn test_pins_and_double_check() {
+    let mut game = ChessGame::new();
+
+    // The bishop is pinned along the file, so it can't move at all. The rook can slide along the pin.
+    let _ = game.import_fen("4k3/4b3/8/8/4Q3/8/4R3/4K3 b - - 0 1");
+    game.set_legal_moves(None);
+    assert!(game
+        .legal_moves
+        .iter()
+        .all(|m| m.from_piece_type == Some(PieceType::King)));
+
+    let _ = game.import_fen("4k3/4r3/8/8/4Q3/8/8/4K3 b - - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("e7e4").is_ok());
+    assert!(game.uci_to_legal_move("e7e5").is_ok());
+    assert!(game.uci_to_legal_move("e7d7").is_err());
+
+    // Knight and rook both give check. Taking the knight with the queen doesn't help.
+    let _ = game.import_fen("4k3/8/q4N2/8/8/8/8/4R1K1 b - - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("a6f6").is_err());
+    assert!(!game.legal_moves.is_empty());
+    assert!(game
+        .legal_moves
+        .iter()
+        .all(|m| m.from_piece_type == Some(PieceType::King)));
+}
+
+#[test]
+fn test_castling_rook_was_shielding_the_king() {
+    let mut game = ChessGame::new();
+    game.variant = Variant::Chess960;
+
+    // Once our rook leaves b1, the rook on a1 sees c1.
+    let _ = game.import_fen("4k3/8/8/8/8/8/8/rR1K4 w B - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("d1b1").is_err());
+
+    let _ = game.import_fen("4k3/8/8/8/8/8/8/1R1K4 w B - 0 1");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move("d1b1").is_ok());
+}
@@ -0,0 +1,255 @@
+use chess_engine::bitboard::Bitboard;
+use chess_engine::chess_game::{ChessGame, POCKET_PIECE_TYPES};
+use chess_engine::color::Color;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::r#move::Move;
+use chess_engine::square::Square;
+use chess_engine::variant::Variant;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+// Random games played from each of these. Positions with castling, en passant and promotions close to hand.
+const FUZZ_POSITIONS: [(Variant, &str); 12] = [
+    (Variant::Standard, INITIAL_GAME_STATE_FEN),
+    (
+        Variant::Standard,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    ),
+    (
+        Variant::Standard,
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    ),
+    (
+        Variant::Standard,
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    ),
+    (
+        Variant::Chess960,
+        "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+    ),
+    (Variant::Chess960, "rkr5/8/8/8/8/8/8/RKR4R w CAca - 0 1"),
+    (
+        Variant::Crazyhouse,
+        "r1bqk2r/pppp1ppp/2n5/4p3/1b2P3/2N5/PPPP1PPP/R1BQKB~1R[NPn] w KQkq - 0 1",
+    ),
+    (Variant::Atomic, INITIAL_GAME_STATE_FEN),
+    (
+        Variant::ThreeCheck,
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+3 0 1",
+    ),
+    (
+        Variant::Antichess,
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1",
+    ),
+    (Variant::KingOfTheHill, INITIAL_GAME_STATE_FEN),
+    (
+        Variant::RacingKings,
+        "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1",
+    ),
+];
+
+const GAMES_PER_POSITION: usize = 3;
+const MAX_PLIES: usize = 60;
+
+// Everything that make has to change and unmake has to put back.
+#[derive(Clone, Debug, PartialEq)]
+struct BoardState {
+    piece_bitboards: [Bitboard; 12],
+    occupancies: [Bitboard; 3],
+    white_to_move: bool,
+    // Where the rook for each castling right is, for the rights still held.
+    castling_rooks: [Option<Square>; 4],
+    en_passant_target: Option<Square>,
+    pockets: [[usize; 5]; 2],
+    promoted_pieces: Bitboard,
+    checks_given: [usize; 2],
+    zobrist_hash: u64,
+    fen: String,
+}
+
+fn board_state(game: &ChessGame) -> BoardState {
+    let mut castling_rooks = [None; 4];
+    for (rights_index, rook) in castling_rooks.iter_mut().enumerate() {
+        if castling_rights(game)[rights_index] {
+            *rook = Some(game.castling_rook_squares()[rights_index]);
+        }
+    }
+
+    return BoardState {
+        piece_bitboards: game.piece_bitboards(),
+        occupancies: [
+            game.occupied_by(Color::White),
+            game.occupied_by(Color::Black),
+            game.occupied(),
+        ],
+        white_to_move: game.white_to_move,
+        castling_rooks,
+        en_passant_target: game.en_passant_target,
+        pockets: [Color::White, Color::Black]
+            .map(|side| POCKET_PIECE_TYPES.map(|p| game.pocket(side, p))),
+        promoted_pieces: game.promoted_pieces(),
+        checks_given: [
+            game.checks_given(Color::White),
+            game.checks_given(Color::Black),
+        ],
+        zobrist_hash: game.zobrist_hash,
+        fen: game.export_fen(),
+    };
+}
+
+// White short, white long, black short, black long.
+fn castling_rights(game: &ChessGame) -> [bool; 4] {
+    return [
+        game.can_white_castle_short,
+        game.can_white_castle_long,
+        game.can_black_castle_short,
+        game.can_black_castle_long,
+    ];
+}
+
+// Checks the position holds together, and panics with `context` if it doesn't.
+fn assert_consistent(game: &ChessGame, context: &str) {
+    // No square holds two pieces, and the occupancies are the pieces.
+    let mut white = 0;
+    let mut black = 0;
+    let piece_bitboards = game.piece_bitboards();
+    for (bitboard_index, bitboard) in piece_bitboards.iter().enumerate() {
+        for (other_index, other) in piece_bitboards.iter().enumerate() {
+            assert!(
+                bitboard_index == other_index || (*bitboard & *other).is_empty(),
+                "Pieces overlap after {context}."
+            );
+        }
+        match bitboard_index < 6 {
+            true => white |= bitboard.0,
+            false => black |= bitboard.0,
+        }
+    }
+    assert_eq!(
+        [
+            game.occupied_by(Color::White),
+            game.occupied_by(Color::Black),
+            game.occupied(),
+        ],
+        [white, black, white | black].map(Bitboard),
+        "Occupancies after {context}."
+    );
+
+    assert_eq!(
+        game.zobrist_hash,
+        game.compute_zobrist(),
+        "Zobrist hash after {context}."
+    );
+
+    // Castling rights need their rook at home.
+    for (rights_index, has_right) in castling_rights(game).iter().enumerate() {
+        let rook_bitboard = match rights_index < 2 {
+            true => piece_bitboards[3],
+            false => piece_bitboards[9],
+        };
+        let rook_square = game.castling_rook_squares()[rights_index];
+        assert!(
+            !has_right || rook_bitboard.contains(rook_square),
+            "Castling right {rights_index} without its rook after {context}."
+        );
+    }
+
+    // En passant targets are behind a pawn that just moved two squares. Black's on rank 6, white's on rank 3.
+    if let Some(target) = game.en_passant_target {
+        let expected_row = match game.white_to_move {
+            true => 2,
+            false => 5,
+        };
+        assert_eq!(
+            target.index() / 8,
+            expected_row,
+            "En passant target {target} after {context}."
+        );
+    }
+
+    // The FEN reads back into the same position.
+    let mut copy = ChessGame::new();
+    copy.variant = game.variant;
+    let fen = game.export_fen();
+    copy.import_fen(&fen)
+        .unwrap_or_else(|e| panic!("Exported `{fen}` after {context}, which doesn't import. {e}"));
+    assert_eq!(
+        board_state(&copy),
+        board_state(game),
+        "FEN round trip of `{fen}` after {context}."
+    );
+}
+
+fn moves_string(moves: &[Move]) -> String {
+    return moves
+        .iter()
+        .map(|m| m.move_to_str())
+        .collect::<Vec<String>>()
+        .join(" ");
+}
+
+/**
+ * Plays random games. In every position on the way, each legal move is made, checked, and unmade again, and the board
+ * has to come back exactly as it was. At the end the whole game is taken back.
+ */
+#[test]
+fn test_random_make_unmake() {
+    let mut rng = ChaCha8Rng::seed_from_u64(48);
+
+    for (variant, fen) in FUZZ_POSITIONS {
+        for game_number in 0..GAMES_PER_POSITION {
+            let mut game = ChessGame::new();
+            game.variant = variant;
+            game.import_fen(fen).expect("Fuzz positions are valid.");
+            game.set_legal_moves(None);
+            assert_consistent(&game, &format!("importing `{fen}`"));
+            let start = board_state(&game);
+
+            let mut moves_played: Vec<Move> = vec![];
+            for _ in 0..MAX_PLIES {
+                if game.legal_moves.is_empty() || game.game_result().is_over() {
+                    break;
+                }
+
+                let before = board_state(&game);
+                for this_move in game.legal_moves.clone() {
+                    let context = format!(
+                        "{variant:?} game {game_number} from `{fen}`, moves {} {}",
+                        moves_string(&moves_played),
+                        this_move.move_to_str()
+                    );
+
+                    game.make_move(&this_move, false);
+                    assert_consistent(&game, &format!("making {context}"));
+                    game.unmake_move(&this_move);
+                    assert_eq!(
+                        board_state(&game),
+                        before,
+                        "Board after unmaking {context}."
+                    );
+                }
+
+                let this_move = *game
+                    .legal_moves
+                    .choose(&mut rng)
+                    .expect("Checked for moves above.");
+                moves_played.push(this_move);
+                game.make_move(&this_move, true);
+            }
+
+            // Then take the whole game back, to check unmakes stack up too.
+            let context = format!(
+                "{variant:?} game {game_number} from `{fen}`, moves {}",
+                moves_string(&moves_played)
+            );
+            for this_move in moves_played.iter().rev() {
+                game.unmake_move(this_move);
+            }
+            assert_eq!(
+                board_state(&game),
+                start,
+                "Board after unmaking all of {context}."
+            );
+        }
+    }
+}
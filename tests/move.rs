@@ -1,31 +1,78 @@
+use chess_engine::move_parse_error::MoveParseError;
 use chess_engine::r#move::Move;
+use chess_engine::square::Square;
 
 #[test]
 fn test_str_to_move() {
     let mut move_str: &str;
-    let mut parsed_move: Result<Move, String>;
+    let mut parsed_move: Result<Move, MoveParseError>;
     let mut unwrapped_move: Move;
 
     // Basic string move parsing.
     move_str = "e2e4";
     parsed_move = Move::str_to_move(move_str);
-    assert!(parsed_move.is_ok(), "Failed to parse move {move_str} correctly.");
+    assert!(
+        parsed_move.is_ok(),
+        "Failed to parse move {move_str} correctly."
+    );
     unwrapped_move = parsed_move.unwrap();
-    assert!(unwrapped_move.from_square == 52);
-    assert!(unwrapped_move.to_square == 36);
+    assert!(unwrapped_move.from_square == Square::new(52));
+    assert!(unwrapped_move.to_square == Square::new(36));
     assert!(unwrapped_move.pawn_promoting_to.is_none());
 
     // Promotion.
     move_str = "e7e8q";
     parsed_move = Move::str_to_move(move_str);
-    assert!(parsed_move.is_ok(), "Failed to parse move {move_str} correctly.");
+    assert!(
+        parsed_move.is_ok(),
+        "Failed to parse move {move_str} correctly."
+    );
     unwrapped_move = parsed_move.unwrap();
-    assert!(unwrapped_move.from_square == 12);
-    assert!(unwrapped_move.to_square == 4);
+    assert!(unwrapped_move.from_square == Square::new(12));
+    assert!(unwrapped_move.to_square == Square::new(4));
     assert!(unwrapped_move.pawn_promoting_to.is_some());
 
     // Should fail.
     move_str = "a0a0";
     parsed_move = Move::str_to_move(move_str);
-    assert!(parsed_move.is_err(), "Parsed invalid move {move_str} when it should have failed.");
-}
\ No newline at end of file
+    assert!(
+        parsed_move.is_err(),
+        "Parsed invalid move {move_str} when it should have failed."
+    );
+}
+
+#[test]
+fn test_str_to_move_errors() {
+    assert_eq!(
+        Move::str_to_move("e2e"),
+        Err(MoveParseError::WrongLength(3))
+    );
+    assert_eq!(
+        Move::str_to_move("i2e4"),
+        Err(MoveParseError::InvalidFile('i'))
+    );
+    assert_eq!(
+        Move::str_to_move("e2e9"),
+        Err(MoveParseError::InvalidRank('9'))
+    );
+    assert_eq!(
+        Move::str_to_move("e7e8x"),
+        Err(MoveParseError::InvalidPiece('x'))
+    );
+    assert_eq!(
+        Move::str_to_move("X@f3"),
+        Err(MoveParseError::InvalidPiece('X'))
+    );
+    assert_eq!(
+        Move::str_to_move("aé2e"),
+        Err(MoveParseError::NotAscii("aé2e".to_string()))
+    );
+    assert_eq!(
+        Move::str_to_move("é@f3"),
+        Err(MoveParseError::NotAscii("é@f3".to_string()))
+    );
+
+    // Usable as a boxed error, with the same message as before.
+    let error: Box<dyn std::error::Error> = Box::new(MoveParseError::InvalidRank('9'));
+    assert!(error.to_string().contains("`9`"));
+}
@@ -0,0 +1,129 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::color::Color;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::move_generation_mode::MoveGenerationMode;
+use chess_engine::variant::Variant;
+
+fn sorted_uci(game: &mut ChessGame, mode: MoveGenerationMode) -> Vec<String> {
+    let moves = game.get_legal_moves_for(mode);
+    let mut ucis: Vec<String> = moves.iter().map(|m| game.move_to_uci(m)).collect();
+    ucis.sort();
+    return ucis;
+}
+
+// Checks the modes against the full list in this position and every position one ply below it.
+fn check_modes(game: &mut ChessGame, depth: u32) {
+    let all = sorted_uci(game, MoveGenerationMode::All);
+
+    // Noisy and quiet moves split the legal moves between them.
+    let noisy = game.get_legal_moves_for(MoveGenerationMode::CapturesAndPromotions);
+    let quiet = game.get_legal_moves_for(MoveGenerationMode::Quiet);
+    assert!(
+        noisy
+            .iter()
+            .all(|m| m.to_piece_type.is_some() || m.pawn_promoting_to.is_some()),
+        "Quiet move in the captures on {}.",
+        game.export_fen()
+    );
+    assert!(
+        quiet
+            .iter()
+            .all(|m| m.to_piece_type.is_none() && m.pawn_promoting_to.is_none()),
+        "Capture or promotion in the quiet moves on {}.",
+        game.export_fen()
+    );
+    let mut union: Vec<String> = noisy
+        .iter()
+        .chain(quiet.iter())
+        .map(|m| game.move_to_uci(m))
+        .collect();
+    union.sort();
+    assert_eq!(union, all, "Modes don't add up on {}.", game.export_fen());
+
+    // Evasions are everything when in check and nothing otherwise.
+    let our_side = match game.white_to_move {
+        true => Color::White,
+        false => Color::Black,
+    };
+    let evasions = sorted_uci(game, MoveGenerationMode::Evasions);
+    match game.is_in_check(&our_side) {
+        true => assert_eq!(evasions, all, "Evasions differ on {}.", game.export_fen()),
+        false => assert!(evasions.is_empty()),
+    }
+
+    if depth == 0 {
+        return;
+    }
+    for m in game.get_legal_moves() {
+        game.make_move(&m, false);
+        check_modes(game, depth - 1);
+        game.unmake_move(&m);
+    }
+}
+
+#[test]
+fn test_modes_add_up_to_all_moves() {
+    let mut game = ChessGame::new();
+
+    let positions: Vec<(Variant, &str)> = vec![
+        (Variant::Standard, INITIAL_GAME_STATE_FEN),
+        (
+            Variant::Standard,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        ),
+        (
+            Variant::Standard,
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        ),
+        (
+            Variant::Standard,
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ),
+        (
+            Variant::Crazyhouse,
+            "r1bqk2r/pppp1ppp/2n5/4p3/1b2P3/2N5/PPPP1PPP/R1BQKB~1R[NPn] w KQkq - 0 1",
+        ),
+        (
+            Variant::Antichess,
+            "rnbqkbnr/pppp1ppp/8/4p3/3P4/8/PPP1PPPP/RNBQKBNR w - - 0 2",
+        ),
+        (
+            Variant::Atomic,
+            "rnbqkb1r/pppp1ppp/5n2/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+        ),
+    ];
+
+    for (variant, fen) in positions.iter() {
+        game.variant = *variant;
+        assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+        check_modes(&mut game, 1);
+    }
+}
+
+#[test]
+fn test_evasions() {
+    let mut game = ChessGame::new();
+
+    // The rook checks along the rank, so no castling out of it.
+    let _ = game.import_fen("4k3/8/8/8/8/8/8/r3K2R w K - 0 1");
+    let evasions = sorted_uci(&mut game, MoveGenerationMode::Evasions);
+    assert!(!evasions.is_empty());
+    assert!(!evasions.contains(&"e1g1".to_string()));
+    assert_eq!(evasions, sorted_uci(&mut game, MoveGenerationMode::All));
+
+    let _ = game.import_fen(INITIAL_GAME_STATE_FEN);
+    assert!(game
+        .get_legal_moves_for(MoveGenerationMode::Evasions)
+        .is_empty());
+}
+
+#[test]
+fn test_promotions_count_as_noisy() {
+    let mut game = ChessGame::new();
+
+    let _ = game.import_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+    let noisy = sorted_uci(&mut game, MoveGenerationMode::CapturesAndPromotions);
+    assert_eq!(noisy, vec!["a7a8b", "a7a8n", "a7a8q", "a7a8r"]);
+    let quiet = sorted_uci(&mut game, MoveGenerationMode::Quiet);
+    assert!(quiet.iter().all(|m| m.starts_with("e1")));
+}
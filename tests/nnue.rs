@@ -0,0 +1,156 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::evaluation_mode::EvaluationMode;
+use chess_engine::nnue::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use std::sync::Arc;
+
+fn random_network(architecture: NnueArchitecture, hidden_size: usize, seed: u64) -> NnueNetwork {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut network = NnueNetwork::new(architecture, hidden_size);
+
+    for w in network.feature_biases.iter_mut() {
+        *w = rng.gen_range(-64..64);
+    }
+    for w in network.feature_weights.iter_mut() {
+        *w = rng.gen_range(-64..64);
+    }
+    for w in network.output_weights.iter_mut() {
+        *w = rng.gen_range(-64..64);
+    }
+    network.output_bias = rng.gen_range(-1000..1000);
+
+    return network;
+}
+
+#[test]
+fn test_network_round_trip() {
+    for architecture in [NnueArchitecture::HalfKP, NnueArchitecture::HalfKA] {
+        let network = random_network(architecture, 8, 1);
+        let bytes = network.to_bytes();
+        let loaded = NnueNetwork::from_bytes(&bytes).expect("Network should load.");
+        assert_eq!(loaded, network);
+    }
+}
+
+#[test]
+fn test_network_rejects_bad_files() {
+    let bytes = random_network(NnueArchitecture::HalfKP, 4, 2).to_bytes();
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    assert!(NnueNetwork::from_bytes(&bad_magic).is_err());
+
+    assert!(NnueNetwork::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(NnueNetwork::from_bytes(&trailing).is_err());
+
+    assert!(NnueNetwork::from_bytes(&[]).is_err());
+}
+
+#[test]
+fn test_incremental_accumulators_match_refresh() {
+    let fens: Vec<&str> = vec![
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "rnbqkbnr/p1pp1ppp/8/1p2pP2/8/8/PPPPP1PP/RNBQKBNR w KQkq e6 0 3",
+    ];
+
+    for architecture in [NnueArchitecture::HalfKP, NnueArchitecture::HalfKA] {
+        let network = Arc::new(random_network(architecture, 16, 3));
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+
+        for fen in fens.iter() {
+            let mut game = ChessGame::new();
+            game.set_nnue_network(Some(network.clone()));
+            let _ = game.import_fen(fen);
+            game.set_legal_moves(None);
+            let initial_accumulator = game.nnue_accumulators[0].clone();
+
+            // Play a random game, checking the accumulator against a rebuild after every move.
+            let mut moves_made = vec![];
+            for _ in 0..40 {
+                if game.legal_moves.is_empty() {
+                    break;
+                }
+                let m = game.legal_moves[rng.gen_range(0..game.legal_moves.len())];
+                game.make_move(&m, true);
+                moves_made.push(m);
+
+                assert_eq!(
+                    game.nnue_accumulators.last(),
+                    Some(&network.refresh_accumulator(&game.piece_bitboards().map(|b| b.0))),
+                    "Accumulator desynced after {} in {fen}.",
+                    m.move_to_str()
+                );
+            }
+
+            // Unwinding should give us back what we started with.
+            while let Some(m) = moves_made.pop() {
+                game.unmake_move(&m);
+            }
+            assert_eq!(game.nnue_accumulators.len(), 1);
+            assert_eq!(game.nnue_accumulators[0], initial_accumulator);
+        }
+    }
+}
+
+#[test]
+fn test_evaluation_mode_switch() {
+    let mut game = ChessGame::new();
+    let _ = game.import_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+    let classical = game.evaluate_board();
+
+    // No network loaded yet.
+    assert!(game.set_evaluation_mode(EvaluationMode::Nnue).is_err());
+    assert_eq!(game.evaluation_mode, EvaluationMode::Classical);
+
+    let network = Arc::new(random_network(NnueArchitecture::HalfKP, 8, 5));
+    game.set_nnue_network(Some(network.clone()));
+    assert!(game.set_evaluation_mode(EvaluationMode::Nnue).is_ok());
+
+    let accumulator = network.refresh_accumulator(&game.piece_bitboards().map(|b| b.0));
+    let expected = network.evaluate(&accumulator, chess_engine::color::Color::White);
+    assert_eq!(game.evaluate_board(), expected);
+
+    let trace = game.evaluate_trace();
+    assert_eq!(trace.total, expected);
+    assert!(trace.get_term("NNUE").is_some());
+
+    // Back to classical.
+    assert!(game.set_evaluation_mode(EvaluationMode::Classical).is_ok());
+    assert_eq!(game.evaluate_board(), classical);
+
+    // Unloading the network drops us back to classical too.
+    let _ = game.set_evaluation_mode(EvaluationMode::Nnue);
+    game.set_nnue_network(None);
+    assert_eq!(game.evaluation_mode, EvaluationMode::Classical);
+    assert!(game.nnue_accumulators.is_empty());
+}
+
+#[test]
+fn test_chunked_kernels_match_reference() {
+    let mut rng = ChaCha8Rng::seed_from_u64(6);
+
+    // Odd length, so the remainder path gets used too.
+    let values: Vec<i16> = (0..37).map(|_| rng.gen_range(-300..300)).collect();
+    let weights: Vec<i16> = (0..37).map(|_| rng.gen_range(-300..300)).collect();
+
+    let mut reference = values.clone();
+    let mut chunked = values.clone();
+    add_assign_reference(&mut reference, &weights);
+    add_assign_chunked(&mut chunked, &weights);
+    assert_eq!(reference, chunked);
+
+    sub_assign_reference(&mut reference, &weights);
+    sub_assign_chunked(&mut chunked, &weights);
+    assert_eq!(reference, chunked);
+    assert_eq!(reference, values);
+
+    assert_eq!(
+        clipped_relu_dot_reference(&values, &weights),
+        clipped_relu_dot_chunked(&values, &weights)
+    );
+}
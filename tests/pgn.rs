@@ -0,0 +1,182 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::game_result::GameResult;
+use chess_engine::illegal_move_error::IllegalMoveError;
+use chess_engine::pgn::{PgnGame, PgnMove};
+use chess_engine::pgn_error::PgnError;
+use chess_engine::variant::Variant;
+
+const ANNOTATED_GAME: &str = r#"[Event "Casual game"]
+[Site "https://lichess.org/abcdefgh"]
+[White "Some \"Quoted\" Player"]
+[Black "?"]
+[Result "1-0"]
+
+{ A short one. } 1. e4 { [%eval 0.3] [%clk 0:05:00] } 1... e5 2. Bc4 (2. Nf3 Nc6 (2... d6) 3. Bb5)
+2... Nc6 3. Qh5 $2 3... Nf6?? 4. Qxf7# ; mate
+1-0
+"#;
+
+#[test]
+fn test_parse_pgn() {
+    let pgn = PgnGame::parse(ANNOTATED_GAME).expect("PGN should parse.");
+
+    assert_eq!(pgn.tag("Event"), Some("Casual game"));
+    assert_eq!(pgn.tag("White"), Some("Some \"Quoted\" Player"));
+    assert_eq!(pgn.tag("Round"), None);
+    assert_eq!(pgn.result, GameResult::WhiteWins);
+    assert_eq!(pgn.comments, vec!["A short one.".to_string()]);
+
+    let sans: Vec<&str> = pgn.moves.iter().map(|m| m.san.as_str()).collect();
+    assert_eq!(sans, vec!["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7#"]);
+
+    assert_eq!(pgn.moves[0].evaluation, Some(30));
+    assert_eq!(pgn.moves[0].comments, vec!["[%clk 0:05:00]".to_string()]);
+    assert_eq!(pgn.moves[4].nags, vec![2]);
+    assert_eq!(pgn.moves[5].nags, vec![4]);
+    assert_eq!(pgn.moves[6].comments, vec!["mate".to_string()]);
+
+    // The variation replaces `Bc4`, and has one of its own replacing `Nc6`.
+    let variation = &pgn.moves[2].variations[0];
+    let variation_sans: Vec<&str> = variation.iter().map(|m| m.san.as_str()).collect();
+    assert_eq!(variation_sans, vec!["Nf3", "Nc6", "Bb5"]);
+    assert_eq!(variation[1].variations, vec![vec![PgnMove::new("d6")]]);
+
+    let (game, moves) = pgn.replay().expect("Main line should replay.");
+    assert_eq!(moves.len(), 7);
+    assert!(game.is_checkmate());
+    assert_eq!(game.game_result(), GameResult::WhiteWins);
+}
+
+#[test]
+fn test_write_pgn_round_trip() {
+    let pgn = PgnGame::parse(ANNOTATED_GAME).expect("PGN should parse.");
+    let written = pgn.to_string();
+
+    assert!(written.starts_with("[Event \"Casual game\"]\n"));
+    assert!(written.contains("[White \"Some \\\"Quoted\\\" Player\"]\n"));
+    let movetext = written.replace('\n', " ");
+    assert!(movetext.contains("1. e4 { [%eval 0.30] } { [%clk 0:05:00] } 1... e5 2. Bc4 (2. Nf3 Nc6 (2... d6) 3. Bb5) 2... Nc6"));
+    assert!(movetext.contains("3. Qh5 $2 Nf6 $4 4. Qxf7# { mate } 1-0"));
+    assert!(written.lines().all(|line| line.len() <= 80));
+
+    assert_eq!(PgnGame::parse(&written), Ok(pgn));
+}
+
+#[test]
+fn test_lenient_movetext() {
+    let pgn = PgnGame::parse("1.e4 e5 2.Nf3 Nc6 3.Bb5 a6 4.O-O!? Nf6 5.0-0-0")
+        .expect("PGN should parse.");
+    let sans: Vec<&str> = pgn.moves.iter().map(|m| m.san.as_str()).collect();
+    assert_eq!(
+        sans,
+        vec!["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "O-O", "Nf6", "0-0-0"]
+    );
+    assert_eq!(pgn.moves[6].nags, vec![5]);
+    assert_eq!(pgn.result, GameResult::Ongoing);
+    assert!(pgn.tags.is_empty());
+
+    // Only the first game is read.
+    let pgn = PgnGame::parse(
+        "[Result \"0-1\"]\n\n1. f3 e5 2. g4 Qh4# 0-1\n\n[Result \"*\"]\n\n1. d4 *\n",
+    )
+    .expect("PGN should parse.");
+    assert_eq!(pgn.moves.len(), 4);
+    assert_eq!(pgn.result, GameResult::BlackWins);
+}
+
+#[test]
+fn test_record_game() {
+    let mut game = ChessGame::new();
+    game.variant = Variant::Chess960;
+    let fen = "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9";
+    assert!(game.import_fen(fen).is_ok());
+    game.set_legal_moves(None);
+
+    let mut pgn = PgnGame::starting_from(&game);
+    assert_eq!(pgn.tag("Variant"), Some("Chess960"));
+    assert_eq!(pgn.tag("SetUp"), Some("1"));
+    assert_eq!(
+        pgn.tag("FEN"),
+        Some(format!("{} 0 1", game.export_fen()).as_str())
+    );
+
+    let mut played = vec![];
+    for (uci, evaluation) in [
+        ("e1f3", Some(-15)),
+        ("e6e5", None),
+        ("h2h3", Some(i64::MIN)),
+    ] {
+        let this_move = game.uci_to_legal_move(uci).expect("Move should be legal.");
+        pgn.push_move(&mut game, &this_move, evaluation);
+        game.make_move(&this_move, true);
+        played.push(this_move);
+    }
+    pgn.set_result(GameResult::Draw);
+
+    let written = pgn.to_string();
+    assert!(written.contains("[Result \"1/2-1/2\"]"));
+    assert!(written.contains("1. Nf3 { [%eval -0.15] } 1... e5 2. h3 { [%eval #-1] } 1/2-1/2"));
+
+    let (_, replayed) = PgnGame::parse(&written)
+        .unwrap()
+        .replay()
+        .expect("Recorded game should replay.");
+    assert_eq!(replayed, played);
+
+    // Nothing special about the usual start.
+    let mut game = ChessGame::new();
+    assert!(game.import_fen(INITIAL_GAME_STATE_FEN).is_ok());
+    let pgn = PgnGame::starting_from(&game);
+    assert_eq!(pgn.tag("FEN"), None);
+    assert_eq!(pgn.tag("Variant"), None);
+    assert_eq!(pgn.tag("Date"), Some("????.??.??"));
+}
+
+#[test]
+fn test_move_numbers_follow_fen() {
+    let pgn = PgnGame::parse("[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 20\"]\n\n20... Kd7 21. e4 *")
+        .expect("PGN should parse.");
+    assert!(pgn.to_string().contains("20... Kd7 21. e4 *"));
+    assert!(pgn.replay().is_ok());
+}
+
+#[test]
+fn test_pgn_errors() {
+    assert_eq!(PgnGame::parse("  \n"), Err(PgnError::Empty));
+    assert_eq!(
+        PgnGame::parse("[Event Casual]\n1. e4"),
+        Err(PgnError::InvalidTag("[Event Casual]".to_string()))
+    );
+    assert_eq!(
+        PgnGame::parse("1. e4 { never closed"),
+        Err(PgnError::UnterminatedComment)
+    );
+    assert_eq!(
+        PgnGame::parse("1. e4 (1. d4"),
+        Err(PgnError::UnbalancedVariation)
+    );
+    assert_eq!(
+        PgnGame::parse("1. e4 e5) 2. Nf3"),
+        Err(PgnError::UnbalancedVariation)
+    );
+    assert_eq!(
+        PgnGame::parse("(1. d4) 1. e4"),
+        Err(PgnError::VariationWithoutMove)
+    );
+
+    let pgn = PgnGame::parse("1. e4 e5 2. Ke3").unwrap();
+    assert_eq!(
+        pgn.replay().map(|_| ()),
+        Err(PgnError::IllegalMove {
+            ply: 2,
+            error: IllegalMoveError::NotLegal("Ke3".to_string())
+        })
+    );
+
+    let pgn = PgnGame::parse("[Variant \"Shogi\"]\n\n1. e4").unwrap();
+    assert_eq!(
+        pgn.replay().map(|_| ()),
+        Err(PgnError::UnsupportedVariant("Shogi".to_string()))
+    );
+}
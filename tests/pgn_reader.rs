@@ -0,0 +1,151 @@
+use chess_engine::pgn_error::PgnError;
+use chess_engine::pgn_read_error::PgnReadError;
+use chess_engine::pgn_reader::PgnReader;
+use std::io::Cursor;
+
+// Five games. The second never closes a comment, and the fourth has a broken tag.
+const DATABASE: &str = r#"[Event "First"]
+[Result "1-0"]
+
+1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0
+
+[Event "Second"]
+[Result "*"]
+
+1. d4 { this comment
+never ends
+[Event "Third"]
+[Result "0-1"]
+% an escaped line, ignored
+1. f3 e5 2. g4 { the long comment
+[%eval #-1]
+wraps } 2... Qh4# 0-1
+[Event "Fourth"
+[Result "*"]
+
+1. c4 *
+
+
+[Event "Fifth"]
+[Result "1/2-1/2"]
+
+1. Nf3 Nf6 1/2-1/2
+"#;
+
+#[test]
+fn test_read_games() {
+    let results: Vec<_> = PgnReader::new(Cursor::new(DATABASE)).collect();
+    assert_eq!(results.len(), 5);
+
+    let events: Vec<Option<String>> = results
+        .iter()
+        .map(|r| {
+            r.as_ref()
+                .ok()
+                .and_then(|game| game.tag("Event").map(|e| e.to_string()))
+        })
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            Some("First".to_string()),
+            None,
+            Some("Third".to_string()),
+            None,
+            Some("Fifth".to_string())
+        ]
+    );
+
+    match &results[1] {
+        Err(PgnReadError::Malformed { line, error }) => {
+            assert_eq!(*line, 6);
+            assert_eq!(*error, PgnError::UnterminatedComment);
+        }
+        other => panic!("Expected the second game to be skipped, got {other:?}."),
+    }
+    match &results[3] {
+        Err(PgnReadError::Malformed { line, error }) => {
+            assert_eq!(*line, 17);
+            assert!(matches!(error, PgnError::InvalidTag(_)));
+        }
+        other => panic!("Expected the fourth game to be skipped, got {other:?}."),
+    }
+
+    // Moves only get checked when asked.
+    let third = results[2].as_ref().unwrap();
+    assert_eq!(third.moves[2].evaluation, Some(i64::MIN));
+    let (game, moves) = third.replay().expect("Third game should replay.");
+    assert_eq!(moves.len(), 4);
+    assert!(game.is_checkmate());
+}
+
+#[test]
+fn test_read_headers() {
+    let headers: Vec<_> = PgnReader::new(Cursor::new(DATABASE)).headers().collect();
+    assert_eq!(headers.len(), 5);
+
+    // Broken movetext doesn't matter when we only want the tags.
+    let second = headers[1].as_ref().expect("Second game's tags are fine.");
+    assert_eq!(
+        *second,
+        vec![
+            ("Event".to_string(), "Second".to_string()),
+            ("Result".to_string(), "*".to_string())
+        ]
+    );
+    assert!(matches!(
+        headers[3],
+        Err(PgnReadError::Malformed { line: 17, .. })
+    ));
+    assert_eq!(headers[4].as_ref().unwrap()[0].1, "Fifth");
+}
+
+#[test]
+fn test_read_many_games() {
+    let game = "[Event \"Repeated\"]\n\n1. e4 e5 2. Nf3 Nc6 *\n\n";
+    let database = game.repeat(2_000);
+
+    let mut count = 0;
+    for result in PgnReader::new(Cursor::new(database.as_bytes())) {
+        let game = result.expect("Every game is well formed.");
+        assert_eq!(game.moves.len(), 4);
+        count += 1;
+    }
+    assert_eq!(count, 2_000);
+
+    // Games with no blank line in between, or no tags at all.
+    let squashed = "1. e4 *\n[Event \"B\"]\n1. d4 *\n[Event \"C\"]\n1. c4 *";
+    let games: Vec<_> = PgnReader::new(Cursor::new(squashed))
+        .filter_map(Result::ok)
+        .collect();
+    assert_eq!(games.len(), 3);
+    assert_eq!(games[2].moves[0].san, "c4");
+}
+
+#[test]
+fn test_read_invalid_utf8() {
+    // A Latin-1 `ü` in the middle game.
+    let mut database = b"[Event \"A\"]\n\n1. e4 *\n\n[White \"M".to_vec();
+    database.push(0xFC);
+    database.extend_from_slice(b"ller\"]\n\n1. d4 *\n\n[Event \"C\"]\n\n1. c4 *\n");
+
+    let games: Vec<_> = PgnReader::new(Cursor::new(database.clone()))
+        .map(|r| r.expect("Every game reads."))
+        .collect();
+    assert_eq!(games.len(), 3);
+    assert_eq!(games[1].tag("White"), Some("M\u{FFFD}ller"));
+    assert_eq!(games[1].moves[0].san, "d4");
+    assert_eq!(games[2].tag("Event"), Some("C"));
+
+    let headers: Vec<_> = PgnReader::new(Cursor::new(database)).headers().collect();
+    assert_eq!(headers.len(), 3);
+    assert!(headers.iter().all(|h| h.is_ok()));
+}
+
+#[test]
+fn test_open_missing_file() {
+    assert!(matches!(
+        PgnReader::open("this/file/does/not/exist.pgn"),
+        Err(PgnReadError::Io(_))
+    ));
+}
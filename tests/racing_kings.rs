@@ -0,0 +1,74 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::game_result::GameResult;
+use chess_engine::variant::Variant;
+
+const RACING_KINGS_START_FEN: &str = "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1";
+
+fn racing_kings_game() -> ChessGame {
+    let mut game = ChessGame::new();
+    game.variant = Variant::RacingKings;
+    return game;
+}
+
+#[test]
+fn test_racing_kings_perft() {
+    let mut game = racing_kings_game();
+
+    let positions: Vec<(&str, [u64; 3])> = vec![
+        (RACING_KINGS_START_FEN, [21, 421, 11_264]),
+        (
+            "8/8/8/2r5/1r2n3/8/4R2K/k1b2BR1 w - - 4 9",
+            [21, 720, 16_558],
+        ),
+        ("8/8/8/4n3/r7/7N/k5K1/8 b - - 4 13", [24, 240, 5_485]),
+        ("8/8/8/8/8/6q1/k1N4R/7K b - - 1 12", [21, 227, 4_432]),
+    ];
+
+    for (fen, expected) in positions.iter() {
+        assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+        for (depth, nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                game.perft(depth as u32 + 1),
+                *nodes,
+                "Perft {} of {fen}.",
+                depth + 1
+            );
+        }
+    }
+}
+
+#[test]
+fn test_no_checks() {
+    let mut game = racing_kings_game();
+    let _ = game.import_fen("8/8/8/k7/8/8/8/1R5K w - - 0 1");
+    game.set_legal_moves(None);
+
+    // Giving check is as illegal as walking into one.
+    assert!(game.uci_to_legal_move("b1a1").is_err());
+    assert!(game.uci_to_legal_move("b1c1").is_ok());
+    assert!(game.legal_moves.iter().all(|m| m.is_check == Some(false)));
+}
+
+#[test]
+fn test_race_to_the_eighth_rank() {
+    let mut game = racing_kings_game();
+
+    // Black got there first.
+    let _ = game.import_fen("k7/8/8/8/8/8/8/7K w - - 0 1");
+    game.set_legal_moves(None);
+    assert_eq!(game.game_result(), GameResult::BlackWins);
+    assert!(game.legal_moves.is_empty());
+
+    // White got there, but black can still follow and draw.
+    let _ = game.import_fen("7K/k7/8/8/8/8/8/8 b - - 0 1");
+    game.set_legal_moves(None);
+    assert_eq!(game.game_result(), GameResult::Ongoing);
+    let follow = game.uci_to_legal_move("a7a8").unwrap();
+    game.make_move(&follow, true);
+    assert_eq!(game.game_result(), GameResult::Draw);
+
+    // Black is too far away to catch up.
+    let _ = game.import_fen("7K/8/k7/8/8/8/8/8 b - - 0 1");
+    game.set_legal_moves(None);
+    assert_eq!(game.game_result(), GameResult::WhiteWins);
+}
@@ -0,0 +1,162 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::illegal_move_error::IllegalMoveError;
+use chess_engine::variant::Variant;
+
+fn game_at(variant: Variant, fen: &str) -> ChessGame {
+    let mut game = ChessGame::new();
+    game.variant = variant;
+    assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+    game.set_legal_moves(None);
+    return game;
+}
+
+fn san_of(game: &mut ChessGame, uci: &str) -> String {
+    let m = game.uci_to_legal_move(uci).expect("Move should be legal.");
+    return game.move_to_san(&m);
+}
+
+fn uci_of(game: &ChessGame, san: &str) -> String {
+    let m = game.parse_san(san).expect("SAN should parse.");
+    return game.move_to_uci(&m);
+}
+
+#[test]
+fn test_san_round_trip() {
+    let positions: Vec<(Variant, &str)> = vec![
+        (Variant::Standard, INITIAL_GAME_STATE_FEN),
+        (
+            Variant::Standard,
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        ),
+        (
+            Variant::Standard,
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        ),
+        (
+            Variant::Chess960,
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        ),
+        (
+            Variant::Crazyhouse,
+            "r1bqk2r/pppp1ppp/2n5/4p3/1b2P3/2N5/PPPP1PPP/R1BQKB~1R[NPn] w KQkq - 0 1",
+        ),
+    ];
+
+    for (variant, fen) in positions {
+        let mut game = game_at(variant, fen);
+        let moves = game.legal_moves.clone();
+        let mut sans: Vec<String> = vec![];
+        for m in moves.iter() {
+            let san = game.move_to_san(m);
+            let parsed = game.parse_san(&san);
+            assert_eq!(parsed, Ok(*m), "`{san}` didn't come back on {fen}.");
+            sans.push(san);
+        }
+
+        // Writing the moves didn't disturb the game.
+        assert_eq!(game.export_fen(), game_at(variant, fen).export_fen());
+        assert_eq!(game.legal_moves.len(), moves.len());
+
+        sans.sort();
+        sans.dedup();
+        assert_eq!(sans.len(), moves.len(), "Two moves share a SAN on {fen}.");
+    }
+}
+
+#[test]
+fn test_move_to_san() {
+    let mut game = game_at(Variant::Standard, INITIAL_GAME_STATE_FEN);
+    assert_eq!(san_of(&mut game, "e2e4"), "e4");
+    assert_eq!(san_of(&mut game, "g1f3"), "Nf3");
+
+    // Knights on b1 and f3 can both reach d2, rooks on a1 and a5 can both reach a3.
+    let mut game = game_at(Variant::Standard, "4k3/8/8/R7/8/5N2/8/RN2K3 w Q - 0 1");
+    assert_eq!(san_of(&mut game, "b1d2"), "Nbd2");
+    assert_eq!(san_of(&mut game, "a1a3"), "R1a3");
+
+    let mut game = game_at(Variant::Standard, "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+    assert_eq!(san_of(&mut game, "e1g1"), "O-O");
+    assert_eq!(san_of(&mut game, "e1c1"), "O-O-O");
+
+    // Three queens that need both file and rank.
+    let mut game = game_at(Variant::Standard, "1k6/8/8/8/4Q2Q/8/8/K6Q w - - 0 1");
+    assert_eq!(san_of(&mut game, "h4e1"), "Qh4e1");
+
+    // Captures, en passant, promotion with check.
+    let mut game = game_at(Variant::Standard, "3r2k1/4P3/8/3pP3/8/8/8/4K3 w - d6 0 1");
+    assert_eq!(san_of(&mut game, "e5d6"), "exd6");
+    assert_eq!(san_of(&mut game, "e7d8q"), "exd8=Q+");
+    assert_eq!(san_of(&mut game, "e7e8n"), "e8=N");
+
+    // Back rank mate.
+    let mut game = game_at(Variant::Standard, "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+    assert_eq!(san_of(&mut game, "a1a8"), "Ra8#");
+
+    // Crazyhouse drops.
+    let mut game = game_at(
+        Variant::Crazyhouse,
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[NP] w KQkq - 0 1",
+    );
+    assert_eq!(san_of(&mut game, "N@e4"), "N@e4");
+    assert_eq!(san_of(&mut game, "P@e4"), "@e4");
+}
+
+#[test]
+fn test_parse_san_is_lenient() {
+    let game = game_at(
+        Variant::Standard,
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    );
+    assert_eq!(uci_of(&game, "O-O"), "e1g1");
+    assert_eq!(uci_of(&game, "0-0-0"), "e1c1");
+    assert_eq!(uci_of(&game, "Qxf6"), "f3f6");
+    assert_eq!(uci_of(&game, "Qf6"), "f3f6");
+    assert_eq!(uci_of(&game, "Qxf6!?"), "f3f6");
+    assert_eq!(uci_of(&game, "dxe6"), "d5e6");
+    assert_eq!(uci_of(&game, "de6"), "d5e6");
+    assert_eq!(uci_of(&game, "Nxf7+"), "e5f7");
+    assert_eq!(uci_of(&game, "Ne5-f7"), "e5f7");
+    assert_eq!(uci_of(&game, "a2a4"), "a2a4");
+
+    let game = game_at(Variant::Standard, "3r2k1/4P3/8/8/8/8/8/4K3 w - - 0 1");
+    assert_eq!(uci_of(&game, "exd8Q+"), "e7d8q");
+    assert_eq!(uci_of(&game, "e8=N"), "e7e8n");
+}
+
+#[test]
+fn test_parse_san_king_move_is_not_castling() {
+    // The Chess960 king lands on g1 either way. `Kg1` is the king move, `O-O` is castling.
+    let mut game = game_at(Variant::Chess960, "4k3/8/8/8/8/8/8/5K1R w K - 0 1");
+    assert_eq!(uci_of(&game, "Kg1"), "f1g1");
+    assert_eq!(uci_of(&game, "O-O"), "f1h1");
+    assert_eq!(san_of(&mut game, "f1g1"), "Kg1");
+    assert_eq!(san_of(&mut game, "f1h1"), "O-O");
+
+    // In standard chess, castling isn't a two square king move.
+    let game = game_at(Variant::Standard, "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+    assert_eq!(
+        game.parse_san("Kg1"),
+        Err(IllegalMoveError::NotLegal("Kg1".to_string()))
+    );
+    assert_eq!(uci_of(&game, "O-O"), "e1g1");
+}
+
+#[test]
+fn test_parse_san_errors() {
+    let game = game_at(Variant::Standard, "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1");
+    assert_eq!(
+        game.parse_san("Nd2"),
+        Err(IllegalMoveError::Ambiguous("Nd2".to_string()))
+    );
+    assert!(game.parse_san("Nbd2").is_ok());
+    assert!(game.parse_san("Nf3d2").is_ok());
+
+    for san in ["Ke3x", "Qd2", "O-O", "e4", "Nz2", "", "N@d2"] {
+        assert_eq!(
+            game.parse_san(san),
+            Err(IllegalMoveError::NotLegal(san.to_string())),
+            "Parsed `{san}` when it should have failed."
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use chess_engine::sprt::{elo_to_score, score_to_elo, MatchScore, Sprt, SprtVerdict};
+
+fn score(wins: u64, draws: u64, losses: u64) -> MatchScore {
+    return MatchScore {
+        wins,
+        draws,
+        losses,
+    };
+}
+
+#[test]
+fn test_elo() {
+    assert_eq!(elo_to_score(0.0), 0.5);
+    assert!((score_to_elo(elo_to_score(100.0)) - 100.0).abs() < 1e-9);
+    assert!((score_to_elo(0.75) - 190.85).abs() < 0.01);
+
+    let even = score(40, 20, 40);
+    assert_eq!(even.games(), 100);
+    assert_eq!(even.score(), 0.5);
+    assert!(even.elo().abs() < 1e-9);
+
+    let ahead = score(60, 20, 20);
+    assert_eq!(ahead.score(), 0.7);
+    assert!((ahead.elo() - 147.19).abs() < 0.01);
+
+    // More games, tighter error bars.
+    let error = ahead.elo_error();
+    assert!(error > 0.0 && error < ahead.elo());
+    assert!(score(600, 200, 200).elo_error() < error);
+
+    // Nothing to go on yet.
+    assert!(score(0, 0, 0).elo_error().is_infinite());
+    assert!(score(3, 0, 0).elo_error().is_infinite());
+}
+
+#[test]
+fn test_sprt() {
+    let sprt = Sprt::default();
+    assert!((sprt.lower_bound() + 2.944).abs() < 0.001);
+    assert!((sprt.upper_bound() - 2.944).abs() < 0.001);
+
+    assert_eq!(sprt.llr(&score(0, 0, 0)), 0.0);
+    assert_eq!(sprt.verdict(&score(10, 10, 10)), SprtVerdict::Continue);
+
+    // Clearly stronger, and clearly weaker.
+    assert_eq!(sprt.verdict(&score(600, 300, 400)), SprtVerdict::AcceptH1);
+    assert_eq!(sprt.verdict(&score(400, 300, 600)), SprtVerdict::AcceptH0);
+
+    // Dead even is closer to elo0 than elo1, so the LLR drifts down over time.
+    assert!(sprt.llr(&score(400, 200, 400)) < 0.0);
+    assert!(sprt.llr(&score(4_000, 2_000, 4_000)) < sprt.llr(&score(400, 200, 400)));
+}
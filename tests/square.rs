@@ -0,0 +1,47 @@
+use chess_engine::file::File;
+use chess_engine::rank::Rank;
+use chess_engine::square::Square;
+
+#[test]
+fn test_square_numbering() {
+    // Same layout as the bitboards, a8 first.
+    assert_eq!("a8".parse::<Square>(), Ok(Square::new(0)));
+    assert_eq!("h8".parse::<Square>(), Ok(Square::new(7)));
+    assert_eq!("e2".parse::<Square>(), Ok(Square::new(52)));
+    assert_eq!("h1".parse::<Square>(), Ok(Square::new(63)));
+
+    let e4: Square = "e4".parse().unwrap();
+    assert_eq!(e4.file(), File::from_char('e').unwrap());
+    assert_eq!(e4.rank(), Rank::from_char('4').unwrap());
+    assert_eq!(
+        Square::from_file_rank(File::A, Rank::FIRST),
+        Square::new(56)
+    );
+    assert_eq!(
+        Square::from_file_rank(File::H, Rank::EIGHTH),
+        Square::new(7)
+    );
+}
+
+#[test]
+fn test_square_round_trip() {
+    for index in 0..64 {
+        let square = Square::new(index);
+        assert_eq!(square.to_string().parse::<Square>(), Ok(square));
+        assert_eq!(Square::from_file_rank(square.file(), square.rank()), square);
+        assert_eq!(usize::from(square), index);
+    }
+}
+
+#[test]
+fn test_invalid_squares() {
+    for s in ["", "e", "e44", "i1", "a0", "a9", "77", "Z1"] {
+        assert!(
+            s.parse::<Square>().is_err(),
+            "Parsed {s} when it should have failed."
+        );
+    }
+    assert!(Square::try_new(64).is_none());
+    assert!(File::new(8).is_none());
+    assert!(Rank::new(8).is_none());
+}
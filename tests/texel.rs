@@ -0,0 +1,114 @@
+use chess_engine::evaluation_parameters::EvaluationParameters;
+use chess_engine::texel::{parse_labelled_position, sigmoid, LabelledPosition, TexelTuner};
+
+#[test]
+fn test_parse_labelled_position() {
+    let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+
+    let bracketed = parse_labelled_position(&format!("{fen} [0.5]")).expect("Should parse.");
+    assert_eq!(bracketed.fen, fen);
+    assert_eq!(bracketed.result, 0.5);
+
+    let pgn_style = parse_labelled_position(&format!("{fen} 1-0")).expect("Should parse.");
+    assert_eq!(pgn_style.result, 1.0);
+
+    let epd_style =
+        parse_labelled_position("8/8/8/8/8/8/8/K1k5 w - - c9 \"0-1\";").expect("Should parse.");
+    assert_eq!(epd_style.fen, "8/8/8/8/8/8/8/K1k5 w - -");
+    assert_eq!(epd_style.result, 0.0);
+
+    assert!(parse_labelled_position(fen).is_err());
+    assert!(parse_labelled_position(&format!("{fen} [1.5]")).is_err());
+    assert!(parse_labelled_position("[1.0]").is_err());
+}
+
+#[test]
+fn test_sigmoid() {
+    assert_eq!(sigmoid(0.0, 1.0), 0.5);
+    assert!(sigmoid(400.0, 1.0) > 0.9);
+    assert!(sigmoid(-400.0, 1.0) < 0.1);
+}
+
+#[test]
+fn test_tuning_reduces_error() {
+    // White is a knight up in every position, and always wins. The tuner should value knights more.
+    // Pawns on both sides keep these out of the endgame rules, and a knight is not enough to start mop-up.
+    let positions: Vec<LabelledPosition> = vec![
+        "4k3/4p3/8/8/8/8/4P3/1N2K3 w - - 0 1",
+        "4k3/3p4/8/8/8/5N2/3P4/4K3 b - - 0 1",
+        "4k3/5p2/8/3N4/8/8/5P2/4K3 w - - 0 1",
+        "4k3/6p1/2N5/8/8/8/6P1/4K3 b - - 0 1",
+    ]
+    .iter()
+    .map(|fen| LabelledPosition {
+        fen: fen.to_string(),
+        result: 1.0,
+    })
+    .collect();
+
+    let parameters = EvaluationParameters::default();
+    let mut tuner = TexelTuner::new(&positions, &parameters).expect("Positions should load.");
+    assert_eq!(tuner.len(), 4);
+    assert_eq!(tuner.skipped, 0);
+
+    // Every result is a white win, so the best K would be as large as possible. Pin it instead.
+    let starting_values = parameters.to_vec();
+    let k = tuner.find_best_k(&starting_values);
+    assert!(k > 0.0 && k <= 10.0);
+    tuner.k = 1.0;
+    let k = tuner.k;
+    let starting_error = tuner.mean_squared_error(&starting_values, k);
+
+    let tuned = tuner.tune(&parameters, 5, false);
+    let tuned_error = tuner.mean_squared_error(&tuned.to_vec(), k);
+
+    assert!(tuned_error < starting_error);
+    assert!(tuned.knight_value > parameters.knight_value);
+    assert_eq!(tuned.queen_value, parameters.queen_value);
+}
+
+#[test]
+fn test_tuner_skips_known_endings() {
+    let positions: Vec<LabelledPosition> = vec![
+        // KNK, a draw whatever the knight is worth.
+        "4k3/8/8/8/8/8/8/1N2K3 w - - 0 1",
+        // KQK, scored by the mating pattern.
+        "4k3/8/8/8/8/8/8/3QK3 w - - 0 1",
+        // A rook and pawns up, so mop-up is on.
+        "4k3/pp6/8/8/8/8/PP6/R3K3 w - - 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+    ]
+    .iter()
+    .map(|fen| LabelledPosition {
+        fen: fen.to_string(),
+        result: 0.5,
+    })
+    .collect();
+
+    let tuner = TexelTuner::new(&positions, &EvaluationParameters::default())
+        .expect("Positions should load.");
+    assert_eq!(tuner.len(), 1);
+    assert_eq!(tuner.skipped, 3);
+}
+
+#[test]
+fn test_tuning_counts_pockets() {
+    // Level on the board, but white has a knight in hand and always wins.
+    let positions: Vec<LabelledPosition> = vec![
+        "r1bqkbnr/pppppppp/8/8/8/8/PPPPPPPP/R1BQKBNR[N] w KQkq - 0 1",
+        "r1bqkbnr/pppppppp/8/8/8/8/PPPPPPPP/R1BQKBNR[N] b KQkq - 0 1",
+    ]
+    .iter()
+    .map(|fen| LabelledPosition {
+        fen: fen.to_string(),
+        result: 1.0,
+    })
+    .collect();
+
+    let parameters = EvaluationParameters::default();
+    let tuner = TexelTuner::new(&positions, &parameters).expect("Positions should load.");
+    assert_eq!(tuner.len(), 2);
+
+    let tuned = tuner.tune(&parameters, 5, false);
+    assert!(tuned.knight_value > parameters.knight_value);
+}
@@ -0,0 +1,102 @@
+use chess_engine::chess_game::ChessGame;
+use chess_engine::color::Color;
+use chess_engine::game_result::GameResult;
+use chess_engine::variant::Variant;
+
+fn three_check_game() -> ChessGame {
+    let mut game = ChessGame::new();
+    game.variant = Variant::ThreeCheck;
+    return game;
+}
+
+#[test]
+fn test_three_check_perft() {
+    let mut game = three_check_game();
+
+    let positions: Vec<(&str, [u64; 3])> = vec![
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+3 0 1",
+            [20, 400, 8_902],
+        ),
+        (
+            "r5k1/p4r1n/1n2q1p1/3p4/8/1P5P/P1P2PK1/RN5R b - - 1+2 1 16",
+            [45, 867, 38_885],
+        ),
+        // Black's next check wins, so most of white's replies never happen.
+        (
+            "8/Q1p5/B5k1/1p2p3/1P6/8/P1PP1K2/RNB5 w - - 2+1 0 19",
+            [29, 307, 9_809],
+        ),
+        ("4k3/8/8/3K4/4P3/8/6P1/8 b - - 1+2 6 18", [5, 44, 248]),
+    ];
+
+    for (fen, expected) in positions.iter() {
+        assert!(game.import_fen(fen).is_ok(), "Failed to import FEN {fen}.");
+        for (depth, nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                game.perft(depth as u32 + 1),
+                *nodes,
+                "Perft {} of {fen}.",
+                depth + 1
+            );
+        }
+    }
+}
+
+#[test]
+fn test_check_counts_in_fen_and_hash() {
+    let mut game = three_check_game();
+
+    // Checks still needed, or checks given at the end. Both describe the same position.
+    let _ = game.import_fen("4k3/8/8/8/8/8/8/4K2R w K - 2+3 0 1");
+    assert_eq!(game.checks_given(Color::White), 1);
+    assert_eq!(game.checks_given(Color::Black), 0);
+    assert_eq!(game.export_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 2+3");
+    let hash = game.zobrist_hash;
+
+    let _ = game.import_fen("4k3/8/8/8/8/8/8/4K2R w K - 0 1 +1+0");
+    assert_eq!(game.checks_given(Color::White), 1);
+    assert_eq!(game.checks_given(Color::Black), 0);
+    assert_eq!(game.zobrist_hash, hash);
+
+    // Same board, different counts, different hash.
+    let _ = game.import_fen("4k3/8/8/8/8/8/8/4K2R w K - 3+3 0 1");
+    assert_ne!(game.zobrist_hash, hash);
+
+    assert!(game
+        .import_fen("4k3/8/8/8/8/8/8/4K2R w K - 4+3 0 1")
+        .is_err());
+    assert!(game
+        .import_fen("4k3/8/8/8/8/8/8/4K2R w K - 3+x 0 1")
+        .is_err());
+}
+
+#[test]
+fn test_third_check_wins() {
+    let mut game = three_check_game();
+    let fen = "4k3/8/8/8/8/8/8/4K2R w K - 1+3 0 1";
+    let _ = game.import_fen(fen);
+    game.set_legal_moves(None);
+    let hash = game.zobrist_hash;
+
+    let check = game.uci_to_legal_move("h1h8").unwrap();
+    assert_eq!(check.is_check, Some(true));
+    game.make_move(&check, true);
+    assert_eq!(game.checks_given(Color::White), 3);
+    assert_eq!(game.checks_given(Color::Black), 0);
+    assert!(game.legal_moves.is_empty());
+    assert_eq!(game.game_result(), GameResult::WhiteWins);
+    assert!(!game.is_checkmate());
+    assert!(!game.is_stalemate());
+
+    game.unmake_move(&check);
+    assert_eq!(game.checks_given(Color::White), 2);
+    assert_eq!(game.checks_given(Color::Black), 0);
+    assert_eq!(game.zobrist_hash, hash);
+
+    // The search goes for it.
+    game.set_legal_moves(None);
+    let (score, best_move) = game.minimax(1, std::i64::MIN, std::i64::MAX);
+    assert_eq!(score, std::i64::MAX);
+    assert_eq!(best_move.unwrap().move_to_str(), "h1h8");
+}
@@ -0,0 +1,172 @@
+use chess_engine::constants::INITIAL_GAME_STATE_FEN;
+use chess_engine::engine_match::{Adjudication, MatchOptions, Termination, TimeControl};
+use chess_engine::game_result::GameResult;
+use chess_engine::tournament::{play_game, round_robin, Crosstable, Pairing};
+use chess_engine::uci_engine::{parse_score, UciEngine};
+use chess_engine::uci_error::UciError;
+use chess_engine::variant::Variant;
+
+// Our own engine over UCI, built alongside the tests.
+const UCI_ENGINE: &str = env!("CARGO_BIN_EXE_uci");
+
+#[test]
+fn test_parse_score() {
+    assert_eq!(
+        parse_score("info depth 12 score cp -35 nodes 1000 pv e7e5"),
+        Some(-35)
+    );
+    assert_eq!(
+        parse_score("info depth 20 score cp 12 lowerbound"),
+        Some(12)
+    );
+    assert_eq!(parse_score("info score mate 3 pv a1a8"), Some(i64::MAX));
+    assert_eq!(parse_score("info score mate -2"), Some(i64::MIN));
+    assert_eq!(parse_score("info depth 3 nodes 200"), None);
+    assert_eq!(parse_score("info string score cp"), None);
+}
+
+#[test]
+fn test_round_robin() {
+    let schedule = round_robin(3, 2, 4);
+    assert_eq!(schedule.len(), 12);
+
+    // First game of every pairing before any second game.
+    assert_eq!(
+        schedule[..3],
+        [
+            Pairing {
+                white: 0,
+                black: 1,
+                opening: 0
+            },
+            Pairing {
+                white: 0,
+                black: 2,
+                opening: 0
+            },
+            Pairing {
+                white: 1,
+                black: 2,
+                opening: 0
+            },
+        ]
+    );
+
+    // Colors swap on the same opening, then it's on to the next one.
+    assert_eq!(
+        schedule[3],
+        Pairing {
+            white: 1,
+            black: 0,
+            opening: 0
+        }
+    );
+    assert_eq!(schedule[6].opening, 1);
+
+    // Every engine plays everyone else twice as white.
+    for player in 0..3 {
+        assert_eq!(schedule.iter().filter(|p| p.white == player).count(), 4);
+    }
+}
+
+#[test]
+fn test_crosstable() {
+    let names = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+    let mut crosstable = Crosstable::new(&names);
+    crosstable.add_game(0, 1, GameResult::WhiteWins);
+    crosstable.add_game(1, 0, GameResult::Draw);
+    crosstable.add_game(2, 0, GameResult::WhiteWins);
+    crosstable.add_game(1, 2, GameResult::BlackWins);
+
+    assert_eq!(crosstable.scores[0][1].wins, 1);
+    assert_eq!(crosstable.scores[0][1].draws, 1);
+    assert_eq!(crosstable.scores[1][0].losses, 1);
+    assert_eq!(crosstable.total(2).wins, 2);
+    assert_eq!(crosstable.total(0).games(), 3);
+    assert_eq!(crosstable.standings(), vec![2, 0, 1]);
+
+    let table = crosstable.to_string();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[1].starts_with(" 1. C"));
+    assert!(lines[1].contains("2.0      2"));
+    assert!(lines[3].starts_with(" 3. B"));
+    assert!(lines[3].trim_end().ends_with("0.0   0.5     -"));
+}
+
+#[test]
+fn test_uci_engine() {
+    let mut engine = UciEngine::start(UCI_ENGINE, &[]).expect("Engine starts.");
+    assert_eq!(engine.name, "chess_engine");
+    engine
+        .new_game(Variant::Standard)
+        .expect("Engine is ready.");
+
+    let answer = engine
+        .go(
+            INITIAL_GAME_STATE_FEN,
+            &["e2e4".to_string()],
+            [1_000, 1_000],
+            0,
+            10_000,
+        )
+        .expect("Engine answers.");
+    assert!(answer.score.is_some());
+
+    let mut game = chess_engine::chess_game::ChessGame::new();
+    game.import_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq -")
+        .expect("Valid FEN.");
+    game.set_legal_moves(None);
+    assert!(game.uci_to_legal_move(&answer.best_move).is_ok());
+
+    assert!(matches!(
+        UciEngine::start("./no-such-engine", &[]),
+        Err(UciError::Spawn { .. })
+    ));
+}
+
+#[test]
+fn test_play_game() {
+    let mut white = UciEngine::start(UCI_ENGINE, &[]).expect("Engine starts.");
+    let mut black = UciEngine::start(UCI_ENGINE, &[]).expect("Engine starts.");
+    white.name = "White engine".to_string();
+    let options = MatchOptions {
+        variant: Variant::Standard,
+        time_control: TimeControl {
+            base_ms: 10_000,
+            increment_ms: 0,
+        },
+        adjudication: Adjudication::default(),
+    };
+
+    // Mate in one.
+    let game = play_game(
+        &mut white,
+        &mut black,
+        "6k1/5ppp/8/8/8/8/8/R5K1 w - -",
+        &options,
+    )
+    .expect("Opening is valid.");
+    assert_eq!(game.result, GameResult::WhiteWins);
+    assert_eq!(game.termination, Termination::OnTheBoard);
+    assert_eq!(game.pgn.moves.len(), 1);
+    assert_eq!(game.pgn.moves[0].san, "Ra8#");
+    assert_eq!(game.pgn.moves[0].evaluation, Some(30_000));
+    assert_eq!(game.pgn.tag("White"), Some("White engine"));
+    assert_eq!(game.pgn.tag("Black"), Some("chess_engine"));
+    assert_eq!(game.pgn.tag("Event"), Some("Tournament"));
+
+    // The same engines play on, and black gets mated too.
+    let game = play_game(
+        &mut white,
+        &mut black,
+        "r5k1/8/8/8/8/8/5PPP/6K1 b - -",
+        &options,
+    )
+    .expect("Opening is valid.");
+    assert_eq!(game.result, GameResult::BlackWins);
+    assert_eq!(game.pgn.moves[0].san, "Ra1#");
+    assert_eq!(game.pgn.moves[0].evaluation, Some(-30_000));
+
+    assert!(play_game(&mut white, &mut black, "not a fen", &options).is_err());
+}